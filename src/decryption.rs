@@ -0,0 +1,555 @@
+use crate::encryption::get_key_length;
+use crate::object_offsets::{get_stream_data_range, scan_object_headers};
+use crate::object_stream_dump::load_object_stream_dump;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+
+/// Padding added to passwords of the standard security handler, to make them 32 bytes long.
+///
+/// See 7.6.3.3 (Encryption Key Algorithm) in PDF v1.7 Spec.
+static PASSWORD_PADDING: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08,
+    0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE, 0x64, 0x53, 0x69, 0x7A,
+];
+
+#[derive(Debug, Clone)]
+pub enum DecryptionError {
+    /// The document uses an encryption that can not be decrypted, with a description of it.
+    Unsupported(String),
+    /// The password is neither the user nor the owner password.
+    WrongPassword,
+}
+
+impl std::fmt::Display for DecryptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecryptionError::Unsupported(encryption) => {
+                write!(f, "{} is not supported", encryption)
+            }
+            DecryptionError::WrongPassword => write!(f, "the password is not correct"),
+        }
+    }
+}
+
+/// The values of the `Encrypt` dictionary used by the standard security handler.
+struct StandardSecurityHandler {
+    /// The revision (`R`).
+    revision: i64,
+    /// Length of the encryption key in bytes.
+    key_length: usize,
+    /// `O`, made from the owner and user password.
+    owner_hash: Vec<u8>,
+    /// `U`, made from the user password.
+    user_hash: Vec<u8>,
+    /// `P`
+    permission_flags: i32,
+    /// The first string of the `ID` in the trailer.
+    file_id: Vec<u8>,
+    /// When `false`, metadata streams are not encrypted.
+    encrypt_metadata: bool,
+}
+
+/// Decrypt the strings and streams of a document encrypted by the standard security handler.
+///
+/// `password` can be the user or the owner password, without it the empty user password
+/// is tried. Only RC4 encryption can be decrypted (`V` 1 and 2, or 4 with `V2` crypt filters).
+///
+/// lopdf can not read the objects in encrypted object streams while loading, so these are
+/// added after reading the streams again from `file_bytes` and decrypting them.
+///
+/// See 7.6 (Encryption) in PDF v1.7 Spec.
+pub fn decrypt_document(
+    raw_doc: &mut Document,
+    file_bytes: &[u8],
+    password: Option<&str>,
+) -> Result<(), DecryptionError> {
+    let handler = get_standard_security_handler(raw_doc)?;
+    let password = password.unwrap_or_default().as_bytes();
+    let key = handler
+        .get_user_key(password)
+        .or_else(|| handler.get_owner_key(password))
+        .ok_or(DecryptionError::WrongPassword)?;
+
+    let encrypt_id = raw_doc
+        .trailer
+        .get(b"Encrypt")
+        .and_then(Object::as_reference)
+        .ok();
+    for (object_id, obj) in raw_doc.objects.iter_mut() {
+        // The encryption dictionary itself is not encrypted.
+        if Some(*object_id) == encrypt_id {
+            continue;
+        }
+        let object_key = get_object_key(&key, *object_id);
+        decrypt_object(&object_key, obj, handler.encrypt_metadata);
+    }
+    reload_object_streams(raw_doc, file_bytes, &key);
+    load_object_stream_objects(raw_doc);
+    Ok(())
+}
+
+fn get_standard_security_handler(
+    raw_doc: &Document,
+) -> Result<StandardSecurityHandler, DecryptionError> {
+    let encrypt = raw_doc
+        .trailer
+        .get(b"Encrypt")
+        .and_then(|encrypt| raw_doc.dereference(encrypt))
+        .and_then(|(_, encrypt)| encrypt.as_dict())
+        .map_err(|_| {
+            DecryptionError::Unsupported("`Encrypt` that is not a dictionary".to_owned())
+        })?;
+    let filter = encrypt
+        .get(b"Filter")
+        .and_then(Object::as_name_str)
+        .unwrap_or_default();
+    if filter != "Standard" {
+        return Err(DecryptionError::Unsupported(format!(
+            "Security handler `{}`",
+            filter
+        )));
+    }
+    let get_i64 = |key: &[u8]| encrypt.get_deref(key, raw_doc).and_then(Object::as_i64);
+    let get_bytes = |key: &[u8]| {
+        encrypt
+            .get_deref(key, raw_doc)
+            .and_then(Object::as_str)
+            .map(|bytes| bytes.to_vec())
+            .map_err(|_| {
+                DecryptionError::Unsupported(format!(
+                    "`Encrypt` without `{}`",
+                    String::from_utf8_lossy(key)
+                ))
+            })
+    };
+    let version = get_i64(b"V").ok();
+    match version {
+        Some(1) | Some(2) => {}
+        Some(4) if get_crypt_filter_method(raw_doc, encrypt) == "V2" => {}
+        Some(4) => return Err(DecryptionError::Unsupported("AES encryption".to_owned())),
+        Some(5) => {
+            return Err(DecryptionError::Unsupported(
+                "AES-256 encryption".to_owned(),
+            ))
+        }
+        _ => {
+            return Err(DecryptionError::Unsupported(format!(
+                "Encryption version (`V`) {}",
+                version.map_or("-".to_owned(), |version| version.to_string())
+            )))
+        }
+    }
+    let revision = get_i64(b"R").unwrap_or(2);
+    let key_length = if revision == 2 {
+        5
+    } else {
+        (get_key_length(raw_doc, encrypt, version) / 8).clamp(5, 16) as usize
+    };
+    let file_id = raw_doc
+        .trailer
+        .get_deref(b"ID", raw_doc)
+        .and_then(Object::as_array)
+        .ok()
+        .and_then(|id| id.first())
+        .and_then(|first_id| first_id.as_str().ok())
+        .map(|first_id| first_id.to_vec())
+        .unwrap_or_default();
+    Ok(StandardSecurityHandler {
+        revision,
+        key_length,
+        owner_hash: get_bytes(b"O")?,
+        user_hash: get_bytes(b"U")?,
+        permission_flags: get_i64(b"P").unwrap_or(-1) as i32,
+        file_id,
+        encrypt_metadata: encrypt
+            .get(b"EncryptMetadata")
+            .and_then(Object::as_bool)
+            .unwrap_or(true),
+    })
+}
+
+/// The `CFM` of the default crypt filter for streams, like `V2` (RC4) or `AESV2`.
+fn get_crypt_filter_method(raw_doc: &Document, encrypt: &Dictionary) -> String {
+    let filter_name = encrypt
+        .get(b"StmF")
+        .and_then(Object::as_name_str)
+        .unwrap_or("Identity");
+    encrypt
+        .get_deref(b"CF", raw_doc)
+        .and_then(Object::as_dict)
+        .and_then(|crypt_filters| crypt_filters.get_deref(filter_name.as_bytes(), raw_doc))
+        .and_then(Object::as_dict)
+        .and_then(|crypt_filter| crypt_filter.get(b"CFM"))
+        .and_then(Object::as_name_str)
+        .unwrap_or("None")
+        .to_owned()
+}
+
+impl StandardSecurityHandler {
+    /// The encryption key made from a user password.
+    ///
+    /// See 7.6.3.3 (Algorithm 2) in PDF v1.7 Spec.
+    fn get_encryption_key(&self, password: &[u8]) -> Vec<u8> {
+        let mut input = pad_password(password).to_vec();
+        input.extend_from_slice(&self.owner_hash[..self.owner_hash.len().min(32)]);
+        input.extend_from_slice(&self.permission_flags.to_le_bytes());
+        input.extend_from_slice(&self.file_id);
+        if self.revision >= 4 && !self.encrypt_metadata {
+            input.extend_from_slice(&[0xFF; 4]);
+        }
+        let mut hash = md5::compute(&input).0;
+        if self.revision >= 3 {
+            for _ in 0..50 {
+                hash = md5::compute(&hash[..self.key_length]).0;
+            }
+        }
+        hash[..self.key_length].to_vec()
+    }
+
+    /// Get the encryption key when `password` is the user password.
+    ///
+    /// See 7.6.3.4 (Algorithm 6) in PDF v1.7 Spec.
+    fn get_user_key(&self, password: &[u8]) -> Option<Vec<u8>> {
+        let key = self.get_encryption_key(password);
+        let is_user_password = if self.revision == 2 {
+            rc4(&key, &PASSWORD_PADDING) == self.user_hash
+        } else {
+            let mut input = PASSWORD_PADDING.to_vec();
+            input.extend_from_slice(&self.file_id);
+            let mut hash = rc4(&key, &md5::compute(&input).0);
+            for round in 1..=19 {
+                hash = rc4(&xor_key(&key, round), &hash);
+            }
+            // Only the first 16 bytes are compared, the rest is arbitrary padding.
+            self.user_hash.get(..16) == Some(hash.as_slice())
+        };
+        is_user_password.then_some(key)
+    }
+
+    /// Get the encryption key when `password` is the owner password.
+    ///
+    /// The user password is decrypted from `O` with the owner password.
+    /// See 7.6.3.4 (Algorithm 7) in PDF v1.7 Spec.
+    fn get_owner_key(&self, password: &[u8]) -> Option<Vec<u8>> {
+        let mut hash = md5::compute(pad_password(password)).0;
+        if self.revision >= 3 {
+            for _ in 0..50 {
+                hash = md5::compute(&hash[..self.key_length]).0;
+            }
+        }
+        let owner_key = &hash[..self.key_length];
+        let user_password = if self.revision == 2 {
+            rc4(owner_key, &self.owner_hash)
+        } else {
+            let mut user_password = self.owner_hash.clone();
+            for round in (0..=19).rev() {
+                user_password = rc4(&xor_key(owner_key, round), &user_password);
+            }
+            user_password
+        };
+        self.get_user_key(&user_password)
+    }
+}
+
+fn pad_password(password: &[u8]) -> [u8; 32] {
+    let mut padded = PASSWORD_PADDING;
+    let length = password.len().min(32);
+    padded[..length].copy_from_slice(&password[..length]);
+    padded[length..].copy_from_slice(&PASSWORD_PADDING[..32 - length]);
+    padded
+}
+
+fn xor_key(key: &[u8], value: u8) -> Vec<u8> {
+    key.iter().map(|byte| byte ^ value).collect()
+}
+
+/// The key for the strings and streams of one object.
+///
+/// See 7.6.2 (Algorithm 1) in PDF v1.7 Spec.
+fn get_object_key(key: &[u8], object_id: ObjectId) -> Vec<u8> {
+    let mut input = key.to_vec();
+    input.extend_from_slice(&object_id.0.to_le_bytes()[..3]);
+    input.extend_from_slice(&object_id.1.to_le_bytes());
+    let hash = md5::compute(&input).0;
+    hash[..(key.len() + 5).min(16)].to_vec()
+}
+
+fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut state: Vec<u8> = (0..=255).collect();
+    let mut j: u8 = 0;
+    for i in 0..256 {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, j as usize);
+    }
+    let (mut i, mut j) = (0u8, 0u8);
+    data.iter()
+        .map(|byte| {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(state[i as usize]);
+            state.swap(i as usize, j as usize);
+            byte ^ state[state[i as usize].wrapping_add(state[j as usize]) as usize]
+        })
+        .collect()
+}
+
+fn decrypt_object(object_key: &[u8], obj: &mut Object, encrypt_metadata: bool) {
+    match obj {
+        Object::String(value, _) => *value = rc4(object_key, value),
+        Object::Array(list) => {
+            for item in list {
+                decrypt_object(object_key, item, encrypt_metadata);
+            }
+        }
+        Object::Dictionary(dict) => {
+            for (_, value) in dict.iter_mut() {
+                decrypt_object(object_key, value, encrypt_metadata);
+            }
+        }
+        // Cross-reference streams are not encrypted.
+        Object::Stream(stream) if stream.dict.type_is(b"XRef") => {}
+        Object::Stream(stream) => {
+            for (_, value) in stream.dict.iter_mut() {
+                decrypt_object(object_key, value, encrypt_metadata);
+            }
+            if encrypt_metadata || !stream.dict.type_is(b"Metadata") {
+                stream.content = rc4(object_key, &stream.content);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Read the object streams from the file again and decrypt them.
+///
+/// lopdf tries to decompress the encrypted data while loading, after which the object
+/// stream is dropped or left empty.
+fn reload_object_streams(raw_doc: &mut Document, file_bytes: &[u8], key: &[u8]) {
+    for (object_id, offset) in scan_object_headers(file_bytes) {
+        match raw_doc.objects.get(&object_id) {
+            None => {}
+            Some(Object::Stream(stream)) if stream.dict.type_is(b"ObjStm") => {}
+            Some(_) => continue,
+        }
+        let (stream_start, data_range) = match get_stream_data_range(file_bytes, offset) {
+            Some(range) => range,
+            None => continue,
+        };
+        let dict = match load_dictionary(&file_bytes[offset..stream_start], object_id) {
+            Some(dict) if dict.type_is(b"ObjStm") => dict,
+            _ => continue,
+        };
+        let length = dict
+            .get_deref(b"Length", raw_doc)
+            .and_then(Object::as_i64)
+            .ok()
+            .and_then(|length| usize::try_from(length).ok())
+            .filter(|length| *length <= data_range.len())
+            .unwrap_or(data_range.len());
+        let mut stream = Object::Stream(Stream::new(
+            dict,
+            file_bytes[data_range.start..data_range.start + length].to_vec(),
+        ));
+        decrypt_object(&get_object_key(key, object_id), &mut stream, true);
+        raw_doc.objects.insert(object_id, stream);
+    }
+}
+
+/// Add the objects in the (decrypted) object streams to the document.
+fn load_object_stream_objects(raw_doc: &mut Document) {
+    let object_streams: Vec<(ObjectId, Stream)> = raw_doc
+        .objects
+        .iter()
+        .filter_map(|(object_id, obj)| match obj {
+            Object::Stream(stream) if stream.dict.type_is(b"ObjStm") => {
+                Some((*object_id, stream.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    for (object_id, stream) in object_streams {
+        let first = stream
+            .dict
+            .get_deref(b"First", raw_doc)
+            .and_then(Object::as_i64)
+            .ok()
+            .and_then(|first| usize::try_from(first).ok());
+        let content = if stream.dict.has(b"Filter") {
+            stream.decompressed_content()
+        } else {
+            Ok(stream.content)
+        };
+        match content.and_then(|content| load_object_stream_dump(&content, first)) {
+            Ok((stream_doc, _)) => {
+                for (stream_object_id, obj) in stream_doc.objects {
+                    // Objects stored directly in the file replace older versions.
+                    raw_doc.objects.entry(stream_object_id).or_insert(obj);
+                }
+            }
+            Err(err) => log::warn!(
+                "Object stream ({},{}) could not be read after decrypting it: {}",
+                object_id.0,
+                object_id.1,
+                err
+            ),
+        }
+    }
+}
+
+/// Load the dictionary of an object from its bytes up to the `stream` keyword.
+///
+/// lopdf can only parse objects while loading a document, so it is wrapped in a minimal PDF.
+fn load_dictionary(object_bytes: &[u8], object_id: ObjectId) -> Option<Dictionary> {
+    let mut file_bytes = b"%PDF-1.5\n".to_vec();
+    let object_offset = file_bytes.len();
+    file_bytes.extend_from_slice(object_bytes);
+    file_bytes.extend_from_slice(b"\nendobj\n");
+    let xref_offset = file_bytes.len();
+    file_bytes.extend_from_slice(
+        format!(
+            "xref\n0 1\n0000000000 65535 f \n{} 1\n{:010} {:05} n \n\
+            trailer\n<< /Size {} >>\nstartxref\n{}\n%%EOF\n",
+            object_id.0,
+            object_offset,
+            object_id.1,
+            object_id.0 + 1,
+            xref_offset
+        )
+        .as_bytes(),
+    );
+    match Document::load_mem(&file_bytes)
+        .ok()?
+        .objects
+        .remove(&object_id)?
+    {
+        Object::Dictionary(dict) => Some(dict),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_doc::create_sample_document;
+    use lopdf::{dictionary, StringFormat};
+
+    const USER_PASSWORD: &str = "user";
+    const OWNER_PASSWORD: &str = "owner";
+
+    /// Encrypt the sample document with RC4, like a PDF writer would, and save it.
+    ///
+    /// See 7.6.3.3 (Algorithm 3, 4 and 5) in PDF v1.7 Spec.
+    fn create_encrypted_file(revision: i64) -> Vec<u8> {
+        let mut doc = create_sample_document().unwrap();
+        let file_id = b"0123456789abcdef".to_vec();
+        doc.trailer.set(
+            "ID",
+            vec![
+                Object::String(file_id.clone(), StringFormat::Hexadecimal),
+                Object::String(file_id.clone(), StringFormat::Hexadecimal),
+            ],
+        );
+        let key_length = if revision == 2 { 5 } else { 16 };
+
+        let mut owner_hash = md5::compute(pad_password(OWNER_PASSWORD.as_bytes())).0;
+        if revision >= 3 {
+            for _ in 0..50 {
+                owner_hash = md5::compute(&owner_hash[..key_length]).0;
+            }
+        }
+        let owner_key = &owner_hash[..key_length];
+        let mut owner_value = rc4(owner_key, &pad_password(USER_PASSWORD.as_bytes()));
+        if revision >= 3 {
+            for round in 1..=19 {
+                owner_value = rc4(&xor_key(owner_key, round), &owner_value);
+            }
+        }
+        let mut handler = StandardSecurityHandler {
+            revision,
+            key_length,
+            owner_hash: owner_value,
+            user_hash: Vec::new(),
+            permission_flags: -4,
+            file_id: file_id.clone(),
+            encrypt_metadata: true,
+        };
+        let key = handler.get_encryption_key(USER_PASSWORD.as_bytes());
+        handler.user_hash = if revision == 2 {
+            rc4(&key, &PASSWORD_PADDING)
+        } else {
+            let mut input = PASSWORD_PADDING.to_vec();
+            input.extend_from_slice(&file_id);
+            let mut hash = rc4(&key, &md5::compute(&input).0);
+            for round in 1..=19 {
+                hash = rc4(&xor_key(&key, round), &hash);
+            }
+            hash.resize(32, 0);
+            hash
+        };
+
+        // RC4 is symmetric, decrypting with the same key encrypts.
+        for (object_id, obj) in doc.objects.iter_mut() {
+            decrypt_object(&get_object_key(&key, *object_id), obj, true);
+        }
+        let encrypt_id = doc.add_object(dictionary! {
+            "Filter" => "Standard",
+            "V" => if revision == 2 { 1 } else { 2 },
+            "R" => revision,
+            "Length" => key_length as i64 * 8,
+            "O" => Object::String(handler.owner_hash, StringFormat::Hexadecimal),
+            "U" => Object::String(handler.user_hash, StringFormat::Hexadecimal),
+            "P" => handler.permission_flags as i64,
+        });
+        doc.trailer.set("Encrypt", encrypt_id);
+        let mut file_bytes = Vec::new();
+        doc.save_to(&mut file_bytes).unwrap();
+        file_bytes
+    }
+
+    fn load_decrypted(file_bytes: &[u8], password: &str) -> Result<Document, DecryptionError> {
+        let mut raw_doc = Document::load_mem(file_bytes).unwrap();
+        decrypt_document(&mut raw_doc, file_bytes, Some(password))?;
+        Ok(raw_doc)
+    }
+
+    fn get_title(raw_doc: &Document) -> Vec<u8> {
+        raw_doc
+            .trailer
+            .get_deref(b"Info", raw_doc)
+            .and_then(Object::as_dict)
+            .and_then(|info| info.get(b"Title"))
+            .and_then(Object::as_str)
+            .unwrap()
+            .to_vec()
+    }
+
+    fn assert_decrypted(raw_doc: &Document) {
+        assert_eq!(get_title(raw_doc), b"Sample document");
+        assert_eq!(
+            raw_doc.extract_text(&[1]).unwrap().trim(),
+            "PDF Structure Viewer"
+        );
+    }
+
+    #[test]
+    fn decrypts_with_user_and_owner_password() {
+        for revision in [2, 3] {
+            let file_bytes = create_encrypted_file(revision);
+            let encrypted_doc = Document::load_mem(&file_bytes).unwrap();
+            assert_ne!(get_title(&encrypted_doc), b"Sample document");
+            assert_decrypted(&load_decrypted(&file_bytes, USER_PASSWORD).unwrap());
+            assert_decrypted(&load_decrypted(&file_bytes, OWNER_PASSWORD).unwrap());
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_password() {
+        for revision in [2, 3] {
+            let file_bytes = create_encrypted_file(revision);
+            for password in ["wrong", ""] {
+                assert!(matches!(
+                    load_decrypted(&file_bytes, password),
+                    Err(DecryptionError::WrongPassword)
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use crate::page_tree::load_page_tree;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+
+/// Stop looking up a named destination after this many name tree nodes.
+static MAX_NAME_TREE_NODES: usize = 10_000;
+
+/// A destination (the target of a Link annotation, outline item or `GoTo` action)
+/// resolved to the page it points to.
+///
+/// See p366 (12.3.2 Destinations) in PDF v1.7 Spec.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedDestination {
+    /// Page number starting from 1, `None` when the page is not in the page tree.
+    pub page_number: Option<usize>,
+    /// The page object, `None` when the destination uses a page number (remote destinations).
+    pub page_id: Option<ObjectId>,
+    /// The view parameters, like `/XYZ 0 792 0`.
+    pub view: String,
+    /// Name of the destination, when it was looked up by name.
+    pub name: Option<String>,
+}
+
+impl ResolvedDestination {
+    pub fn description(&self) -> String {
+        let page = match (self.page_number, self.page_id) {
+            (Some(page_number), _) => format!("page {}", page_number),
+            (None, Some(page_id)) => format!("({},{}) not in page tree", page_id.0, page_id.1),
+            (None, None) => "unknown page".to_owned(),
+        };
+        let mut description = format!("→ {}", page);
+        if !self.view.is_empty() {
+            description.push_str(&format!(", {}", self.view));
+        }
+        if let Some(name) = &self.name {
+            description.push_str(&format!(" (named `{}`)", name));
+        }
+        description
+    }
+}
+
+/// Resolve a destination: an explicit destination array, a name or a string
+/// (named destinations) or a dictionary with a `D` entry.
+///
+/// Returns `None` if the destination can not be resolved.
+pub fn resolve_destination(
+    raw_doc: &Document,
+    destination: &Object,
+) -> Option<ResolvedDestination> {
+    resolve_destination_inner(raw_doc, destination, None, 0)
+}
+
+fn resolve_destination_inner(
+    raw_doc: &Document,
+    destination: &Object,
+    name: Option<String>,
+    depth: usize,
+) -> Option<ResolvedDestination> {
+    // Named destinations can point to other named destinations, do not follow them forever.
+    if depth > 4 {
+        log::warn!("Named destinations refer to each other too often, stopped resolving.");
+        return None;
+    }
+    let (_, destination) = raw_doc.dereference(destination).ok()?;
+    match destination {
+        Object::Array(list) => {
+            let (page_number, page_id) = match list.first()? {
+                Object::Reference(page_id) => (get_page_number(raw_doc, *page_id), Some(*page_id)),
+                // Remote destinations use a page index starting from 0.
+                Object::Integer(page_index) => (usize::try_from(*page_index + 1).ok(), None),
+                _ => return None,
+            };
+            Some(ResolvedDestination {
+                page_number,
+                page_id,
+                view: get_view_string(&list[1..]),
+                name,
+            })
+        }
+        Object::Dictionary(dict) => {
+            resolve_destination_inner(raw_doc, dict.get(b"D").ok()?, name, depth + 1)
+        }
+        Object::Name(dest_name) | Object::String(dest_name, _) => {
+            let target = find_named_destination(raw_doc, dest_name)?;
+            let name = String::from_utf8_lossy(dest_name).to_string();
+            resolve_destination_inner(raw_doc, target, Some(name), depth + 1)
+        }
+        _ => None,
+    }
+}
+
+/// Look up a named destination in the `Dests` name tree of the `Names` dictionary,
+/// and in the `Dests` dictionary of the catalog (used before PDF 1.2).
+fn find_named_destination<'a>(raw_doc: &'a Document, name: &[u8]) -> Option<&'a Object> {
+    let catalog = raw_doc.catalog().ok()?;
+    let name_tree = catalog
+        .get(b"Names")
+        .and_then(|names| raw_doc.dereference(names))
+        .ok()
+        .and_then(|(_, names)| names.as_dict().ok())
+        .and_then(|names| names.get(b"Dests").ok());
+    if let Some(name_tree) = name_tree {
+        let mut visited_nodes = 0;
+        if let Some(target) = find_in_name_tree(raw_doc, name_tree, name, &mut visited_nodes) {
+            return Some(target);
+        }
+    }
+    let (_, dests) = raw_doc.dereference(catalog.get(b"Dests").ok()?).ok()?;
+    dests.as_dict().ok()?.get(name).ok()
+}
+
+/// Search a name tree, going into `Kids` whose `Limits` include the name.
+///
+/// See p88 (7.9.6 Name Trees) in PDF v1.7 Spec.
+fn find_in_name_tree<'a>(
+    raw_doc: &'a Document,
+    node: &'a Object,
+    name: &[u8],
+    visited_nodes: &mut usize,
+) -> Option<&'a Object> {
+    *visited_nodes += 1;
+    if *visited_nodes > MAX_NAME_TREE_NODES {
+        return None;
+    }
+    let node = raw_doc.dereference(node).ok()?.1.as_dict().ok()?;
+    if let Ok(Object::Array(names)) = node.get(b"Names") {
+        for pair in names.chunks_exact(2) {
+            if let Object::String(key, _) = &pair[0] {
+                if key == name {
+                    return Some(&pair[1]);
+                }
+            }
+        }
+    }
+    if let Ok(Object::Array(kids)) = node.get(b"Kids") {
+        for kid in kids {
+            if is_in_limits(raw_doc, kid, name) == Some(false) {
+                continue;
+            }
+            if let Some(target) = find_in_name_tree(raw_doc, kid, name, visited_nodes) {
+                return Some(target);
+            }
+        }
+    }
+    None
+}
+
+/// Get all entries (keys and values) of a name tree, in the order they are stored.
+///
+/// See p88 (7.9.6 Name Trees) in PDF v1.7 Spec.
+pub fn get_name_tree_entries<'a>(
+    raw_doc: &'a Document,
+    node: &'a Object,
+) -> Vec<(&'a [u8], &'a Object)> {
+    let mut entries = Vec::new();
+    let mut visited_nodes = 0;
+    collect_name_tree_entries(raw_doc, node, &mut entries, &mut visited_nodes);
+    entries
+}
+
+fn collect_name_tree_entries<'a>(
+    raw_doc: &'a Document,
+    node: &'a Object,
+    entries: &mut Vec<(&'a [u8], &'a Object)>,
+    visited_nodes: &mut usize,
+) {
+    *visited_nodes += 1;
+    if *visited_nodes > MAX_NAME_TREE_NODES {
+        log::warn!("Name tree has too many nodes, stopped reading it.");
+        return;
+    }
+    let node = match raw_doc
+        .dereference(node)
+        .and_then(|(_, node)| node.as_dict())
+    {
+        Ok(node) => node,
+        Err(_) => return,
+    };
+    if let Ok(Object::Array(names)) = node.get(b"Names") {
+        for pair in names.chunks_exact(2) {
+            if let Object::String(key, _) = &pair[0] {
+                entries.push((key.as_slice(), &pair[1]));
+            }
+        }
+    }
+    if let Ok(Object::Array(kids)) = node.get(b"Kids") {
+        for kid in kids {
+            collect_name_tree_entries(raw_doc, kid, entries, visited_nodes);
+        }
+    }
+}
+
+/// Check the `Limits` of a name tree node, `None` if it has no (valid) limits.
+fn is_in_limits(raw_doc: &Document, node: &Object, name: &[u8]) -> Option<bool> {
+    let node: &Dictionary = raw_doc.dereference(node).ok()?.1.as_dict().ok()?;
+    match node.get(b"Limits").ok()? {
+        Object::Array(limits) => match (limits.first()?, limits.get(1)?) {
+            (Object::String(first, _), Object::String(last, _)) => {
+                Some(first.as_slice() <= name && name <= last.as_slice())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Get the page number (starting from 1) of a page object.
+pub fn get_page_number(raw_doc: &Document, page_id: ObjectId) -> Option<usize> {
+    let page_tree = load_page_tree(raw_doc).ok()?;
+    page_tree
+        .leaf_pages()
+        .iter()
+        .position(|page| page.object_id == page_id)
+        .map(|index| index + 1)
+}
+
+fn get_view_string(parameters: &[Object]) -> String {
+    parameters
+        .iter()
+        .map(|parameter| match parameter {
+            Object::Name(name) => format!("/{}", String::from_utf8_lossy(name)),
+            Object::Integer(value) => value.to_string(),
+            Object::Real(value) => value.to_string(),
+            Object::Null => "null".to_owned(),
+            _ => "?".to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,128 @@
+use lopdf::{Dictionary, Document, Object};
+use serde::Serialize;
+
+/// Permissions in the `P` entry of the encryption dictionary, with their bit position (from 1).
+///
+/// See p61 (Table 22, User access permissions) in PDF v1.7 Spec.
+static PERMISSION_BITS: [(u32, &str); 8] = [
+    (3, "print"),
+    (4, "modify"),
+    (5, "copy"),
+    (6, "annotate"),
+    (9, "fill forms"),
+    (10, "extract for accessibility"),
+    (11, "assemble"),
+    (12, "print high quality"),
+];
+
+/// Get the permissions of a `P` value, with `true` if they are granted.
+pub fn get_permissions(p_value: i64) -> Vec<(&'static str, bool)> {
+    // The value is a 32 bit signed integer, but some writers store it unsigned.
+    let flags = p_value as u32;
+    PERMISSION_BITS
+        .iter()
+        .map(|(bit, name)| (*name, flags & (1 << (bit - 1)) != 0))
+        .collect()
+}
+
+/// Describe the permissions of a `P` value, like `allowed: print, copy; denied: modify`.
+pub fn describe_permissions(p_value: i64) -> String {
+    let permissions = get_permissions(p_value);
+    let list = |granted: bool| -> String {
+        let names: Vec<&str> = permissions
+            .iter()
+            .filter(|(_, is_granted)| *is_granted == granted)
+            .map(|(name, _)| *name)
+            .collect();
+        if names.is_empty() {
+            "none".to_owned()
+        } else {
+            names.join(", ")
+        }
+    };
+    format!("allowed: {}; denied: {}", list(true), list(false))
+}
+
+/// The encryption settings from the `Encrypt` dictionary in the trailer.
+///
+/// See p55 (7.6 Encryption) in PDF v1.7 Spec.
+#[derive(Debug, Clone, Serialize)]
+pub struct EncryptionInfo {
+    /// The security handler, like `Standard`.
+    pub filter: Option<String>,
+    /// The algorithm version (`V`).
+    pub version: Option<i64>,
+    /// The revision of the standard security handler (`R`).
+    pub revision: Option<i64>,
+    /// Length of the encryption key in bits.
+    pub key_length: i64,
+    /// The `P` value.
+    pub permission_flags: Option<i64>,
+    pub allowed: Vec<&'static str>,
+    pub denied: Vec<&'static str>,
+}
+
+/// Check if the trailer has an `Encrypt` entry.
+pub fn is_encrypted(raw_doc: &Document) -> bool {
+    raw_doc.trailer.has(b"Encrypt")
+}
+
+/// Get the encryption settings, `None` if the document is not encrypted
+/// or the `Encrypt` entry is not a dictionary.
+pub fn get_encryption_info(raw_doc: &Document) -> Option<EncryptionInfo> {
+    let (_, encrypt) = raw_doc
+        .dereference(raw_doc.trailer.get(b"Encrypt").ok()?)
+        .ok()?;
+    let dict = encrypt.as_dict().ok()?;
+    let get_i64 =
+        |dict: &Dictionary, key: &[u8]| dict.get_deref(key, raw_doc).and_then(Object::as_i64).ok();
+    let version = get_i64(dict, b"V");
+    let permission_flags = get_i64(dict, b"P");
+    let permissions = permission_flags.map(get_permissions).unwrap_or_default();
+    Some(EncryptionInfo {
+        filter: dict
+            .get(b"Filter")
+            .and_then(Object::as_name_str)
+            .ok()
+            .map(|filter| filter.to_owned()),
+        version,
+        revision: get_i64(dict, b"R"),
+        key_length: get_key_length(raw_doc, dict, version),
+        permission_flags,
+        allowed: permissions
+            .iter()
+            .filter(|(_, granted)| *granted)
+            .map(|(name, _)| *name)
+            .collect(),
+        denied: permissions
+            .iter()
+            .filter(|(_, granted)| !*granted)
+            .map(|(name, _)| *name)
+            .collect(),
+    })
+}
+
+/// Get the key length in bits, from `Length` or from the default crypt filter (`StdCF`).
+pub fn get_key_length(raw_doc: &Document, dict: &Dictionary, version: Option<i64>) -> i64 {
+    match version {
+        Some(5) => return 256,
+        Some(4) => {
+            let crypt_filter_length = dict
+                .get_deref(b"CF", raw_doc)
+                .and_then(Object::as_dict)
+                .and_then(|crypt_filters| crypt_filters.get_deref(b"StdCF", raw_doc))
+                .and_then(Object::as_dict)
+                .and_then(|crypt_filter| crypt_filter.get_deref(b"Length", raw_doc))
+                .and_then(Object::as_i64);
+            if let Ok(length) = crypt_filter_length {
+                // Often written in bytes instead of bits.
+                return if length <= 32 { length * 8 } else { length };
+            }
+        }
+        _ => {}
+    }
+    // Default is 40 bits.
+    dict.get_deref(b"Length", raw_doc)
+        .and_then(Object::as_i64)
+        .unwrap_or(40)
+}
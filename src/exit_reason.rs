@@ -0,0 +1,97 @@
+use lopdf::ObjectId;
+use std::io::{Error, ErrorKind};
+use std::sync::Mutex;
+
+lazy_static::lazy_static! {
+    /// The object that was being printed when printing failed.
+    static ref FAILED_OBJECT: Mutex<Option<ObjectId>> = Mutex::new(None);
+}
+
+/// Why the program exits with an error, printed as one line on stderr for scripts.
+///
+/// Like: `ERROR: kind=decode_failure object=12,0 detail=...`
+#[derive(Debug)]
+pub struct ExitReason {
+    /// Short `snake_case` name of the kind of error, like `parse_failure`.
+    pub kind: &'static str,
+    pub detail: String,
+}
+
+impl ExitReason {
+    /// Create an `io::Error` that carries the exit reason.
+    pub fn into_error(error_kind: ErrorKind, kind: &'static str, detail: impl ToString) -> Error {
+        Error::new(
+            error_kind,
+            ExitReason {
+                kind,
+                detail: detail.to_string(),
+            },
+        )
+    }
+}
+
+impl std::fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.detail)
+    }
+}
+
+impl std::error::Error for ExitReason {}
+
+/// Remember the object that was being printed, to report it when printing fails.
+pub fn set_failed_object(object_id: ObjectId) {
+    if let Ok(mut failed_object) = FAILED_OBJECT.lock() {
+        *failed_object = Some(object_id);
+    }
+}
+
+/// Forget the failed object, used before running again in `watch` mode.
+pub fn reset_failed_object() {
+    if let Ok(mut failed_object) = FAILED_OBJECT.lock() {
+        *failed_object = None;
+    }
+}
+
+/// Get the kind of exit reason for a lopdf error.
+pub fn get_lopdf_error_kind(err: &lopdf::Error) -> &'static str {
+    match err {
+        lopdf::Error::ContentDecode => "decode_failure",
+        lopdf::Error::IO(err) if err.kind() == ErrorKind::TimedOut => "timeout",
+        lopdf::Error::IO(_) => "io_error",
+        lopdf::Error::ObjectNotFound | lopdf::Error::PageNumberNotFound(_) => "missing_object",
+        lopdf::Error::Header
+        | lopdf::Error::Parse { .. }
+        | lopdf::Error::Syntax(_)
+        | lopdf::Error::Xref(_)
+        | lopdf::Error::Trailer
+        | lopdf::Error::Offset(_)
+        | lopdf::Error::ObjectIdMismatch => "parse_failure",
+        _ => "invalid_structure",
+    }
+}
+
+/// Print the exit reason of an error as one line on stderr, without colors.
+pub fn print_exit_reason(err: &Error) {
+    let (kind, detail) = match err
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<ExitReason>())
+    {
+        Some(exit_reason) => (exit_reason.kind, exit_reason.detail.clone()),
+        None => {
+            let kind = match err.kind() {
+                ErrorKind::InvalidInput => "invalid_input",
+                ErrorKind::InvalidData => "invalid_data",
+                ErrorKind::TimedOut => "timeout",
+                _ => "io_error",
+            };
+            (kind, err.to_string())
+        }
+    };
+    let object = match FAILED_OBJECT.lock().ok().and_then(|object_id| *object_id) {
+        Some(object_id) => format!(" object={},{}", object_id.0, object_id.1),
+        None => String::new(),
+    };
+    // Keep it on one line.
+    let detail = detail.replace(char::is_control, " ");
+    eprintln!("ERROR: kind={}{} detail={}", kind, object, detail);
+}
@@ -0,0 +1,85 @@
+use lopdf::{Document, Error, Object, ObjectId};
+use std::io::Write;
+use std::path::Path;
+use yansi::{Paint, Style};
+
+/// Filters lopdf can decode, see `Stream::decompressed_content`.
+static SUPPORTED_FILTERS: [&str; 2] = ["FlateDecode", "LZWDecode"];
+
+/// Write the decoded content of a stream to a file, or to `out` when `export_to` is `-`.
+///
+/// When a filter can not be decoded the content is written as stored in the file.
+pub fn export_stream(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    object_id: ObjectId,
+    export_to: &Path,
+) -> Result<(), Error> {
+    let stream = match raw_doc.objects.get(&object_id) {
+        Some(Object::Stream(stream)) => stream,
+        Some(_) => {
+            log::error!("Object ({},{}) is not a stream.", object_id.0, object_id.1);
+            return Err(Error::Type);
+        }
+        None => {
+            log::error!("Object ({},{}) does not exist.", object_id.0, object_id.1);
+            return Err(Error::ObjectNotFound);
+        }
+    };
+
+    let filters = if stream.dict.has(b"Filter") {
+        stream.filters().unwrap_or_else(|_| {
+            log::warn!("Stream has an invalid `Filter`, it should be a name or array of names.");
+            vec!["(invalid)".to_owned()]
+        })
+    } else {
+        Vec::new()
+    };
+    let (content, decoded) = if filters.is_empty() {
+        (stream.content.clone(), true)
+    } else {
+        // lopdf does not decompress images, so decompress a copy that is not marked as an image.
+        let mut copy = stream.clone();
+        copy.dict.remove(b"Subtype");
+        match copy.decompressed_content() {
+            Ok(content) => (content, true),
+            Err(_) => {
+                match filters
+                    .iter()
+                    .find(|filter| !SUPPORTED_FILTERS.contains(&filter.as_str()))
+                {
+                    Some(filter) => log::warn!(
+                        "Filter `{}` can not be decoded, writing the stream as stored in the file.",
+                        filter
+                    ),
+                    None => log::warn!(
+                        "Stream could not be decoded (corrupt data?), \
+                        writing the stream as stored in the file."
+                    ),
+                }
+                (stream.content.clone(), false)
+            }
+        }
+    };
+
+    if export_to == Path::new("-") {
+        out.write_all(&content)?;
+        return Ok(());
+    }
+    std::fs::write(export_to, &content)?;
+    let extra_info_style = Style::default().italic();
+    writeln!(
+        out,
+        "Wrote {} bytes of ({},{}) to {} {}",
+        Style::default().bold().paint(content.len()),
+        object_id.0,
+        object_id.1,
+        Paint::default(export_to.display()).bold(),
+        extra_info_style.paint(if decoded {
+            "(decoded)".to_owned()
+        } else {
+            format!("(as stored, filters: {})", filters.join(", "))
+        })
+    )?;
+    Ok(())
+}
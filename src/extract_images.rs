@@ -0,0 +1,365 @@
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use lopdf::{Dictionary, Document, Error, Object, ObjectId, Stream};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use yansi::{Paint, Style};
+
+/// Description of an image written next to its raw samples,
+/// for images that can not be converted to PNG.
+#[derive(Debug, Serialize)]
+struct ImageSidecar {
+    width: i64,
+    height: i64,
+    bits_per_component: i64,
+    color_space: String,
+    filters: Vec<String>,
+    /// If the data file contains the decoded samples or the bytes as stored in the file.
+    decoded: bool,
+}
+
+/// How an image was written.
+enum ImageOutput {
+    Jpeg,
+    Jpeg2000,
+    Png,
+    /// Samples with a `.json` description, with the reason why no PNG was written.
+    Raw(String),
+}
+
+/// Write all image XObjects to `output_dir`, named by their object id (like `12_0.png`).
+///
+/// `DCTDecode` images are written as `.jpg` and `JPXDecode` images as `.jp2`, unchanged.
+/// Other images are decoded and written as PNG when the color space is gray, RGB
+/// (also as `ICCBased`) or an indexed RGB palette. When that is not possible,
+/// the samples are written as `.raw` with a `.json` file describing them.
+/// Inline images (in content streams) are not included.
+pub fn extract_images(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    output_dir: &Path,
+) -> Result<(), Error> {
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+    let extra_info_style = Style::default().italic();
+
+    std::fs::create_dir_all(output_dir)?;
+    writeln!(out, "--- {} ---", Paint::cyan("Images").bold())?;
+    let mut image_count = 0;
+    for (object_id, object) in raw_doc.objects.iter() {
+        let stream = match object {
+            Object::Stream(stream) if is_image(&stream.dict) => stream,
+            _ => continue,
+        };
+        image_count += 1;
+        let (file_name, output) = write_image(raw_doc, *object_id, stream, output_dir)?;
+        let note = match output {
+            ImageOutput::Jpeg => "JPEG, unchanged".to_owned(),
+            ImageOutput::Jpeg2000 => "JPEG 2000, unchanged".to_owned(),
+            ImageOutput::Png => "decoded to PNG".to_owned(),
+            ImageOutput::Raw(reason) => format!("raw samples, {}", reason),
+        };
+        writeln!(
+            out,
+            "{} {} {}",
+            value_style.paint(format!("({},{})", object_id.0, object_id.1)),
+            label_style.paint(file_name),
+            extra_info_style.paint(format!("({})", note))
+        )?;
+    }
+    if image_count == 0 {
+        writeln!(out, "{}", extra_info_style.paint("No images found."))?;
+    }
+    Ok(())
+}
+
+fn write_image(
+    raw_doc: &Document,
+    object_id: ObjectId,
+    stream: &Stream,
+    output_dir: &Path,
+) -> Result<(String, ImageOutput), Error> {
+    let base_name = format!("{}_{}", object_id.0, object_id.1);
+    let filters = stream.filters().unwrap_or_default();
+    // Only write JPEG files without other filters (like ASCIIHexDecode) applied.
+    match filters.as_slice() {
+        [filter] if filter == "DCTDecode" => {
+            let file_name = format!("{}.jpg", base_name);
+            std::fs::write(output_dir.join(&file_name), &stream.content)?;
+            return Ok((file_name, ImageOutput::Jpeg));
+        }
+        [filter] if filter == "JPXDecode" => {
+            let file_name = format!("{}.jp2", base_name);
+            std::fs::write(output_dir.join(&file_name), &stream.content)?;
+            return Ok((file_name, ImageOutput::Jpeg2000));
+        }
+        _ => {}
+    }
+
+    let get_number = |key: &[u8]| -> i64 {
+        stream
+            .dict
+            .get(key)
+            .and_then(|value| raw_doc.dereference(value))
+            .and_then(|(_, value)| value.as_i64())
+            .unwrap_or(0)
+    };
+    let width = get_number(b"Width");
+    let height = get_number(b"Height");
+    let is_image_mask = stream
+        .dict
+        .get(b"ImageMask")
+        .and_then(Object::as_bool)
+        .unwrap_or(false);
+    // Image masks always use 1 bit per sample.
+    let bits_per_component = if is_image_mask {
+        1
+    } else {
+        get_number(b"BitsPerComponent")
+    };
+    let color_space = stream
+        .dict
+        .get(b"ColorSpace")
+        .and_then(|color_space| raw_doc.dereference(color_space))
+        .map(|(_, color_space)| color_space)
+        .ok();
+
+    let samples = decode_samples(stream, &filters);
+    let decoded = samples.is_some();
+    let samples = samples.unwrap_or_else(|| stream.content.clone());
+    let png_format = if is_image_mask {
+        Ok(PngFormat {
+            color_type: 0,
+            channels: 1,
+            palette: None,
+        })
+    } else {
+        get_png_format(raw_doc, color_space, bits_per_component)
+    };
+    let reason = match png_format {
+        _ if !decoded => format!("not all filters are supported: {}", filters.join(", ")),
+        Ok(png_format) => {
+            match encode_png(width, height, bits_per_component, &png_format, &samples) {
+                Some(png) => {
+                    let file_name = format!("{}.png", base_name);
+                    std::fs::write(output_dir.join(&file_name), png)?;
+                    return Ok((file_name, ImageOutput::Png));
+                }
+                None => "size does not match the samples".to_owned(),
+            }
+        }
+        Err(reason) => reason,
+    };
+
+    let file_name = format!("{}.raw", base_name);
+    std::fs::write(output_dir.join(&file_name), &samples)?;
+    let sidecar = ImageSidecar {
+        width,
+        height,
+        bits_per_component,
+        color_space: color_space
+            .map(|color_space| get_color_space_name(raw_doc, color_space))
+            .unwrap_or_else(|| "-".to_owned()),
+        filters,
+        decoded,
+    };
+    let sidecar_json = serde_json::to_string_pretty(&sidecar)
+        .map_err(|err| Error::IO(std::io::Error::other(err)))?;
+    std::fs::write(output_dir.join(format!("{}.json", base_name)), sidecar_json)?;
+    Ok((file_name, ImageOutput::Raw(reason)))
+}
+
+/// Decode the image data, `None` if a filter is not supported.
+fn decode_samples(stream: &Stream, filters: &[String]) -> Option<Vec<u8>> {
+    if filters.is_empty() {
+        return Some(stream.content.clone());
+    }
+    // lopdf does not decompress images, so decompress a copy that is not marked as an image.
+    let mut copy = stream.clone();
+    copy.dict.remove(b"Subtype");
+    copy.decompressed_content().ok()
+}
+
+struct PngFormat {
+    /// PNG color type: 0 (gray), 2 (RGB) or 3 (palette).
+    color_type: u8,
+    channels: i64,
+    /// RGB entries for the `PLTE` chunk.
+    palette: Option<Vec<u8>>,
+}
+
+/// Get the PNG format for a color space, or why it can not be written as PNG.
+fn get_png_format(
+    raw_doc: &Document,
+    color_space: Option<&Object>,
+    bits_per_component: i64,
+) -> Result<PngFormat, String> {
+    let color_space = color_space.ok_or_else(|| "no color space".to_owned())?;
+    let channels = get_color_space_channels(raw_doc, color_space);
+    let format = match channels {
+        Some(1) => PngFormat {
+            color_type: 0,
+            channels: 1,
+            palette: None,
+        },
+        Some(3) => PngFormat {
+            color_type: 2,
+            channels: 3,
+            palette: None,
+        },
+        _ => match get_indexed_palette(raw_doc, color_space) {
+            Some(palette) => PngFormat {
+                color_type: 3,
+                channels: 1,
+                palette: Some(palette),
+            },
+            None => {
+                return Err(format!(
+                    "color space {} can not be written as PNG",
+                    get_color_space_name(raw_doc, color_space)
+                ))
+            }
+        },
+    };
+    // Allowed bit depths, see the `IHDR` chunk in the PNG specification.
+    let allowed_bit_depths: &[i64] = match format.color_type {
+        0 => &[1, 2, 4, 8, 16],
+        2 => &[8, 16],
+        _ => &[1, 2, 4, 8],
+    };
+    if !allowed_bit_depths.contains(&bits_per_component) {
+        return Err(format!(
+            "{} bits per component can not be written as PNG",
+            bits_per_component
+        ));
+    }
+    Ok(format)
+}
+
+/// The number of components of a gray or RGB color space, `None` for other color spaces.
+fn get_color_space_channels(raw_doc: &Document, color_space: &Object) -> Option<i64> {
+    match color_space {
+        Object::Name(name) => match name.as_slice() {
+            b"DeviceGray" | b"CalGray" => Some(1),
+            b"DeviceRGB" | b"CalRGB" => Some(3),
+            _ => None,
+        },
+        Object::Array(list) => match list.first()?.as_name().ok()? {
+            b"CalGray" => Some(1),
+            b"CalRGB" => Some(3),
+            b"ICCBased" => {
+                let (_, profile) = raw_doc.dereference(list.get(1)?).ok()?;
+                let channels = profile
+                    .as_stream()
+                    .ok()?
+                    .dict
+                    .get(b"N")
+                    .ok()?
+                    .as_i64()
+                    .ok()?;
+                Some(channels).filter(|channels| *channels == 1 || *channels == 3)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Get the RGB palette of an `Indexed` color space with an RGB base.
+///
+/// See p156 (8.6.6.3 Indexed Colour Spaces) in PDF v1.7 Spec.
+fn get_indexed_palette(raw_doc: &Document, color_space: &Object) -> Option<Vec<u8>> {
+    let list = color_space.as_array().ok()?;
+    if list.first()?.as_name().ok()? != b"Indexed" {
+        return None;
+    }
+    let (_, base) = raw_doc.dereference(list.get(1)?).ok()?;
+    if get_color_space_channels(raw_doc, base) != Some(3) {
+        return None;
+    }
+    let high_value = list.get(2)?.as_i64().ok()?;
+    let palette_size = usize::try_from(high_value + 1).ok()?.min(256) * 3;
+    let lookup = match raw_doc.dereference(list.get(3)?).ok()?.1 {
+        Object::String(lookup, _) => lookup.clone(),
+        Object::Stream(stream) => decode_samples(stream, &stream.filters().ok()?)?,
+        _ => return None,
+    };
+    lookup.get(..palette_size).map(<[u8]>::to_vec)
+}
+
+fn get_color_space_name(raw_doc: &Document, color_space: &Object) -> String {
+    match color_space {
+        Object::Name(name) => String::from_utf8_lossy(name).to_string(),
+        Object::Array(list) => match list.first().and_then(|first| first.as_name().ok()) {
+            Some(name) => String::from_utf8_lossy(name).to_string(),
+            None => "unknown".to_owned(),
+        },
+        Object::Reference(_) => match raw_doc.dereference(color_space) {
+            Ok((_, color_space)) if !matches!(color_space, Object::Reference(_)) => {
+                get_color_space_name(raw_doc, color_space)
+            }
+            _ => "unknown".to_owned(),
+        },
+        _ => "unknown".to_owned(),
+    }
+}
+
+/// Encode samples as PNG, `None` if there are not enough samples for the size.
+///
+/// Rows of PDF image samples and PNG scanlines use the same layout,
+/// so each row only needs a filter type byte (0: none) in front.
+fn encode_png(
+    width: i64,
+    height: i64,
+    bits_per_component: i64,
+    format: &PngFormat,
+    samples: &[u8],
+) -> Option<Vec<u8>> {
+    let width_u32 = u32::try_from(width).ok().filter(|width| *width > 0)?;
+    let height_u32 = u32::try_from(height).ok().filter(|height| *height > 0)?;
+    let row_bits = (width as u128) * (format.channels as u128) * (bits_per_component as u128);
+    let row_bytes = usize::try_from(row_bits.div_ceil(8)).ok()?;
+    let data_size = row_bytes.checked_mul(height as usize)?;
+    if samples.len() < data_size {
+        return None;
+    }
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    for row in samples[..data_size].chunks_exact(row_bytes) {
+        encoder.write_all(&[0]).ok()?;
+        encoder.write_all(row).ok()?;
+    }
+    let image_data = encoder.finish().ok()?;
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&width_u32.to_be_bytes());
+    header.extend_from_slice(&height_u32.to_be_bytes());
+    // Bit depth, color type, compression, filter and interlace method.
+    header.extend_from_slice(&[bits_per_component as u8, format.color_type, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    write_png_chunk(&mut png, b"IHDR", &header);
+    if let Some(palette) = &format.palette {
+        write_png_chunk(&mut png, b"PLTE", palette);
+    }
+    write_png_chunk(&mut png, b"IDAT", &image_data);
+    write_png_chunk(&mut png, b"IEND", &[]);
+    Some(png)
+}
+
+fn write_png_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(chunk_type);
+    hasher.update(data);
+    png.extend_from_slice(&hasher.finalize().to_be_bytes());
+}
+
+fn is_image(dict: &Dictionary) -> bool {
+    dict.get(b"Subtype")
+        .and_then(Object::as_name)
+        .is_ok_and(|subtype| subtype == b"Image")
+}
@@ -0,0 +1,170 @@
+use crate::page_tree::{get_page_node, load_page_tree};
+use crate::print_tree::{decode_content_stream, decode_text_string};
+use lopdf::content::Operation;
+use lopdf::{Dictionary, Document, Error, Object, ObjectId, Stream};
+use std::io::Write;
+
+/// Kerning in a `TJ` array (in thousandths of a unit of text space) that is seen as a space.
+static TJ_SPACE_THRESHOLD: f64 = -200.0;
+
+/// Print the text of a page, or of all pages, as plain text without styling.
+///
+/// The strings shown by `Tj`, `TJ`, `'` and `"` are printed in the order of the
+/// content stream, spaces and line breaks are added when the text position moves.
+/// Fonts are not used: strings are shown as Latin-1, or as UTF-16BE when they start
+/// with a byte order mark. Text in form XObjects is not included.
+pub fn print_text(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    page: Option<usize>,
+) -> Result<(), Error> {
+    let page_ids: Vec<ObjectId> = match page {
+        Some(page_number) => vec![get_page_node(raw_doc, page_number)?.object_id],
+        None => load_page_tree(raw_doc)?
+            .leaf_pages()
+            .iter()
+            .map(|page| page.object_id)
+            .collect(),
+    };
+    for (index, page_id) in page_ids.into_iter().enumerate() {
+        if index > 0 {
+            // Separate pages with a form feed, like `pdftotext` does.
+            writeln!(out, "\x0c")?;
+        }
+        let page_dict = raw_doc.get_dictionary(page_id)?;
+        let content = get_page_content(raw_doc, page_dict);
+        let operations = decode_content_stream(&Stream::new(Dictionary::new(), content))?;
+        writeln!(out, "{}", extract_text(&operations.operations))?;
+    }
+    Ok(())
+}
+
+/// Get the (decompressed) content of a page, `Contents` can be a stream or an array of streams.
+fn get_page_content(raw_doc: &Document, page_dict: &Dictionary) -> Vec<u8> {
+    let stream_ids = match page_dict.get(b"Contents") {
+        Ok(Object::Reference(object_id)) => vec![*object_id],
+        Ok(Object::Array(list)) => list
+            .iter()
+            .filter_map(|item| item.as_reference().ok())
+            .collect(),
+        _ => Vec::new(),
+    };
+    let mut content = Vec::new();
+    for stream_id in stream_ids {
+        match raw_doc.get_object(stream_id).and_then(Object::as_stream) {
+            Ok(stream) => {
+                // lopdf returns an error for streams without filters.
+                let stream_content = if stream.dict.has(b"Filter") {
+                    stream
+                        .decompressed_content()
+                        .unwrap_or_else(|_| stream.content.clone())
+                } else {
+                    stream.content.clone()
+                };
+                content.extend(stream_content);
+                // The streams are concatenated, an operator can not be split between them.
+                content.push(b'\n');
+            }
+            Err(_) => log::warn!(
+                "Page content ({},{}) is not a stream.",
+                stream_id.0,
+                stream_id.1
+            ),
+        }
+    }
+    content
+}
+
+fn extract_text(operations: &[Operation]) -> String {
+    let mut text = String::new();
+    let mut last_line_y = None;
+    for operation in operations {
+        let operands = &operation.operands;
+        match operation.operator.as_str() {
+            "Tj" => push_strings(&mut text, operands),
+            "'" => {
+                push_separator(&mut text, '\n');
+                push_strings(&mut text, operands);
+            }
+            "\"" => {
+                push_separator(&mut text, '\n');
+                // The first two operands are the word and character spacing.
+                push_strings(&mut text, operands.get(2..).unwrap_or_default());
+            }
+            "TJ" => {
+                for item in operands.iter().flat_map(|operand| match operand {
+                    Object::Array(list) => list.as_slice(),
+                    _ => std::slice::from_ref(operand),
+                }) {
+                    match item.as_float() {
+                        Ok(kerning) if kerning < TJ_SPACE_THRESHOLD => {
+                            push_separator(&mut text, ' ')
+                        }
+                        Ok(_) => {}
+                        Err(_) => push_strings(&mut text, std::slice::from_ref(item)),
+                    }
+                }
+            }
+            "Td" | "TD" => match get_numbers(operands).as_slice() {
+                [_, ty] if *ty != 0.0 => push_separator(&mut text, '\n'),
+                [tx, _] if *tx > 0.0 => push_separator(&mut text, ' '),
+                _ => {}
+            },
+            "T*" => push_separator(&mut text, '\n'),
+            "Tm" => {
+                // A new line when the text moves up or down, otherwise a new word.
+                let line_y = get_numbers(operands).get(5).copied();
+                if last_line_y.is_some() && line_y != last_line_y {
+                    push_separator(&mut text, '\n');
+                } else {
+                    push_separator(&mut text, ' ');
+                }
+                last_line_y = line_y;
+            }
+            "BT" => last_line_y = None,
+            "ET" => push_separator(&mut text, ' '),
+            _ => {}
+        }
+    }
+    text.trim_end().to_owned()
+}
+
+fn push_strings(text: &mut String, operands: &[Object]) {
+    for operand in operands {
+        if let Object::String(value, _) = operand {
+            text.push_str(&decode_string(value));
+        }
+    }
+}
+
+/// Add a space or line break, unless the text is empty or already ends with one.
+/// A line break replaces a space at the end.
+fn push_separator(text: &mut String, separator: char) {
+    if text.is_empty() || text.ends_with('\n') {
+        return;
+    }
+    if text.ends_with(' ') {
+        if separator == ' ' {
+            return;
+        }
+        text.pop();
+    }
+    text.push(separator);
+}
+
+/// Strings in content streams are encoded by the font, which is not used here.
+/// Latin-1 is close to the common `WinAnsiEncoding` and `StandardEncoding` for ASCII text.
+fn decode_string(value: &[u8]) -> String {
+    if value.starts_with(&[0xFE, 0xFF]) {
+        decode_text_string(value)
+    } else {
+        value.iter().map(|byte| char::from(*byte)).collect()
+    }
+}
+
+fn get_numbers(operands: &[Object]) -> Vec<f64> {
+    operands
+        .iter()
+        .filter_map(|operand| operand.as_float().ok())
+        .collect()
+}
@@ -0,0 +1,965 @@
+use crate::page_tree::get_page_node;
+use crate::print_tree::{
+    decode_content_stream, decode_text_string, get_redacted_string, get_tree_start_dictionary,
+    is_content_stream, DepthInfo, TreeCursorInfo, TreeCursorSettings, TreeDisplaySettings,
+};
+use lopdf::{Dictionary, Document, Error, Object, Stream, StringFormat};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// Version of the JSON output format.
+///
+/// Increase this when a change to the structs below is not backwards compatible.
+pub static JSON_FORMAT_VERSION: u32 = 1;
+
+/// Root of the JSON output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonDocument {
+    pub format_version: u32,
+    pub file_name: String,
+    /// PDF version from the header, like `1.7`.
+    pub pdf_version: String,
+    /// The trailer, or the start set with `tree_start`, or the page when `page` is set.
+    pub root: JsonNode,
+}
+
+/// A value in the tree together with the key it is stored under.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonNode {
+    /// Dictionary key, `None` for array items and the root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(flatten)]
+    pub value: JsonValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JsonValue {
+    Null,
+    Boolean {
+        value: bool,
+    },
+    Integer {
+        value: i64,
+    },
+    Real {
+        value: f64,
+    },
+    Name {
+        value: String,
+    },
+    String {
+        value: String,
+        format: JsonStringFormat,
+    },
+    Array {
+        items: Vec<JsonNode>,
+    },
+    Dictionary {
+        entries: Vec<JsonNode>,
+    },
+    Stream {
+        entries: Vec<JsonNode>,
+        /// Length of the (decompressed) content in bytes.
+        length: usize,
+        /// The operations of a content stream, in the same cases as in the printed tree.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        operations: Vec<JsonNode>,
+    },
+    /// An operation of a content stream, its path ends with the index of the operation.
+    Operation {
+        operator: String,
+        operands: Vec<JsonNode>,
+    },
+    Reference {
+        object_number: u32,
+        generation: u16,
+        /// The referenced object, or a `skipped` node with the reason it was not followed.
+        /// `None` when references are not resolved, like in the `info` JSON.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        target: Option<Box<JsonNode>>,
+    },
+    /// Part of the tree that was not included, with the reason why.
+    Skipped {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonStringFormat {
+    Literal,
+    Hexadecimal,
+}
+
+/// One node of the tree, used for newline delimited JSON (one line per node).
+///
+/// Children are not included, they follow on the next lines with a longer `path`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonLine {
+    /// Dot separated path that can be used with `expand`, array items use their index.
+    pub path: String,
+    pub depth: usize,
+    /// Same names as the `type` field of `JsonValue`.
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// The value of simple types, the amount of items for arrays, dictionaries and streams.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+}
+
+/// Gets the nodes of the tree while it is walked, parents before the nodes below them.
+pub trait JsonVisitor {
+    /// Called for every node before the nodes below it.
+    ///
+    /// The items and entries of `node` are empty, they are given with the next calls.
+    /// `child_count` is the amount of items or entries in the PDF object,
+    /// also the ones that are not walked.
+    fn start_node(
+        &mut self,
+        cursor: &TreeCursorInfo,
+        node: JsonNode,
+        child_count: Option<usize>,
+    ) -> Result<(), Error>;
+
+    /// Called after all nodes below the last started node.
+    fn end_node(&mut self, cursor: &TreeCursorInfo) -> Result<(), Error>;
+}
+
+/// Builds the nested `JsonNode`s from the walked nodes.
+#[derive(Debug, Default)]
+struct JsonTreeBuilder {
+    /// The started nodes that are not ended yet.
+    open_nodes: Vec<JsonNode>,
+    root: Option<JsonNode>,
+}
+
+impl JsonVisitor for JsonTreeBuilder {
+    fn start_node(
+        &mut self,
+        _cursor: &TreeCursorInfo,
+        node: JsonNode,
+        _child_count: Option<usize>,
+    ) -> Result<(), Error> {
+        self.open_nodes.push(node);
+        Ok(())
+    }
+
+    fn end_node(&mut self, _cursor: &TreeCursorInfo) -> Result<(), Error> {
+        let node = match self.open_nodes.pop() {
+            Some(node) => node,
+            None => return Ok(()),
+        };
+        match self.open_nodes.last_mut().map(|parent| &mut parent.value) {
+            None => self.root = Some(node),
+            Some(JsonValue::Array { items }) => items.push(node),
+            Some(JsonValue::Stream { operations, .. })
+                if matches!(node.value, JsonValue::Operation { .. }) =>
+            {
+                operations.push(node)
+            }
+            Some(JsonValue::Dictionary { entries } | JsonValue::Stream { entries, .. }) => {
+                entries.push(node)
+            }
+            Some(JsonValue::Reference { target, .. }) => *target = Some(Box::new(node)),
+            // Other values have no nodes below them.
+            Some(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Convert the document into the JSON structs.
+pub fn get_json_document(
+    display_settings: &TreeDisplaySettings,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+    file_name: String,
+) -> Result<JsonDocument, Error> {
+    // Nothing is printed while building the nodes.
+    let mut sink = std::io::sink();
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, &mut sink);
+    let mut builder = JsonTreeBuilder::default();
+    walk_json_tree(display_settings, raw_doc, &cursor, &mut builder)?;
+    let root = builder
+        .root
+        .ok_or_else(|| Error::Syntax("The JSON tree has no root.".to_owned()))?;
+    Ok(JsonDocument {
+        format_version: JSON_FORMAT_VERSION,
+        file_name,
+        pdf_version: raw_doc.version.clone(),
+        root,
+    })
+}
+
+/// Print the document as JSON.
+pub fn print_json_tree(
+    out: &mut dyn Write,
+    display_settings: &TreeDisplaySettings,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+    file_name: String,
+) -> Result<(), Error> {
+    let json_document =
+        get_json_document(display_settings, tree_cursor_settings, raw_doc, file_name)?;
+    let json_string = serde_json::to_string_pretty(&json_document)
+        .map_err(|err| Error::Syntax(format!("Could not create JSON: {}", err)))?;
+    writeln!(out, "{}", json_string)?;
+    Ok(())
+}
+
+/// Prints a `JsonLine` for each node while the tree is walked.
+struct JsonLinePrinter;
+
+impl JsonVisitor for JsonLinePrinter {
+    fn start_node(
+        &mut self,
+        cursor: &TreeCursorInfo,
+        node: JsonNode,
+        child_count: Option<usize>,
+    ) -> Result<(), Error> {
+        if cursor.is_hidden() {
+            return Ok(());
+        }
+        let json_line = get_json_line(cursor, &node.value, child_count);
+        let json_string = serde_json::to_string(&json_line)
+            .map_err(|err| Error::Syntax(format!("Could not create JSON: {}", err)))?;
+        writeln!(cursor.output(), "{}", json_string)?;
+        Ok(())
+    }
+
+    fn end_node(&mut self, _cursor: &TreeCursorInfo) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Print the document as newline delimited JSON, one `JsonLine` per node.
+///
+/// The lines are printed while the tree is walked, so large files start printing right away.
+pub fn print_ndjson_tree(
+    out: &mut dyn Write,
+    display_settings: &TreeDisplaySettings,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+) -> Result<(), Error> {
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, out);
+    walk_json_tree(display_settings, raw_doc, &cursor, &mut JsonLinePrinter)
+}
+
+/// The line of a node, without the nodes below it.
+fn get_json_line(
+    cursor: &TreeCursorInfo,
+    value: &JsonValue,
+    child_count: Option<usize>,
+) -> JsonLine {
+    let count = child_count.map(|child_count| serde_json::json!(child_count));
+    let (type_name, value) = match value {
+        JsonValue::Null => ("null", None),
+        JsonValue::Boolean { value } => ("boolean", Some(serde_json::json!(value))),
+        JsonValue::Integer { value } => ("integer", Some(serde_json::json!(value))),
+        JsonValue::Real { value } => ("real", Some(serde_json::json!(value))),
+        JsonValue::Name { value } => ("name", Some(serde_json::json!(value))),
+        JsonValue::String { value, .. } => ("string", Some(serde_json::json!(value))),
+        JsonValue::Array { .. } => ("array", count),
+        JsonValue::Dictionary { .. } => ("dictionary", count),
+        JsonValue::Stream { length, .. } => ("stream", Some(serde_json::json!(length))),
+        JsonValue::Operation { operator, operands } => (
+            "operation",
+            Some(serde_json::json!({ "operator": operator, "operands": operands })),
+        ),
+        JsonValue::Reference {
+            object_number,
+            generation,
+            ..
+        } => (
+            "reference",
+            Some(serde_json::json!(format!(
+                "({},{})",
+                object_number, generation
+            ))),
+        ),
+        JsonValue::Skipped { reason } => ("skipped", Some(serde_json::json!(reason))),
+    };
+    JsonLine {
+        path: cursor.get_path().join("."),
+        depth: cursor.get_depth_count(),
+        type_name: type_name.to_owned(),
+        value,
+    }
+}
+
+/// Walk the tree like `print_pdf_tree` does and give each node to `visitor`.
+///
+/// The same settings apply as for the printed tree, like `expand`, `max_depth` and `sort_keys`.
+pub fn walk_json_tree(
+    display_settings: &TreeDisplaySettings,
+    raw_doc: &Document,
+    cursor: &TreeCursorInfo,
+    visitor: &mut dyn JsonVisitor,
+) -> Result<(), Error> {
+    let mut cursor = cursor.clone();
+    let root_dict = if let Some(page_number) = display_settings.page {
+        let object_id = get_page_node(raw_doc, page_number)?.object_id;
+        cursor.add_parent_object_id(object_id);
+        raw_doc.get_dictionary(object_id)?
+    } else if let Some(tree_start) = display_settings.tree_start {
+        match get_tree_start_dictionary(raw_doc, tree_start) {
+            Ok((object_id, start_dict)) => {
+                if let Some(object_id) = object_id {
+                    cursor.add_parent_object_id(object_id);
+                }
+                start_dict
+            }
+            Err(err) => {
+                log::error!(
+                    "Could not find the start of the tree, printing from the trailer: {}",
+                    err
+                );
+                &raw_doc.trailer
+            }
+        }
+    } else {
+        &raw_doc.trailer
+    };
+    let root = JsonNode {
+        key: None,
+        value: JsonValue::Dictionary {
+            entries: Vec::new(),
+        },
+    };
+    cursor.check_timeout()?;
+    visitor.start_node(&cursor, root, Some(root_dict.len()))?;
+    walk_dictionary(display_settings, root_dict, raw_doc, &cursor, visitor)?;
+    visitor.end_node(&cursor)
+}
+
+/// Walk an object and the nodes below it, `cursor` already includes the key or index of `obj`.
+fn walk_node(
+    display_settings: &TreeDisplaySettings,
+    key: Option<String>,
+    obj: &Object,
+    raw_doc: &Document,
+    cursor: &TreeCursorInfo,
+    visitor: &mut dyn JsonVisitor,
+) -> Result<(), Error> {
+    let node = JsonNode {
+        key,
+        value: get_json_value(display_settings, obj),
+    };
+    cursor.check_timeout()?;
+    visitor.start_node(cursor, node, get_child_count(obj))?;
+    walk_content(display_settings, obj, raw_doc, cursor, visitor)?;
+    visitor.end_node(cursor)
+}
+
+/// Add a node for a part of the tree that is not walked, below the node of `cursor`.
+fn walk_skipped(
+    reason: &str,
+    cursor: &TreeCursorInfo,
+    visitor: &mut dyn JsonVisitor,
+) -> Result<(), Error> {
+    let cursor = cursor.add_depth(DepthInfo::default());
+    let node = JsonNode {
+        key: None,
+        value: JsonValue::Skipped {
+            reason: reason.to_owned(),
+        },
+    };
+    visitor.start_node(&cursor, node, None)?;
+    visitor.end_node(&cursor)
+}
+
+/// Walk the nodes below `obj`, like `print_pdf_object_content`.
+fn walk_content(
+    display_settings: &TreeDisplaySettings,
+    obj: &Object,
+    raw_doc: &Document,
+    cursor: &TreeCursorInfo,
+    visitor: &mut dyn JsonVisitor,
+) -> Result<(), Error> {
+    match obj {
+        Object::Array(_) | Object::Stream(_) if cursor.is_past_only_path(display_settings) => {}
+        Object::Array(list) => {
+            let next_expand_label = match cursor.next_expand_label(display_settings) {
+                Ok(label) => label,
+                Err(_) => {
+                    log::debug!("Took wrong path in tree somewhere.");
+                    return Ok(());
+                }
+            };
+            for (index, item) in list.iter().enumerate() {
+                if let Some(expand_label) = &next_expand_label {
+                    if expand_label != &index.to_string() {
+                        continue;
+                    }
+                }
+                let item_cursor = cursor.add_array_item_depth(index, false);
+                walk_node(display_settings, None, item, raw_doc, &item_cursor, visitor)?;
+            }
+        }
+        Object::Dictionary(dict) => {
+            walk_dictionary(display_settings, dict, raw_doc, cursor, visitor)?;
+        }
+        Object::Stream(stream) => {
+            walk_dictionary(display_settings, &stream.dict, raw_doc, cursor, visitor)?;
+            walk_operations(display_settings, stream, cursor, visitor)?;
+        }
+        Object::Reference(object_id) => {
+            let ref_obj = match raw_doc.objects.get(object_id) {
+                Some(ref_obj) => ref_obj,
+                None => return walk_skipped("missing_object", cursor, visitor),
+            };
+            if cursor.get_max_depth_count(display_settings) > display_settings.max_depth {
+                return walk_skipped("max_depth", cursor, visitor);
+            }
+            let mut new_cursor = cursor.add_depth(DepthInfo::default());
+            // References on the `expand` path do not count for `follow_refs_depth`.
+            let on_expand_path = display_settings
+                .expand
+                .as_ref()
+                .is_some_and(|expand| cursor.get_path().len() <= expand.len());
+            if !on_expand_path {
+                if display_settings
+                    .follow_refs_depth
+                    .is_some_and(|follow_refs_depth| {
+                        cursor.get_reference_depth() >= follow_refs_depth
+                    })
+                {
+                    return walk_skipped("follow_refs_depth", cursor, visitor);
+                }
+                new_cursor.add_reference_depth();
+            }
+            if !display_settings.display_parent
+                && !display_settings.no_cycle_guard
+                && cursor.check_parent_visited(object_id)
+            {
+                return walk_skipped("display_parent", cursor, visitor);
+            }
+            new_cursor.add_parent_object_id(*object_id);
+            walk_node(
+                display_settings,
+                None,
+                ref_obj,
+                raw_doc,
+                &new_cursor,
+                visitor,
+            )?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Walk the entries of a dictionary, like `print_pdf_dictionary`.
+fn walk_dictionary(
+    display_settings: &TreeDisplaySettings,
+    dict: &Dictionary,
+    raw_doc: &Document,
+    cursor: &TreeCursorInfo,
+    visitor: &mut dyn JsonVisitor,
+) -> Result<(), Error> {
+    if cursor.get_max_depth_count(display_settings) >= display_settings.max_depth {
+        if !dict.is_empty() {
+            walk_skipped("max_depth", cursor, visitor)?;
+        }
+        return Ok(());
+    }
+    if cursor.is_past_only_path(display_settings) {
+        return Ok(());
+    }
+    let next_expand_label = match cursor.next_expand_label(display_settings) {
+        Ok(label) => label,
+        Err(_) => {
+            log::debug!("Took wrong path in tree somewhere.");
+            return Ok(());
+        }
+    };
+    let mut entries: Vec<(String, &Object)> = dict
+        .iter()
+        .map(|(key, obj)| (String::from_utf8_lossy(key).to_string(), obj))
+        .collect();
+    if display_settings.sort_keys {
+        entries.sort_by(|(first, _), (second, _)| first.cmp(second));
+    }
+    for (key, obj) in entries {
+        if next_expand_label
+            .as_ref()
+            .is_some_and(|expand_label| expand_label != &key)
+        {
+            continue;
+        }
+        let new_cursor = cursor.add_depth(DepthInfo {
+            name: Some(key.clone()),
+            indent_line: false,
+        });
+        if !display_settings.display_font && key == "Font" {
+            let node = JsonNode {
+                key: Some(key),
+                value: get_json_value(display_settings, obj),
+            };
+            visitor.start_node(&new_cursor, node, get_child_count(obj))?;
+            walk_skipped("display_font", &new_cursor, visitor)?;
+            visitor.end_node(&new_cursor)?;
+            continue;
+        }
+        walk_node(
+            display_settings,
+            Some(key),
+            obj,
+            raw_doc,
+            &new_cursor,
+            visitor,
+        )?;
+    }
+    Ok(())
+}
+
+/// Walk the operations of a content stream, like `print_content_stream`.
+///
+/// Each operation is labeled with its index, so its path can be used with `expand`.
+fn walk_operations(
+    display_settings: &TreeDisplaySettings,
+    stream: &Stream,
+    cursor: &TreeCursorInfo,
+    visitor: &mut dyn JsonVisitor,
+) -> Result<(), Error> {
+    // Internal streams contain no operations, the `max_depth` node is added for the dictionary.
+    if display_settings.no_decompress
+        || stream.dict.type_is(b"XRef")
+        || stream.dict.type_is(b"ObjStm")
+        || !is_content_stream(display_settings, cursor)
+        || cursor.get_max_depth_count(display_settings) >= display_settings.max_depth
+    {
+        return Ok(());
+    }
+    // The path can end with the index of an operation, then only that operation is walked.
+    let expand_index = match cursor.next_expand_label(display_settings) {
+        Ok(Some(label)) => match label.parse::<usize>() {
+            Ok(expand_index) => Some(expand_index),
+            // A key of the stream dictionary.
+            Err(_) => return Ok(()),
+        },
+        Ok(None) => None,
+        Err(_) => return Ok(()),
+    };
+    let content = match decode_content_stream(stream) {
+        Ok(content) => content,
+        Err(err) => {
+            log::warn!("Could not decode content stream: {}", err);
+            return walk_skipped("decode_failure", cursor, visitor);
+        }
+    };
+    for (index, operation) in content.operations.iter().enumerate() {
+        if expand_index.is_some_and(|expand_index| expand_index != index) {
+            continue;
+        }
+        if let Some(operator_filter) = &display_settings.operator_filter {
+            if !operator_filter.contains(&operation.operator) {
+                continue;
+            }
+        }
+        let operation_cursor = cursor.add_array_item_depth(index, false);
+        let node = JsonNode {
+            key: None,
+            value: JsonValue::Operation {
+                operator: operation.operator.clone(),
+                operands: operation
+                    .operands
+                    .iter()
+                    .map(|operand| JsonNode {
+                        key: None,
+                        value: get_direct_json_value(display_settings, operand),
+                    })
+                    .collect(),
+            },
+        };
+        operation_cursor.check_timeout()?;
+        visitor.start_node(&operation_cursor, node, None)?;
+        visitor.end_node(&operation_cursor)?;
+    }
+    Ok(())
+}
+
+/// Convert an object to JSON without following references.
+pub fn get_unresolved_json_value(obj: &Object) -> JsonValue {
+    get_direct_json_value(&TreeDisplaySettings::default(), obj)
+}
+
+/// Convert an object to JSON with its items and entries, without following references.
+fn get_direct_json_value(display_settings: &TreeDisplaySettings, obj: &Object) -> JsonValue {
+    let get_entries = |dict: &Dictionary| {
+        dict.iter()
+            .map(|(key, value)| JsonNode {
+                key: Some(String::from_utf8_lossy(key).to_string()),
+                value: get_direct_json_value(display_settings, value),
+            })
+            .collect()
+    };
+    match (get_json_value(display_settings, obj), obj) {
+        (JsonValue::Array { .. }, Object::Array(list)) => JsonValue::Array {
+            items: list
+                .iter()
+                .map(|item| JsonNode {
+                    key: None,
+                    value: get_direct_json_value(display_settings, item),
+                })
+                .collect(),
+        },
+        (JsonValue::Dictionary { .. }, Object::Dictionary(dict)) => JsonValue::Dictionary {
+            entries: get_entries(dict),
+        },
+        (JsonValue::Stream { length, .. }, Object::Stream(stream)) => JsonValue::Stream {
+            entries: get_entries(&stream.dict),
+            length,
+            operations: Vec::new(),
+        },
+        (value, _) => value,
+    }
+}
+
+/// Convert an object to JSON, arrays and dictionaries without their items and entries.
+fn get_json_value(display_settings: &TreeDisplaySettings, obj: &Object) -> JsonValue {
+    match obj {
+        Object::Null => JsonValue::Null,
+        Object::Boolean(value) => JsonValue::Boolean { value: *value },
+        Object::Integer(value) => JsonValue::Integer { value: *value },
+        Object::Real(value) => JsonValue::Real { value: *value },
+        Object::Name(name) => JsonValue::Name {
+            value: String::from_utf8_lossy(name).to_string(),
+        },
+        Object::String(value, format) => JsonValue::String {
+            value: if display_settings.redact_strings {
+                get_redacted_string(value)
+            } else {
+                decode_text_string(value)
+            },
+            format: match format {
+                StringFormat::Literal => JsonStringFormat::Literal,
+                StringFormat::Hexadecimal => JsonStringFormat::Hexadecimal,
+            },
+        },
+        Object::Array(_) => JsonValue::Array { items: Vec::new() },
+        Object::Dictionary(_) => JsonValue::Dictionary {
+            entries: Vec::new(),
+        },
+        Object::Stream(stream) => JsonValue::Stream {
+            entries: Vec::new(),
+            length: stream.content.len(),
+            operations: Vec::new(),
+        },
+        Object::Reference(object_id) => JsonValue::Reference {
+            object_number: object_id.0,
+            generation: object_id.1,
+            target: None,
+        },
+    }
+}
+
+/// The amount of items or entries of arrays, dictionaries and streams.
+fn get_child_count(obj: &Object) -> Option<usize> {
+    match obj {
+        Object::Array(list) => Some(list.len()),
+        Object::Dictionary(dict) => Some(dict.len()),
+        Object::Stream(stream) => Some(stream.dict.len()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_doc::create_sample_document;
+
+    fn get_sample_json_document(display_settings: &TreeDisplaySettings) -> JsonDocument {
+        let raw_doc = create_sample_document().unwrap();
+        get_json_document(
+            display_settings,
+            &TreeCursorSettings::default(),
+            &raw_doc,
+            "sample.pdf".to_owned(),
+        )
+        .unwrap()
+    }
+
+    fn get_entry<'a>(node: &'a JsonNode, key: &str) -> &'a JsonNode {
+        let entries = match &node.value {
+            JsonValue::Dictionary { entries } | JsonValue::Stream { entries, .. } => entries,
+            JsonValue::Reference {
+                target: Some(target),
+                ..
+            } => return get_entry(target, key),
+            value => panic!("Not a dictionary: {:?}", value),
+        };
+        entries
+            .iter()
+            .find(|entry| entry.key.as_deref() == Some(key))
+            .unwrap_or_else(|| panic!("No entry `{}` in {:?}", key, entries))
+    }
+
+    #[test]
+    fn json_document_round_trip() {
+        let json_document = get_sample_json_document(&TreeDisplaySettings::default());
+        let json_string = serde_json::to_string(&json_document).unwrap();
+        let parsed: JsonDocument = serde_json::from_str(&json_string).unwrap();
+        assert_eq!(parsed, json_document);
+    }
+
+    #[test]
+    fn json_value_round_trip() {
+        let values = vec![
+            JsonValue::Null,
+            JsonValue::Boolean { value: true },
+            JsonValue::Integer { value: -3 },
+            JsonValue::Real { value: 0.5 },
+            JsonValue::Name {
+                value: "Page".to_owned(),
+            },
+            JsonValue::String {
+                value: "Hello".to_owned(),
+                format: JsonStringFormat::Hexadecimal,
+            },
+            JsonValue::Reference {
+                object_number: 4,
+                generation: 0,
+                target: Some(Box::new(JsonNode {
+                    key: None,
+                    value: JsonValue::Skipped {
+                        reason: "max_depth".to_owned(),
+                    },
+                })),
+            },
+            JsonValue::Stream {
+                entries: vec![JsonNode {
+                    key: Some("Length".to_owned()),
+                    value: JsonValue::Integer { value: 12 },
+                }],
+                length: 12,
+                operations: vec![JsonNode {
+                    key: None,
+                    value: JsonValue::Operation {
+                        operator: "Tf".to_owned(),
+                        operands: vec![
+                            JsonNode {
+                                key: None,
+                                value: JsonValue::Name {
+                                    value: "F1".to_owned(),
+                                },
+                            },
+                            JsonNode {
+                                key: None,
+                                value: JsonValue::Integer { value: 24 },
+                            },
+                        ],
+                    },
+                }],
+            },
+        ];
+        for value in values {
+            let json_string = serde_json::to_string(&value).unwrap();
+            let parsed: JsonValue = serde_json::from_str(&json_string).unwrap();
+            assert_eq!(parsed, value, "{}", json_string);
+        }
+    }
+
+    #[test]
+    fn json_line_round_trip() {
+        let json_line = JsonLine {
+            path: "Root.Pages.Kids.0".to_owned(),
+            depth: 5,
+            type_name: "reference".to_owned(),
+            value: Some(serde_json::json!("(3,0)")),
+        };
+        let json_string = serde_json::to_string(&json_line).unwrap();
+        assert!(
+            json_string.contains(r#""type":"reference""#),
+            "{}",
+            json_string
+        );
+        let parsed: JsonLine = serde_json::from_str(&json_string).unwrap();
+        assert_eq!(parsed, json_line);
+    }
+
+    #[test]
+    fn json_follows_expand_path() {
+        let display_settings = TreeDisplaySettings {
+            expand: Some(vec!["Root".to_owned(), "Pages".to_owned()]),
+            only_path: true,
+            ..Default::default()
+        };
+        let json_document = get_sample_json_document(&display_settings);
+        let root_entries = match &json_document.root.value {
+            JsonValue::Dictionary { entries } => entries,
+            value => panic!("Not a dictionary: {:?}", value),
+        };
+        assert_eq!(root_entries.len(), 1);
+        let pages = get_entry(get_entry(&json_document.root, "Root"), "Pages");
+        // With `only_path` the entries of the last item are not included.
+        assert_eq!(
+            pages.value,
+            JsonValue::Reference {
+                object_number: 1,
+                generation: 0,
+                target: Some(Box::new(JsonNode {
+                    key: None,
+                    value: JsonValue::Dictionary {
+                        entries: Vec::new()
+                    },
+                })),
+            }
+        );
+    }
+
+    #[test]
+    fn ndjson_paths_can_be_used_with_expand() {
+        let raw_doc = create_sample_document().unwrap();
+        let mut output = Vec::new();
+        print_ndjson_tree(
+            &mut output,
+            &TreeDisplaySettings::default(),
+            &TreeCursorSettings::default(),
+            &raw_doc,
+        )
+        .unwrap();
+        let json_lines: Vec<JsonLine> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let kid = json_lines
+            .iter()
+            .find(|json_line| json_line.path == "Root.Pages.Kids.0")
+            .unwrap();
+        assert_eq!(kid.type_name, "reference");
+
+        let display_settings = TreeDisplaySettings {
+            expand: Some(kid.path.split('.').map(str::to_owned).collect()),
+            ..Default::default()
+        };
+        let json_document = get_sample_json_document(&display_settings);
+        let kids = get_entry(
+            get_entry(get_entry(&json_document.root, "Root"), "Pages"),
+            "Kids",
+        );
+        assert!(
+            matches!(&kids.value, JsonValue::Array { items } if items.len() == 1),
+            "{:?}",
+            kids
+        );
+    }
+
+    #[test]
+    fn ndjson_operation_paths_can_be_used_with_expand() {
+        let raw_doc = create_sample_document().unwrap();
+        let mut output = Vec::new();
+        print_ndjson_tree(
+            &mut output,
+            &TreeDisplaySettings::default(),
+            &TreeCursorSettings::default(),
+            &raw_doc,
+        )
+        .unwrap();
+        let json_lines: Vec<JsonLine> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        // The `Td` operation, after `BT` and `Tf`.
+        let operation = json_lines
+            .iter()
+            .find(|json_line| json_line.path == "Root.Pages.Kids.0.Contents.2")
+            .unwrap();
+        assert_eq!(operation.type_name, "operation");
+        assert_eq!(
+            operation.value,
+            Some(serde_json::json!({
+                "operator": "Td",
+                "operands": [
+                    { "type": "integer", "value": 72 },
+                    { "type": "integer", "value": 720 },
+                ],
+            }))
+        );
+
+        let display_settings = TreeDisplaySettings {
+            expand: Some(operation.path.split('.').map(str::to_owned).collect()),
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        crate::print_tree::print_pdf_tree(
+            &mut output,
+            &display_settings,
+            &TreeCursorSettings::default(),
+            &raw_doc,
+            "sample.pdf".to_owned(),
+        )
+        .unwrap();
+        let tree = String::from_utf8(output).unwrap();
+        // Only the `Td` operation is printed, not the text of the `Tj` operation.
+        assert!(tree.contains("Tx: "), "{}", tree);
+        assert!(!tree.contains("PDF Structure Viewer"), "{}", tree);
+
+        let json_document = get_sample_json_document(&display_settings);
+        let kids = get_entry(
+            get_entry(get_entry(&json_document.root, "Root"), "Pages"),
+            "Kids",
+        );
+        let page = match &kids.value {
+            JsonValue::Array { items } => &items[0],
+            value => panic!("Not an array: {:?}", value),
+        };
+        let contents = get_entry(page, "Contents");
+        let operations = match &contents.value {
+            JsonValue::Reference {
+                target: Some(target),
+                ..
+            } => match &target.value {
+                JsonValue::Stream { operations, .. } => operations,
+                value => panic!("Not a stream: {:?}", value),
+            },
+            value => panic!("Not a reference: {:?}", value),
+        };
+        assert_eq!(operations.len(), 1);
+        assert!(
+            matches!(&operations[0].value, JsonValue::Operation { operator, .. } if operator == "Td"),
+            "{:?}",
+            operations
+        );
+    }
+
+    #[test]
+    fn json_stops_at_timeout() {
+        let raw_doc = create_sample_document().unwrap();
+        let tree_cursor_settings = TreeCursorSettings {
+            timeout: Some(std::time::Duration::ZERO),
+            ..Default::default()
+        };
+        let result = get_json_document(
+            &TreeDisplaySettings::default(),
+            &tree_cursor_settings,
+            &raw_doc,
+            "sample.pdf".to_owned(),
+        );
+        assert!(
+            matches!(&result, Err(Error::IO(err)) if err.kind() == std::io::ErrorKind::TimedOut),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn json_decodes_text_strings() {
+        // `Hi` as UTF-16BE with byte order mark.
+        let obj = Object::String(
+            vec![0xFE, 0xFF, 0, b'H', 0, b'i'],
+            StringFormat::Hexadecimal,
+        );
+        assert_eq!(
+            get_unresolved_json_value(&obj),
+            JsonValue::String {
+                value: "Hi".to_owned(),
+                format: JsonStringFormat::Hexadecimal,
+            }
+        );
+    }
+}
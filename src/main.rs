@@ -1,21 +1,52 @@
 #![forbid(unsafe_code)]
 #![deny(clippy::all)]
 
+mod decryption;
+mod destinations;
+mod encryption;
+mod exit_reason;
+mod export_stream;
+mod extract_images;
+mod extract_text;
+mod json_tree;
+mod markdown_tree;
+mod object_hashes;
+mod object_offsets;
+mod object_stream_dump;
+mod page_tree;
+mod pager;
+mod print_attachments;
+mod print_fonts;
+mod print_object_sizes;
+mod print_overview;
 mod print_pdf_info;
 mod print_tree;
+mod references;
+mod revision_diff;
+mod sample_doc;
 mod simple_logger;
+mod stats;
+mod validate;
 
+use decryption::DecryptionError;
+use exit_reason::ExitReason;
 use log::LevelFilter;
-use lopdf::Document;
-use print_tree::{TreeCursorSettings, TreeDisplaySettings};
+use lopdf::{Document, ObjectId};
+use print_tree::{
+    get_value_formatter, SearchSettings, TreeChars, TreeCursorSettings, TreeDisplaySettings,
+    ValueFormatter, FORMATTABLE_TYPE_NAMES,
+};
 use std::{
-    io::{Error, ErrorKind},
-    path::PathBuf,
+    fs::File,
+    io::{BufWriter, Error, ErrorKind, IsTerminal, Write},
+    path::{Path, PathBuf},
     str::FromStr,
+    time::{Duration, SystemTime},
 };
 use structopt::StructOpt;
+use yansi::Paint;
 
-#[derive(Debug, StructOpt)]
+#[derive(Debug, Clone, StructOpt)]
 #[structopt(
     name = "pdf_structure_viewer",
     about = "Inspect how the PDF's structure looks."
@@ -29,18 +60,95 @@ struct Opts {
     #[structopt(short, long, parse(from_occurrences))]
     verbose: u8,
 
+    /// Send the output to a pager (`$PAGER` or `less`), keeping the colors.
+    ///
+    /// Warnings and errors are printed after the pager is closed.
+    #[structopt(long)]
+    pager: bool,
+
+    /// Print again each time the input file changes, until stopped with Ctrl-C.
+    ///
+    /// The file is checked for changes every half second.
+    #[structopt(long, conflicts_with = "pager")]
+    watch: bool,
+
+    /// Write the output to this file instead of stdout.
+    ///
+    /// Colors are then disabled, unless `--color always` is used.
+    #[structopt(long, parse(from_os_str), conflicts_with = "pager")]
+    output: Option<PathBuf>,
+
+    /// Print without colors and other styling.
+    ///
+    /// Colors are also disabled when the output is not a terminal,
+    /// set `CLICOLOR_FORCE=1` to keep them.
+    #[structopt(long)]
+    no_color: bool,
+
+    /// When to print colors: `auto`, `always` or `never`.
+    ///
+    /// With `auto` colors are only printed when the output is a terminal.
+    /// Default: `auto`.
+    #[structopt(long, conflicts_with = "no-color")]
+    color: Option<ColorMode>,
+
+    /// Draw the tree connectors and boxes with ASCII characters (`|-`, `` `- ``, `+`).
+    ///
+    /// For terminals and fonts without box-drawing characters.
+    #[structopt(long)]
+    ascii: bool,
+
+    /// Skip this amount of bytes at the start of the input file.
+    ///
+    /// Useful when the PDF is embedded in another file, the PDF should start at this offset.
+    #[structopt(long)]
+    input_offset: Option<usize>,
+
+    /// Password to decrypt an encrypted document, the user or the owner password.
+    ///
+    /// Without it the empty user password is tried. Only RC4 encryption can be decrypted.
+    #[structopt(long)]
+    password: Option<String>,
+
     /// Input file
+    ///
+    /// Required for all commands except `sample-doc`.
     #[structopt(parse(from_os_str))]
-    input: PathBuf,
+    input: Option<PathBuf>,
 
     #[structopt(subcommand)]
     cmd: Command,
 }
 
 #[derive(Debug, Clone, StructOpt)]
+// Only created once, so the size of the `Tree` variant does not matter.
+#[allow(clippy::large_enum_variant)]
 enum Command {
     /// Print general info about the PDF.
-    Info,
+    Info {
+        /// Print the info as JSON, for scripts.
+        #[structopt(long)]
+        json: bool,
+    },
+    /// Print the most important facts about the PDF in a small overview.
+    Overview,
+    /// Print how many values of each type the PDF contains, with the amount of pages
+    /// and the size of the streams.
+    Stats {
+        /// Also add up the size of the streams after decoding them, this can be slow.
+        #[structopt(long)]
+        decoded_stream_bytes: bool,
+
+        /// Also count how often each operator is used in the content streams.
+        ///
+        /// This decodes every content stream, which can be slow.
+        #[structopt(long)]
+        operator_counts: bool,
+
+        /// Print the stats as JSON, for scripts.
+        #[structopt(long)]
+        json: bool,
+    },
     /// Print the structure of the PDF in a tree structure.
     Tree {
         /// How deep the tree should be printed.
@@ -49,13 +157,37 @@ enum Command {
         #[structopt(long)]
         max_depth: Option<usize>,
 
+        /// Do not print the items less deep than this, their children are still printed.
+        ///
+        /// The hidden levels are collapsed, so the first printed level starts at the left margin.
+        /// Useful to skip the trailer and catalog when looking at deeper structures.
+        #[structopt(long)]
+        min_depth: Option<usize>,
+
+        /// How many references to follow after the `expand` path, regardless of `max-depth`.
+        ///
+        /// Following references can make the tree explode even when it is not deep,
+        /// the referenced objects are then not printed.
+        #[structopt(long)]
+        follow_refs_depth: Option<usize>,
+
         /// Print tree, but only expend from this node.
         ///
-        /// Each item should be separated by a dot (`.`)
-        /// Example: `Root.Pages.Kids`
+        /// Each item should be separated by a dot (`.`), array items are selected by index.
+        /// Example: `Root.Pages.Kids.0.Contents`
         #[structopt(short, long)]
         expand: Option<String>,
 
+        /// Count `max-depth` from the end of the `expand` path instead of from the trailer.
+        #[structopt(long, requires = "expand")]
+        relative_depth: bool,
+
+        /// Only print the items on the `expand` path, like a breadcrumb.
+        ///
+        /// The item at the end of the path is printed without its children.
+        #[structopt(long, requires = "expand")]
+        only_path: bool,
+
         /// Add type names after the property name for more info.
         ///
         /// Printing the type names is disabled by default to reduce clutter.
@@ -79,6 +211,27 @@ enum Command {
         #[structopt(long)]
         hex_display_limit: Option<usize>,
 
+        /// Cut off literal strings and names after this many characters.
+        ///
+        /// Using a value of `0` (the default) will not cut off values.
+        /// Hexadecimal strings use `hex_display_limit` instead.
+        #[structopt(long)]
+        max_value_len: Option<usize>,
+
+        /// Print arrays of up to this many numbers on one line, like `[0 0 595 842]`.
+        ///
+        /// Useful for bounding boxes, rectangles and matrices.
+        /// Default: arrays are always printed with one item per line.
+        #[structopt(long)]
+        inline_number_arrays: Option<usize>,
+
+        /// Limit how deep nested arrays and dictionaries in content stream operands are printed.
+        ///
+        /// Deeper items are replaced with `[...]` or `{...}`.
+        /// Default: `8`.
+        #[structopt(long)]
+        max_operand_array_depth: Option<usize>,
+
         /// Continue expanding the tree after a `Font` items is found.
         ///
         /// Printing font data is disabled by default to reduce clutter.
@@ -91,10 +244,111 @@ enum Command {
         #[structopt(long)]
         display_parent: bool,
 
+        /// Do not stop expanding references that were already visited higher up in the tree.
+        ///
+        /// Only `max-depth` will limit how deep the tree is printed.
+        /// Useful when objects are intentionally shared and should be shown in full.
+        #[structopt(long)]
+        no_cycle_guard: bool,
+
+        /// Replace the content of all strings with their length.
+        ///
+        /// Useful to share the structure of a PDF without sharing its text.
+        /// Names and numbers are still shown.
+        #[structopt(long)]
+        redact_strings: bool,
+
+        /// Print literal strings as the stored bytes, bytes that are not printable ASCII
+        /// are escaped like `\xFE`.
+        ///
+        /// Strings starting with the UTF-16BE byte order mark still show the decoded text.
+        #[structopt(long)]
+        raw_string_bytes: bool,
+
+        /// Use a compact marker (`…+3`) for items that are not printed.
+        ///
+        /// By default this is `...skipped 3 items...`.
+        #[structopt(long)]
+        short_skip_marker: bool,
+
+        /// Mark objects that are followed through a reference with `(via reference)`
+        /// and dictionaries and arrays that are stored directly in their parent with `(direct)`.
+        ///
+        /// Useful to see which objects are shared and which can be inlined.
+        #[structopt(long)]
+        mark_indirect: bool,
+
+        /// Only print the keys of the first dictionary (after `expand`) with their amount of children.
+        ///
+        /// Gives a quick map of a part of the document, to decide what to `expand` next.
+        #[structopt(long)]
+        overview_tree: bool,
+
+        /// Print the entries of dictionaries sorted by key instead of in the order of the file.
+        ///
+        /// Makes it easier to compare the output for two files or find a key.
+        #[structopt(long)]
+        sort_keys: bool,
+
+        /// Print a chain of dictionaries that each have a single entry on one line.
+        ///
+        /// Like `Root > Pages > Kids`, the chain ends at arrays and streams.
+        #[structopt(long)]
+        collapse_single_child: bool,
+
+        /// Show the value of references to simple values (like an indirect `Length`) directly.
+        ///
+        /// This is done for booleans, numbers, names and strings.
+        #[structopt(long)]
+        resolve_scalars: bool,
+
+        /// Add the width and height of page boxes like `MediaBox` and `CropBox`.
+        ///
+        /// Sizes are printed in points and millimeters, common paper sizes are recognized.
+        #[structopt(long)]
+        display_box_sizes: bool,
+
+        /// Start the tree at the document catalog (`Root`) instead of the trailer.
+        ///
+        /// The `expand` path is then relative to the catalog. Same as `--from catalog`.
+        #[structopt(long, conflicts_with_all = &["from", "root-ref"])]
+        from_catalog: bool,
+
+        /// Start the tree at a dictionary the trailer points to, instead of the trailer.
+        ///
+        /// Options:
+        /// `catalog`|`root`: The document catalog,
+        /// `info`: The document information dictionary,
+        /// `pages`: The root of the page tree.
+        ///
+        /// The `expand` path is then relative to this dictionary.
+        #[structopt(long, conflicts_with = "root-ref")]
+        from: Option<TreeStart>,
+
+        /// Start the tree at this object, like `12` or `12,0`, instead of the trailer.
+        ///
+        /// The object has to be a dictionary, the `expand` path is then relative to it.
+        #[structopt(long, parse(try_from_str = parse_object_id))]
+        root_ref: Option<ObjectId>,
+
+        /// Start the tree at the `Page` dictionary of this page, the first page is 1.
+        ///
+        /// The `expand` path is then relative to the page.
+        /// Attributes the page inherits from the page tree are listed on top.
+        #[structopt(
+            long,
+            conflicts_with_all = &["from-catalog", "from", "root-ref", "since-offset", "only-streams"]
+        )]
+        page: Option<usize>,
+
         /// Do not print the legend on top of the output.
         #[structopt(long)]
         hide_legend: bool,
 
+        /// Print the legend on one line, like `Nu=null b=bool Z=int ...`.
+        #[structopt(long, conflicts_with = "hide-legend")]
+        compact_legend: bool,
+
         /// When added streams will be displayed.
         ///
         /// Options:
@@ -118,16 +372,129 @@ enum Command {
         #[structopt(long)]
         stream_enhanced_operator_info: bool,
 
+        /// Print all operations of a content stream on the same level.
+        ///
+        /// By default operations between `q` and `Q`, `BT` and `ET` and marked content
+        /// (`BMC`/`BDC` and `EMC`) are printed one level deeper.
+        #[structopt(long)]
+        no_stream_nesting: bool,
+
+        /// Also print the raw operands after each operator in content streams.
+        ///
+        /// Can be combined with `stream_enhanced_operator_info` to compare the
+        /// labeled arguments with the values stored in the stream.
+        #[structopt(long, conflicts_with = "stream-raw-operations")]
+        show_raw_operands: bool,
+
         /// Force the decoding of streams even if no content stream is expected.
         ///
         /// This might display incorrect results.
         #[structopt(long)]
         force_stream_decoding: bool,
 
+        /// Also decode streams stored under this key as content streams.
+        ///
+        /// Can be used multiple times. Streams under `Contents`, `N`, `R`, `D` and `AP`
+        /// are always decoded. Example: `--decode-stream-key Stream`
+        #[structopt(long, number_of_values = 1)]
+        decode_stream_key: Vec<String>,
+
+        /// Do not decompress streams, show the bytes as stored in the file.
+        ///
+        /// Use with `display-stream hex` to inspect the compressed data.
+        /// Content streams are not decoded in this mode.
+        #[structopt(long)]
+        no_decompress: bool,
+
+        /// Collapse runs of the same operator in content streams.
+        ///
+        /// Only the first and last operation of a run of 3 or more are printed,
+        /// the rest is replaced by a line with the count (like `re ×340`).
+        #[structopt(long)]
+        collapse_repeats: bool,
+
+        /// Print the index of each operation in content streams, like `#340`.
+        ///
+        /// The index can be used at the end of the `expand` path to print only that operation,
+        /// like `Root.Pages.Kids.Contents.340`.
+        #[structopt(long)]
+        operation_indices: bool,
+
+        /// Only print these operators in content streams.
+        ///
+        /// Operators should be separated by a comma (`,`).
+        /// Example: `rg,RG,k,K,sc,scn`
+        /// The amount of operations that are not printed is shown instead.
+        /// Can also be given as `--grep`.
+        #[structopt(long, alias = "grep")]
+        operator_filter: Option<String>,
+
+        /// Highlight these dictionary keys, everything is still printed.
+        ///
+        /// Keys should be separated by a comma (`,`), without the `/`.
+        /// Example: `MediaBox,Font`
+        #[structopt(long)]
+        highlight: Option<String>,
+
+        /// Print the value of a type with another formatter, like `Name=hex`.
+        ///
+        /// Can be used multiple times. Types: `Integer_Number`, `Real_Number`, `Name`,
+        /// `Literal_String` and `Hexadecimal_String`.
+        /// Formatters: `hex` (bytes in hexadecimal), `text` (decode as text string,
+        /// UTF-16BE or UTF-8) and `debug` (the internal representation).
+        #[structopt(long, number_of_values = 1, parse(try_from_str = parse_value_format))]
+        value_format: Vec<(String, ValueFormatter)>,
+
+        /// Add the fill and stroke color in effect to text and path painting operations.
+        ///
+        /// The colors are tracked through the color operators (`rg`, `k`, `cs`, `scn`,...)
+        /// and saved/restored with `q` and `Q`.
+        #[structopt(long)]
+        track_colors: bool,
+
+        /// Add the position on the page (in user space) to text showing operations.
+        ///
+        /// The position is tracked through `cm`, `q`/`Q` and the text positioning operators.
+        /// Glyph widths are not used, so text shown after other text on the same line
+        /// gets the position where that line started.
+        #[structopt(long)]
+        text_positions: bool,
+
+        /// Show a short summary of objects referenced by operands in content streams.
+        ///
+        /// This includes indirect references and names defined in the `Resources` (like `/Im1 Do`).
+        /// Requires `stream_raw_operations` not to be enabled.
+        #[structopt(long)]
+        inline_stream_refs: bool,
+
         /// Print line numbers.
+        ///
+        /// The amount of lines is printed after the tree.
         #[structopt(long)]
         print_line_numbers: bool,
 
+        /// Replace the outer levels of the indentation with their count (like `·20·`)
+        /// when the indentation would take up more than half of the terminal width.
+        ///
+        /// The terminal width is read from `COLUMNS`, or 80 if it is not set.
+        #[structopt(long)]
+        adaptive_indent: bool,
+
+        /// Give the indentation lines of each level another color.
+        ///
+        /// Makes it easier to see which line belongs to which parent in deep trees.
+        /// Without colors (like with `no-color`) the lines look the same as before.
+        #[structopt(long)]
+        depth_guides: bool,
+
+        /// Cut off lines longer than this width with `…`, `0` does not cut off lines.
+        ///
+        /// Default: the terminal width when printing to a terminal, otherwise lines are not cut off.
+        /// The terminal width is read from `COLUMNS`, or 80 if it is not set.
+        /// Hexadecimal strings are then shown as far as they fit, unless `hex_display_limit` is set.
+        #[structopt(long)]
+        width: Option<usize>,
+
         /// The minimum amount of character the line will be padded to.
         ///
         /// Default is 4, so `   1` until `9999`.
@@ -135,14 +502,233 @@ enum Command {
         /// When the line number exceeds the padding width the number will just extend the margin.
         #[structopt(long)]
         line_number_padding_width: Option<u8>,
+
+        /// The number of the first line, when printing line numbers.
+        ///
+        /// Default is 1. Wider numbers extend the margin, like with `line-number-padding-width`.
+        #[structopt(long, requires = "print-line-numbers")]
+        line_number_start: Option<u64>,
+
+        /// Only print the objects stored after this byte offset in the file.
+        ///
+        /// Useful to inspect what an incremental update appended to the original file,
+        /// use the offset right after the original `%%EOF`.
+        /// Objects inside object streams use the offset of the object stream.
+        #[structopt(long)]
+        since_offset: Option<usize>,
+
+        /// Only list the streams in the document with their path, kind and length.
+        ///
+        /// Use `force-stream-decoding` to also print the decoded content of each stream.
+        #[structopt(long, conflicts_with = "since-offset")]
+        only_streams: bool,
+
+        /// Output format of the tree.
+        ///
+        /// Options:
+        /// `tree`: (default) Print a tree for humans,
+        /// `json`: Print the tree as JSON, see `json_tree.rs` for the format,
+        /// `ndjson`: Print one JSON object per node on each line,
+        /// `markdown`: Print the tree as a Markdown nested list.
+        #[structopt(long)]
+        format: Option<OutputFormat>,
+
+        /// Stop printing after this amount of seconds and exit with an error.
+        ///
+        /// Useful to bound the runtime when processing untrusted files.
+        #[structopt(long)]
+        timeout: Option<u64>,
     },
     /// Print the internal structure of the PDF.
     /// This is similar to how the PDF is stored in the file.
     Structure,
+    /// Print the page tree with the `Count` of each node.
+    ///
+    /// For each page shows if `Resources`, `MediaBox`, `CropBox` and `Rotate`
+    /// are defined on the page itself or inherited from an ancestor.
+    PageTree,
+    /// Check the structure of the document and print the problems found.
+    ///
+    /// Checks the page tree (`Count`, `Kids` and `MediaBox`), the `Length` of streams,
+    /// references to missing objects and object ids that are defined more than once.
+    /// Exits with an error when errors are found.
+    Validate,
+    /// Print the outline (bookmarks) with the page each item points to.
+    ///
+    /// Named destinations are looked up in the `Dests` name tree.
+    Outline,
+    /// Print the paths from the trailer to an object.
+    ///
+    /// Shows why an object is in the document, shortest paths first.
+    Parents {
+        /// The object to find, like `12` or `12,0`.
+        #[structopt(parse(try_from_str = parse_object_id))]
+        object_id: ObjectId,
+
+        /// The maximum amount of paths to print.
+        ///
+        /// Default: 10
+        #[structopt(long)]
+        limit: Option<usize>,
+    },
+    /// Print all known content stream operators with their arguments.
+    ///
+    /// The input file is not used.
+    Operators,
+    /// Print the objects in the decoded content of an object stream.
+    ///
+    /// Useful for debugging corrupt object streams. The input file is not used.
+    ParseObjStm {
+        /// File with the decoded (decompressed) content of the object stream.
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+
+        /// Offset of the first object (the `First` value of the stream dictionary).
+        ///
+        /// Default: the end of the list of numbers at the start.
+        #[structopt(long)]
+        first: Option<usize>,
+    },
+    /// Write a small sample PDF to experiment with.
+    #[structopt(setting = structopt::clap::AppSettings::Hidden)]
+    SampleDoc {
+        /// Where to write the PDF.
+        #[structopt(parse(from_os_str))]
+        output: PathBuf,
+    },
+    /// Print the objects used by pages that take up the most space.
+    ///
+    /// The size includes the object with its stream and all objects it references,
+    /// directly or indirectly.
+    Sizes {
+        /// The amount of objects to print.
+        ///
+        /// Default: 10
+        #[structopt(long)]
+        limit: Option<usize>,
+    },
+    /// Print all fonts with their encoding and if they have a `ToUnicode` CMap.
+    ///
+    /// Fonts without either explain most garbled text when extracting text.
+    Fonts,
+    /// Print the embedded files (attachments) with their size and MIME type.
+    Attachments {
+        /// Write the embedded files to this directory.
+        #[structopt(long, parse(from_os_str))]
+        extract_dir: Option<PathBuf>,
+    },
+    /// Find objects by the MD5 hash of their content.
+    ///
+    /// The hash does not depend on object ids, so objects can be matched between files.
+    /// Without a hash, the hash of every object is printed.
+    FindByHash {
+        /// The hash or the start of it, in hexadecimal.
+        hash: Option<String>,
+    },
+    /// Write the decoded content of a stream to a file.
+    ///
+    /// Useful for fonts, embedded files and images. When a filter can not be decoded,
+    /// the stream is written as stored in the file.
+    ExportStream {
+        /// The stream object, like `12` or `12,0`.
+        #[structopt(parse(try_from_str = parse_object_id))]
+        object_id: ObjectId,
+
+        /// The file to write to, `-` writes to stdout.
+        #[structopt(long, parse(from_os_str))]
+        export_to: PathBuf,
+    },
+    /// Write all images to a directory, named by their object id.
+    ///
+    /// JPEG images are written unchanged, other images are converted to PNG when
+    /// possible, otherwise the samples are written with a `.json` file describing them.
+    ExtractImages {
+        /// The directory to write the images to.
+        #[structopt(parse(from_os_str))]
+        output_dir: PathBuf,
+    },
+    /// Print the text of the pages as plain text, in the order of the content streams.
+    ///
+    /// Spaces and line breaks are guessed from the text positioning operators.
+    /// Fonts are not used, so text with other encodings than Latin-1 or UTF-16BE is garbled.
+    Text {
+        /// Only print the text of this page, the first page is 1.
+        #[structopt(long)]
+        page: Option<usize>,
+    },
+    /// Print which objects were added, changed or removed between two revisions.
+    ///
+    /// Each `%%EOF` marker ends a revision, the original file is revision 0
+    /// and every incremental update adds a revision.
+    RevisionDiff {
+        /// The revision to compare from, starting at 0.
+        from: usize,
+
+        /// The revision to compare to.
+        to: usize,
+    },
+    /// Print where the structure of another PDF differs from the input, starting at the trailer.
+    ///
+    /// Added keys are marked with `+`, removed keys with `-` and changed values with `~`.
+    /// References are followed, so objects are compared by their content instead of their id.
+    Diff {
+        /// The PDF to compare with.
+        ///
+        /// It is loaded like the input file, with the same `input-offset` and `password`.
+        #[structopt(parse(from_os_str))]
+        other: PathBuf,
+
+        /// How deep the documents should be compared.
+        ///
+        /// Default: 20
+        #[structopt(long)]
+        max_depth: Option<usize>,
+
+        /// Only compare this path, like `Root.Pages`.
+        #[structopt(short, long)]
+        expand: Option<String>,
+
+        /// Count `max-depth` from the end of the `expand` path instead of from the trailer.
+        #[structopt(long, requires = "expand")]
+        relative_depth: bool,
+    },
+    /// Find dictionary keys and name or string values, printed with their path and object id.
+    ///
+    /// The path can be used with `expand` in the `tree` command.
+    /// Matching is case insensitive unless `case-sensitive` is set.
+    Search {
+        /// The text to search for.
+        query: String,
+
+        /// Only match dictionary keys.
+        #[structopt(long, conflicts_with = "by-value")]
+        by_key: bool,
+
+        /// Only match name and string values.
+        #[structopt(long)]
+        by_value: bool,
+
+        /// Use the query as a regular expression.
+        #[structopt(long)]
+        regex: bool,
+
+        /// Match upper and lower case letters exactly.
+        #[structopt(long)]
+        case_sensitive: bool,
+
+        /// Also match the decoded content of streams.
+        #[structopt(long)]
+        search_content: bool,
+
+        /// Only print the number of matches, like `37 matching nodes`.
+        #[structopt(long)]
+        count_only: bool,
+    },
 }
 
-#[derive(Debug, Clone, StructOpt, PartialEq)]
+#[derive(Debug, Clone, StructOpt, PartialEq, Default)]
 pub enum StreamDisplay {
+    #[default]
     NoDisplay,
     Hex,
     Tree,
@@ -163,9 +749,79 @@ impl FromStr for StreamDisplay {
     }
 }
 
-impl Default for StreamDisplay {
-    fn default() -> Self {
-        StreamDisplay::NoDisplay
+/// Where the tree starts when it should not start at the trailer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TreeStart {
+    /// The document catalog, `Root` in the trailer.
+    Catalog,
+    /// The document information dictionary, `Info` in the trailer.
+    Info,
+    /// The root of the page tree, `Pages` in the catalog.
+    Pages,
+    /// Any object by id, like `12,0`.
+    Object(ObjectId),
+}
+
+impl FromStr for TreeStart {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowercase_s = s.to_lowercase();
+
+        match lowercase_s.as_ref() {
+            "catalog" | "root" => Ok(Self::Catalog),
+            "info" => Ok(Self::Info),
+            "pages" => Ok(Self::Pages),
+            _ => Err("Unknown start of the tree.".to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Tree,
+    Json,
+    Ndjson,
+    Markdown,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowercase_s = s.to_lowercase();
+
+        match lowercase_s.as_ref() {
+            "tree" => Ok(Self::Tree),
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err("Unknown format.".to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowercase_s = s.to_lowercase();
+
+        match lowercase_s.as_ref() {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err("Unknown color mode.".to_owned()),
+        }
     }
 }
 
@@ -175,94 +831,782 @@ fn main() -> Result<(), Error> {
     // Get log settings
     initialize_logger(&opts);
 
-    let file_name = opts
+    let color_mode = match opts.no_color {
+        true => ColorMode::Never,
+        false => opts.color.unwrap_or_default(),
+    };
+    let force_color = color_mode == ColorMode::Always
+        || std::env::var("CLICOLOR_FORCE").is_ok_and(|value| value != "0");
+    if color_mode == ColorMode::Never || (!force_color && !is_output_terminal(&opts)) {
+        Paint::disable();
+    }
+
+    // Report a panic (caused by unexpected input) as an error for this file instead of crashing,
+    // so a batch run over many files can continue with the next file.
+    let input_name = opts
         .input
+        .as_ref()
+        .map(|input| input.display().to_string())
+        .unwrap_or_default();
+    std::panic::set_hook(Box::new(|panic_info| {
+        if !is_broken_pipe_panic(panic_info.payload()) {
+            log::error!("Internal error: {}", panic_info);
+        }
+    }));
+    if opts.watch {
+        return watch_input(opts, &input_name);
+    }
+    // Print a line for scripts instead of the `Debug` output of the error.
+    if let Err(err) = run_catching_panics(opts, &input_name) {
+        exit_reason::print_exit_reason(&err);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_catching_panics(opts: Opts, input_name: &str) -> Result<(), Error> {
+    match std::panic::catch_unwind(|| run(opts)) {
+        Ok(result) => result,
+        // The output was closed while printing, that is not an internal error.
+        Err(payload) if is_broken_pipe_panic(&*payload) => Ok(()),
+        Err(_) => {
+            log::error!(
+                "Could not process `{}` because of an internal error.",
+                input_name
+            );
+            simple_logger::print_warning_summary();
+            Err(ExitReason::into_error(
+                ErrorKind::Other,
+                "internal_error",
+                "Internal error",
+            ))
+        }
+    }
+}
+
+/// `print!` panics when stdout is closed, like when a pager is quit before the end.
+fn is_broken_pipe_panic(payload: &(dyn std::any::Any + Send)) -> bool {
+    let message = match payload.downcast_ref::<String>() {
+        Some(message) => message.as_str(),
+        None => payload.downcast_ref::<&str>().copied().unwrap_or_default(),
+    };
+    message.starts_with("failed printing to stdout")
+        && message.to_lowercase().contains("broken pipe")
+}
+
+/// Run the command each time the input file changes.
+///
+/// The screen is cleared before each run. This only stops when the program is stopped.
+fn watch_input(opts: Opts, input_name: &str) -> Result<(), Error> {
+    let input = match &opts.input {
+        Some(input) => input.clone(),
+        None => {
+            log::error!("No input file given to watch.");
+            return Err(ExitReason::into_error(
+                ErrorKind::InvalidInput,
+                "invalid_input",
+                "No input file given to watch.",
+            ));
+        }
+    };
+    let mut last_state = None;
+    let mut first_run = true;
+    loop {
+        let state = get_file_state(&input);
+        // A missing file is only reported once, until it is back.
+        if first_run || state != last_state {
+            first_run = false;
+            last_state = state;
+            // Clear the screen and move the cursor to the top left.
+            print!("\x1b[2J\x1b[H");
+            // Clear before errors of this run are logged on stderr.
+            std::io::stdout().flush()?;
+            simple_logger::reset_warning_summary();
+            exit_reason::reset_failed_object();
+            if let Err(err) = run_catching_panics(opts.clone(), input_name) {
+                exit_reason::print_exit_reason(&err);
+            }
+            println!(
+                "{}",
+                yansi::Paint::new(format!(
+                    "Watching `{}` for changes, press Ctrl-C to stop.",
+                    input_name
+                ))
+                .dimmed()
+                .italic()
+            );
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+/// Get the modification time and size of a file, `None` if it can not be read.
+fn get_file_state(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn run(opts: Opts) -> Result<(), Error> {
+    let mut pager = match opts.pager {
+        true => pager::start_pager(),
+        false => None,
+    };
+    // Everything the command prints is written to `out`:
+    // the file of `--output`, the pager or stdout.
+    let mut out: Box<dyn Write> = match (&opts.output, pager.as_mut()) {
+        (Some(output), _) => match File::create(output) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(err) => {
+                log::error!("Could not create `{}`: {}", output.display(), err);
+                return Err(ExitReason::into_error(err.kind(), "write_failure", err));
+            }
+        },
+        (None, Some(pager)) => match pager.stdin.take() {
+            Some(pager_stdin) => Box::new(BufWriter::new(pager_stdin)),
+            None => Box::new(std::io::stdout().lock()),
+        },
+        (None, None) => Box::new(std::io::stdout().lock()),
+    };
+    if pager.is_some() {
+        simple_logger::hold_messages();
+    }
+    let result = run_command(opts, &mut *out);
+    // Also write what was printed before an error.
+    let result = match out.flush() {
+        Err(err) if err.kind() != ErrorKind::BrokenPipe => {
+            log::error!("Could not write the output: {}", err);
+            Err(ExitReason::into_error(err.kind(), "write_failure", err))
+        }
+        _ => result,
+    };
+    if let Some(mut pager) = pager {
+        // Closing its input tells the pager that the output is complete.
+        drop(out);
+        let pager_status = pager.wait();
+        simple_logger::print_held_messages();
+        pager_status?;
+    }
+    result
+}
+
+fn run_command(opts: Opts, out: &mut dyn Write) -> Result<(), Error> {
+    let tree_chars = if opts.ascii {
+        TreeChars::ASCII
+    } else {
+        TreeChars::UNICODE
+    };
+
+    if let Command::SampleDoc { output } = &opts.cmd {
+        if let Err(err) = sample_doc::save_sample_document(output) {
+            log::error!("Error while writing sample document: {}", err);
+            return Err(ExitReason::into_error(
+                ErrorKind::Other,
+                "write_failure",
+                err,
+            ));
+        }
+        return Ok(());
+    }
+    if let Command::Operators = &opts.cmd {
+        return match print_tree::print_operator_list(out, &tree_chars) {
+            Err(err) if err.kind() != ErrorKind::BrokenPipe => {
+                log::error!("Error while printing: {}", err);
+                Err(ExitReason::into_error(err.kind(), "write_failure", err))
+            }
+            _ => Ok(()),
+        };
+    }
+    if let Command::ParseObjStm { file, first } = &opts.cmd {
+        return print_object_stream_dump(out, file, *first);
+    }
+
+    let input = match &opts.input {
+        Some(input) => input,
+        None => {
+            log::error!("No input file given.");
+            return Err(ExitReason::into_error(
+                ErrorKind::InvalidInput,
+                "invalid_input",
+                "No input file given.",
+            ));
+        }
+    };
+    let file_name = input
         .file_name()
         .map(|name| name.to_string_lossy().to_string())
         .unwrap_or_else(|| "<no_file_name>".to_owned());
 
-    let mut raw_doc = match Document::load(opts.input) {
-        Ok(doc) => doc,
-        Err(lopdf::Error::IO(err)) => {
-            log::error!("IO Error while reading file: {}", err);
-            return Err(err);
+    let (file_bytes, mut raw_doc, decrypted) =
+        load_document(input, opts.input_offset, opts.password.as_deref())?;
+
+    let output_is_terminal = is_output_terminal(&opts);
+    let result = match opts.cmd {
+        Command::Info { json: true } => {
+            print_pdf_info::print_pdf_info_json(out, &raw_doc, decrypted)
         }
-        Err(err) => {
-            log::error!("Error while loading file: {}", err);
-            return Err(Error::new(ErrorKind::InvalidData, err));
+        Command::Info { json: false } => {
+            print_pdf_info::print_pdf_info(out, &raw_doc, &file_bytes, decrypted)
         }
-    };
-
-    match opts.cmd {
-        Command::Info => {
-            print_pdf_info::print_pdf_info(&raw_doc).unwrap();
+        Command::Overview => {
+            print_overview::print_overview(out, &raw_doc, file_name, &file_bytes, &tree_chars)
         }
+        Command::Stats {
+            decoded_stream_bytes,
+            operator_counts,
+            json,
+        } => stats::print_stats(out, &raw_doc, decoded_stream_bytes, operator_counts, json),
         Command::Tree {
             max_depth,
+            min_depth,
+            follow_refs_depth,
             expand,
+            relative_depth,
+            only_path,
             display_type_names,
             array_display_limit,
             hex_display_limit,
+            max_value_len,
+            inline_number_arrays,
+            max_operand_array_depth,
             display_stream,
             display_font,
             display_parent,
+            no_cycle_guard,
+            display_box_sizes,
+            resolve_scalars,
+            redact_strings,
+            raw_string_bytes,
+            short_skip_marker,
+            mark_indirect,
+            overview_tree,
+            sort_keys,
+            collapse_single_child,
+            from_catalog,
+            from,
+            root_ref,
+            page,
             hide_legend,
+            compact_legend,
             stream_raw_operations,
             stream_enhanced_operator_info,
+            no_stream_nesting,
+            show_raw_operands,
             force_stream_decoding,
+            decode_stream_key,
+            no_decompress,
+            collapse_repeats,
+            operation_indices,
+            operator_filter,
+            highlight,
+            value_format,
+            track_colors,
+            text_positions,
+            inline_stream_refs,
             print_line_numbers,
+            adaptive_indent,
+            depth_guides,
+            width,
             line_number_padding_width,
+            line_number_start,
+            since_offset,
+            only_streams,
+            format,
+            timeout,
         } => {
+            // Hidden levels can not be left out of nested JSON.
+            if format == Some(OutputFormat::Json) && min_depth.is_some() {
+                log::error!("`min-depth` can not be used with `--format json`.");
+                return Err(ExitReason::into_error(
+                    ErrorKind::InvalidInput,
+                    "invalid_input",
+                    "`min-depth` can not be used with `--format json`.",
+                ));
+            }
+            // Only cut off lines for people reading them, keep the output intact for scripts.
+            let max_line_width = match width {
+                Some(0) => None,
+                Some(width) => Some(width),
+                None if output_is_terminal => Some(get_terminal_width()),
+                None => None,
+            };
             // Tree display settings
             let default_tree_settings = TreeDisplaySettings::default();
             let tree_display_settings = TreeDisplaySettings {
                 max_depth: max_depth.unwrap_or(default_tree_settings.max_depth),
+                follow_refs_depth,
                 expand: expand.map(|path| path.split('.').map(|s| s.to_owned()).collect()),
+                relative_depth,
+                only_path,
                 display_type_names,
                 array_display_limit: match array_display_limit {
                     Some(0) => None,
                     Some(x) => Some(x),
                     None => default_tree_settings.array_display_limit,
                 },
+                max_value_len: max_value_len.filter(|max_value_len| *max_value_len > 0),
                 hex_display_limit: match hex_display_limit {
                     Some(0) => None,
                     Some(x) => Some(x),
+                    // The line is already cut off where it does not fit anymore.
+                    None if max_line_width.is_some() => None,
                     None => default_tree_settings.hex_display_limit,
                 },
+                inline_number_array_limit: inline_number_arrays
+                    .or(default_tree_settings.inline_number_array_limit),
+                max_operand_array_depth: max_operand_array_depth
+                    .unwrap_or(default_tree_settings.max_operand_array_depth),
                 display_stream: display_stream.unwrap_or(default_tree_settings.display_stream),
                 display_font,
                 display_parent,
+                no_cycle_guard,
                 display_legend: !hide_legend,
+                compact_legend,
                 stream_enhanced_operations: !stream_raw_operations,
                 stream_enhanced_operator_info,
+                stream_nesting: !no_stream_nesting,
+                show_raw_operands,
                 force_stream_decoding,
+                decode_stream_keys: decode_stream_key,
+                no_decompress,
+                inline_stream_refs,
+                display_box_sizes,
+                tree_start: root_ref
+                    .map(TreeStart::Object)
+                    .or(from)
+                    .or_else(|| from_catalog.then_some(TreeStart::Catalog)),
+                page,
+                collapse_repeats,
+                operation_indices,
+                operator_filter: operator_filter.map(|operators| {
+                    operators
+                        .split(',')
+                        .map(|operator| operator.trim().to_owned())
+                        .collect()
+                }),
+                highlight_keys: highlight
+                    .map(|keys| {
+                        keys.split(',')
+                            .map(|key| key.trim().trim_start_matches('/').to_owned())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                resolve_scalars,
+                track_colors,
+                text_positions,
+                redact_strings,
+                raw_string_bytes,
+                short_skip_marker,
+                mark_indirect,
+                overview_tree,
+                sort_keys,
+                collapse_single_child,
+                value_formatters: value_format,
             };
             // Tree cursor settings
             let default_cursor_settings = TreeCursorSettings::default();
             let tree_cursor_settings = TreeCursorSettings {
+                tree_chars,
                 print_line_numbers,
                 line_number_padding: line_number_padding_width
                     .unwrap_or(default_cursor_settings.line_number_padding),
+                line_number_start: line_number_start
+                    .unwrap_or(default_cursor_settings.line_number_start),
+                timeout: timeout.map(Duration::from_secs),
+                max_indent_width: adaptive_indent.then(|| get_terminal_width() / 2),
+                max_line_width,
+                min_depth: min_depth.unwrap_or(default_cursor_settings.min_depth),
+                depth_guides,
             };
 
             // Decode streams as this will be needed.
+            if !no_decompress {
+                raw_doc.decompress();
+            }
+            if format == Some(OutputFormat::Json) {
+                json_tree::print_json_tree(
+                    out,
+                    &tree_display_settings,
+                    &tree_cursor_settings,
+                    &raw_doc,
+                    file_name,
+                )
+            } else if format == Some(OutputFormat::Ndjson) {
+                json_tree::print_ndjson_tree(
+                    out,
+                    &tree_display_settings,
+                    &tree_cursor_settings,
+                    &raw_doc,
+                )
+            } else if format == Some(OutputFormat::Markdown) {
+                markdown_tree::print_markdown_tree(
+                    out,
+                    &tree_display_settings,
+                    &tree_cursor_settings,
+                    &raw_doc,
+                    file_name,
+                )
+            } else if let Some(since_offset) = since_offset {
+                let locations = object_offsets::get_object_locations(&file_bytes, &raw_doc);
+                let objects =
+                    object_offsets::get_objects_since_offset(&locations, &raw_doc, since_offset);
+                print_tree::print_pdf_object_list(
+                    out,
+                    &tree_display_settings,
+                    &tree_cursor_settings,
+                    &raw_doc,
+                    file_name,
+                    &objects,
+                )
+            } else if only_streams {
+                print_tree::print_stream_list(
+                    out,
+                    &tree_display_settings,
+                    &tree_cursor_settings,
+                    &raw_doc,
+                    file_name,
+                )
+            } else {
+                print_tree::print_pdf_tree(
+                    out,
+                    &tree_display_settings,
+                    &tree_cursor_settings,
+                    &raw_doc,
+                    file_name,
+                )
+            }
+        }
+        Command::Structure => writeln!(out, "{:#?}", raw_doc).map_err(lopdf::Error::from),
+        Command::PageTree => {
+            let tree_cursor_settings = TreeCursorSettings {
+                tree_chars,
+                print_line_numbers: false,
+                ..Default::default()
+            };
+            print_tree::print_page_tree(out, &tree_cursor_settings, &raw_doc, file_name)
+        }
+        Command::Validate => match validate::print_validation(out, &raw_doc, &file_bytes) {
+            Ok(true) => {
+                simple_logger::print_warning_summary();
+                return Err(ExitReason::into_error(
+                    ErrorKind::InvalidData,
+                    "validation_failed",
+                    "The document has structural errors.",
+                ));
+            }
+            Ok(false) => Ok(()),
+            Err(err) => Err(err.into()),
+        },
+        Command::Outline => {
+            let tree_cursor_settings = TreeCursorSettings {
+                tree_chars,
+                print_line_numbers: false,
+                ..Default::default()
+            };
+            print_tree::print_outline(out, &tree_cursor_settings, &raw_doc, file_name)
+        }
+        Command::Parents { object_id, limit } => {
+            let tree_cursor_settings = TreeCursorSettings {
+                tree_chars,
+                print_line_numbers: false,
+                ..Default::default()
+            };
+            print_tree::print_parents(
+                out,
+                &tree_cursor_settings,
+                &raw_doc,
+                file_name,
+                object_id,
+                limit.unwrap_or(10),
+            )
+        }
+        Command::Operators | Command::ParseObjStm { .. } | Command::SampleDoc { .. } => {
+            // Handled before loading the input file.
+            Ok(())
+        }
+        Command::Sizes { limit } => {
+            print_object_sizes::print_object_sizes(out, &raw_doc, limit.unwrap_or(10))
+        }
+        Command::Fonts => print_fonts::print_fonts(out, &raw_doc),
+        Command::Attachments { extract_dir } => {
+            print_attachments::print_attachments(out, &raw_doc, extract_dir.as_deref())
+        }
+        Command::FindByHash { hash } => {
+            object_hashes::print_objects_by_hash(out, &raw_doc, hash.as_deref())
+        }
+        Command::ExtractImages { output_dir } => {
+            extract_images::extract_images(out, &raw_doc, &output_dir)
+        }
+        Command::ExportStream {
+            object_id,
+            export_to,
+        } => export_stream::export_stream(out, &raw_doc, object_id, &export_to),
+        Command::Text { page } => extract_text::print_text(out, &raw_doc, page),
+        Command::RevisionDiff { from, to } => {
+            revision_diff::print_revision_diff(out, &file_bytes, from, to)
+        }
+        Command::Diff {
+            other,
+            max_depth,
+            expand,
+            relative_depth,
+        } => {
+            let (_, mut other_doc, _) =
+                load_document(&other, opts.input_offset, opts.password.as_deref())?;
+            // Compare the decoded content of streams.
             raw_doc.decompress();
-            if tree_display_settings.display_stream != StreamDisplay::NoDisplay {}
-            print_tree::print_pdf_tree(
+            other_doc.decompress();
+            let default_tree_settings = TreeDisplaySettings::default();
+            let tree_display_settings = TreeDisplaySettings {
+                max_depth: max_depth.unwrap_or(default_tree_settings.max_depth),
+                expand: expand.map(|path| path.split('.').map(|s| s.to_owned()).collect()),
+                relative_depth,
+                ..default_tree_settings
+            };
+            let tree_cursor_settings = TreeCursorSettings {
+                tree_chars,
+                print_line_numbers: false,
+                ..Default::default()
+            };
+            let other_file_name = other
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "<no_file_name>".to_owned());
+            print_tree::print_document_diff(
+                out,
                 &tree_display_settings,
                 &tree_cursor_settings,
                 &raw_doc,
+                &other_doc,
+                file_name,
+                other_file_name,
+            )
+        }
+        Command::Search {
+            query,
+            by_key,
+            by_value,
+            regex,
+            case_sensitive,
+            search_content,
+            count_only,
+        } => {
+            let search_settings = SearchSettings {
+                query,
+                regex,
+                case_sensitive,
+                // Match both when neither is given.
+                by_key: by_key || !by_value,
+                by_value: by_value || !by_key,
+                search_content,
+                count_only,
+            };
+            let tree_cursor_settings = TreeCursorSettings {
+                tree_chars,
+                print_line_numbers: false,
+                ..Default::default()
+            };
+            print_tree::print_search_results(
+                out,
+                &tree_cursor_settings,
+                &raw_doc,
                 file_name,
+                &search_settings,
             )
-            .unwrap();
         }
-        Command::Structure => {
-            println!("{:#?}", raw_doc);
+    };
+    check_print_result(result, input)?;
+    simple_logger::print_warning_summary();
+    Ok(())
+}
+
+/// Report an error while printing, except when the output was closed.
+///
+/// The reader of the output can stop early (like `head`, or a pager that is quit),
+/// that is a normal end of the output.
+fn check_print_result(result: Result<(), lopdf::Error>, input: &Path) -> Result<(), Error> {
+    match result {
+        Err(lopdf::Error::IO(err)) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+        Err(err) => {
+            log::error!("Error while printing `{}`: {}", input.display(), err);
+            Err(to_io_error(err))
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Read the file, parse it and decrypt it with the password (or the empty user password).
+///
+/// Returns the bytes of the PDF (starting at `input_offset`), the document and
+/// if it was decrypted.
+fn load_document(
+    path: &Path,
+    input_offset: Option<usize>,
+    password: Option<&str>,
+) -> Result<(Vec<u8>, Document, bool), Error> {
+    let file_bytes = match std::fs::read(path) {
+        Ok(file_bytes) => file_bytes,
+        Err(err) => {
+            log::error!("IO Error while reading file: {}", err);
+            return Err(ExitReason::into_error(err.kind(), "read_failure", err));
+        }
+    };
+    let file_bytes = match input_offset {
+        Some(input_offset) => match file_bytes.get(input_offset..) {
+            Some(pdf_bytes) if pdf_bytes.starts_with(b"%PDF-") => pdf_bytes.to_vec(),
+            _ => {
+                log::error!(
+                    "No PDF header (`%PDF-`) found at input offset {}.",
+                    input_offset
+                );
+                return Err(ExitReason::into_error(
+                    ErrorKind::InvalidData,
+                    "parse_failure",
+                    "No PDF header found at input offset.",
+                ));
+            }
+        },
+        None => file_bytes,
+    };
+    let mut raw_doc = match Document::load_mem(&file_bytes) {
+        Ok(doc) => doc,
+        Err(lopdf::Error::IO(err)) => {
+            log::error!("IO Error while reading file: {}", err);
+            return Err(ExitReason::into_error(err.kind(), "read_failure", err));
+        }
+        Err(err) => {
+            log::error!("Error while loading file: {}", err);
+            return Err(ExitReason::into_error(
+                ErrorKind::InvalidData,
+                exit_reason::get_lopdf_error_kind(&err),
+                err,
+            ));
+        }
+    };
+
+    object_offsets::check_root_objects(&raw_doc);
+    let mut decrypted = false;
+    if encryption::is_encrypted(&raw_doc) {
+        match decryption::decrypt_document(&mut raw_doc, &file_bytes, password) {
+            Ok(()) => decrypted = true,
+            Err(DecryptionError::WrongPassword) if password.is_none() => log::warn!(
+                "The document is encrypted with a password, use `--password` to decrypt it. \
+                Strings and streams are shown as stored."
+            ),
+            Err(DecryptionError::WrongPassword) => {
+                log::error!("The password is not the user or owner password of the document.");
+                return Err(ExitReason::into_error(
+                    ErrorKind::PermissionDenied,
+                    "wrong_password",
+                    "The password is not correct.",
+                ));
+            }
+            Err(err @ DecryptionError::Unsupported(_)) => log::warn!(
+                "The document can not be decrypted ({}), strings and streams are shown as stored.",
+                err
+            ),
         }
+    } else if password.is_some() {
+        log::warn!("The document is not encrypted, `--password` is ignored.");
     }
+    Ok((file_bytes, raw_doc, decrypted))
+}
+
+fn to_io_error(err: lopdf::Error) -> Error {
+    let kind = exit_reason::get_lopdf_error_kind(&err);
+    match err {
+        lopdf::Error::IO(err) => ExitReason::into_error(err.kind(), kind, err),
+        err => ExitReason::into_error(ErrorKind::InvalidData, kind, err),
+    }
+}
+
+fn print_object_stream_dump(
+    out: &mut dyn Write,
+    file: &Path,
+    first: Option<usize>,
+) -> Result<(), Error> {
+    let dump = match std::fs::read(file) {
+        Ok(dump) => dump,
+        Err(err) => {
+            log::error!("IO Error while reading file: {}", err);
+            return Err(ExitReason::into_error(err.kind(), "read_failure", err));
+        }
+    };
+    let (raw_doc, objects) = match object_stream_dump::load_object_stream_dump(&dump, first) {
+        Ok(result) => result,
+        Err(err) => {
+            log::error!("Error while parsing object stream: {}", err);
+            return Err(ExitReason::into_error(
+                ErrorKind::InvalidData,
+                "parse_failure",
+                err,
+            ));
+        }
+    };
+    let file_name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "<no_file_name>".to_owned());
+    let result = print_tree::print_pdf_object_list(
+        out,
+        &TreeDisplaySettings::default(),
+        &TreeCursorSettings::default(),
+        &raw_doc,
+        file_name,
+        &objects,
+    );
+    check_print_result(result, file)?;
+    simple_logger::print_warning_summary();
     Ok(())
 }
 
+/// The output is written to a terminal, not to a file or another program.
+fn is_output_terminal(opts: &Opts) -> bool {
+    opts.output.is_none() && std::io::stdout().is_terminal()
+}
+
+/// Get the width of the terminal from `COLUMNS`, or 80 if it is not set.
+fn get_terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(80)
+}
+
+/// Parse an object id like `12`, `12,0` or `12 0`.
+fn parse_object_id(value: &str) -> Result<ObjectId, String> {
+    let mut parts = value
+        .split(|character: char| character == ',' || character.is_whitespace())
+        .filter(|part| !part.is_empty());
+    let object_number = parts
+        .next()
+        .and_then(|part| part.parse::<u32>().ok())
+        .ok_or_else(|| format!("Invalid object id: `{}`", value))?;
+    let generation = match parts.next() {
+        Some(part) => part
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid generation number: `{}`", part))?,
+        None => 0,
+    };
+    Ok((object_number, generation))
+}
+
+/// Parse a type name and formatter like `Name=hex`.
+fn parse_value_format(value: &str) -> Result<(String, ValueFormatter), String> {
+    let (type_name, formatter_name) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Expected `TYPE=FORMATTER`, found: `{}`", value))?;
+    if !FORMATTABLE_TYPE_NAMES.contains(&type_name) {
+        return Err(format!(
+            "Unknown type `{}`, expected one of: {}",
+            type_name,
+            FORMATTABLE_TYPE_NAMES.join(", ")
+        ));
+    }
+    let formatter = get_value_formatter(formatter_name)
+        .ok_or_else(|| format!("Unknown formatter: `{}`", formatter_name))?;
+    Ok((type_name.to_owned(), formatter))
+}
+
 /// Setup logger. This will select where to print the log message and how many.
 fn initialize_logger(opts: &Opts) {
     let log_filter: LevelFilter = if opts.debug {
@@ -282,3 +1626,26 @@ fn initialize_logger(opts: &Opts) {
     log::set_logger(&simple_logger::LOGGER).unwrap();
     log::set_max_level(log_filter);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Opts, structopt::clap::Error> {
+        Opts::from_iter_safe(std::iter::once("pdf_structure_viewer").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn no_color_on_its_own() {
+        let opts = parse_args(&["--no-color", "file.pdf", "info"]).unwrap();
+        assert!(opts.no_color);
+        assert_eq!(opts.color, None);
+    }
+
+    #[test]
+    fn color_mode() {
+        let opts = parse_args(&["--color", "never", "file.pdf", "info"]).unwrap();
+        assert_eq!(opts.color, Some(ColorMode::Never));
+        assert!(parse_args(&["--color", "always", "--no-color", "file.pdf", "info"]).is_err());
+    }
+}
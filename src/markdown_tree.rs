@@ -0,0 +1,198 @@
+use crate::json_tree::{walk_json_tree, JsonNode, JsonValue, JsonVisitor};
+use crate::print_tree::{TreeCursorInfo, TreeCursorSettings, TreeDisplaySettings};
+use lopdf::{Document, Error};
+use std::io::Write;
+
+/// Print the tree as a Markdown nested list, for pasting into issues and documentation.
+///
+/// Uses the same walk as the JSON output, so the same settings apply.
+pub fn print_markdown_tree(
+    out: &mut dyn Write,
+    display_settings: &TreeDisplaySettings,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+    file_name: String,
+) -> Result<(), Error> {
+    writeln!(
+        out,
+        "**{}** (PDF {})",
+        escape_markdown(&file_name),
+        escape_markdown(&raw_doc.version)
+    )?;
+    writeln!(out)?;
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, out);
+    walk_json_tree(
+        display_settings,
+        raw_doc,
+        &cursor,
+        &mut MarkdownPrinter::default(),
+    )
+}
+
+/// Prints a list item as `- label: value (type)` for each node while the tree is walked.
+#[derive(Debug, Default)]
+struct MarkdownPrinter {
+    /// For each started node that is not ended yet, if it added a level to the list.
+    levels: Vec<bool>,
+    /// A reference with its level and text, printed together with its target on one line.
+    open_reference: Option<(usize, String)>,
+}
+
+impl JsonVisitor for MarkdownPrinter {
+    fn start_node(
+        &mut self,
+        cursor: &TreeCursorInfo,
+        node: JsonNode,
+        child_count: Option<usize>,
+    ) -> Result<(), Error> {
+        // The root is not printed, its entries are the first level of the list.
+        if self.levels.is_empty() || cursor.is_hidden() {
+            self.levels.push(false);
+            return Ok(());
+        }
+        if let Some((level, reference_text)) = self.open_reference.take() {
+            // This is the target of the reference, its children are one level deeper.
+            let value_text = get_value_text(&node.value, child_count);
+            print_list_item(
+                cursor,
+                level,
+                &format!("{} → {}", reference_text, value_text),
+            )?;
+            self.levels.push(false);
+            return Ok(());
+        }
+        let level = self
+            .levels
+            .iter()
+            .filter(|added_level| **added_level)
+            .count();
+        let label = match &node.key {
+            Some(key) => format!("{}: ", escape_markdown(key)),
+            None => String::new(),
+        };
+        match &node.value {
+            JsonValue::Reference {
+                object_number,
+                generation,
+                ..
+            } => {
+                self.open_reference = Some((
+                    level,
+                    format!("{}({},{})", label, object_number, generation),
+                ));
+            }
+            value => {
+                let value_text = get_value_text(value, child_count);
+                print_list_item(cursor, level, &format!("{}{}", label, value_text))?;
+            }
+        }
+        self.levels.push(true);
+        Ok(())
+    }
+
+    fn end_node(&mut self, cursor: &TreeCursorInfo) -> Result<(), Error> {
+        self.levels.pop();
+        // A reference without target.
+        if let Some((level, reference_text)) = self.open_reference.take() {
+            print_list_item(
+                cursor,
+                level,
+                &format!("{} (reference, not followed)", reference_text),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn print_list_item(cursor: &TreeCursorInfo, level: usize, text: &str) -> Result<(), Error> {
+    writeln!(cursor.output(), "{}- {}", "  ".repeat(level), text)?;
+    Ok(())
+}
+
+fn get_value_text(value: &JsonValue, child_count: Option<usize>) -> String {
+    let child_count = child_count.unwrap_or_default();
+    match value {
+        JsonValue::Null => "null".to_owned(),
+        JsonValue::Boolean { value } => format!("{} (boolean)", value),
+        JsonValue::Integer { value } => format!("{} (integer)", value),
+        JsonValue::Real { value } => format!("{} (real)", value),
+        JsonValue::Name { value } => format!("/{} (name)", escape_markdown(value)),
+        JsonValue::String { value, .. } => format!(
+            "'{}' (string)",
+            escape_markdown(&value.escape_default().to_string())
+        ),
+        JsonValue::Array { .. } => format!("(array, {} items)", child_count),
+        JsonValue::Dictionary { .. } => format!("(dictionary, {} entries)", child_count),
+        JsonValue::Stream { length, .. } => format!("(stream, {} bytes)", length),
+        JsonValue::Operation { operator, operands } => format!(
+            "{} (operation, {} operands)",
+            escape_markdown(operator),
+            operands.len()
+        ),
+        JsonValue::Reference {
+            object_number,
+            generation,
+            ..
+        } => format!(
+            "({},{}) (reference, not followed)",
+            object_number, generation
+        ),
+        JsonValue::Skipped { reason } => format!("... (skipped: {})", escape_markdown(reason)),
+    }
+}
+
+/// Put a `\` before the characters that Markdown would use for formatting, links or tables.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if matches!(
+            character,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '|' | '<' | '>' | '#'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_doc::create_sample_document;
+
+    #[test]
+    fn escapes_markdown_characters() {
+        assert_eq!(
+            escape_markdown("a|b*c_d`e[f]"),
+            r"a\|b\*c\_d\`e\[f\]".to_owned()
+        );
+        assert_eq!(escape_markdown("Helvetica"), "Helvetica");
+    }
+
+    #[test]
+    fn follows_expand_path() {
+        let raw_doc = create_sample_document().unwrap();
+        let display_settings = TreeDisplaySettings {
+            expand: Some(vec!["Root".to_owned(), "Pages".to_owned()]),
+            only_path: true,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        print_markdown_tree(
+            &mut output,
+            &display_settings,
+            &TreeCursorSettings::default(),
+            &raw_doc,
+            "sample_doc.pdf".to_owned(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "**sample\\_doc.pdf** (PDF 1.5)\n\
+            \n\
+            - Root: (8,0) → (dictionary, 2 entries)\n  \
+            - Pages: (1,0) → (dictionary, 5 entries)\n"
+        );
+    }
+}
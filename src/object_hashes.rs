@@ -0,0 +1,144 @@
+use crate::print_tree::get_type_label;
+use lopdf::{Document, Error, Object, ObjectId};
+use std::collections::BTreeMap;
+use std::io::Write;
+use yansi::Style;
+
+/// Keys of a stream dictionary that depend on how the content is stored, not on the content.
+static STORAGE_KEYS: [&[u8]; 3] = [b"Length", b"Filter", b"DecodeParms"];
+
+/// Print the objects whose content hash starts with `hash`,
+/// or the hash of every object when no hash is given.
+///
+/// See `get_object_hash` for what is included in the hash.
+pub fn print_objects_by_hash(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    hash: Option<&str>,
+) -> Result<(), Error> {
+    let label_style = Style::default().italic();
+    let value_style = Style::default().bold();
+
+    // Objects with the same content are listed together.
+    let mut hash_index: BTreeMap<String, Vec<ObjectId>> = BTreeMap::new();
+    for (object_id, object) in raw_doc.objects.iter() {
+        hash_index
+            .entry(get_object_hash(object))
+            .or_default()
+            .push(*object_id);
+    }
+
+    let hash = hash.map(|hash| hash.trim().to_lowercase());
+    let mut match_count = 0;
+    for (object_hash, object_ids) in hash_index.iter() {
+        if let Some(hash) = &hash {
+            if !object_hash.starts_with(hash.as_str()) {
+                continue;
+            }
+        }
+        for object_id in object_ids {
+            match_count += 1;
+            let type_label = raw_doc
+                .objects
+                .get(object_id)
+                .and_then(get_type_label)
+                .map(|type_label| format!(" <{}>", type_label))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "{} {}{}",
+                object_hash,
+                value_style.paint(format!("({},{})", object_id.0, object_id.1)),
+                label_style.paint(type_label)
+            )?;
+        }
+    }
+    if let Some(hash) = hash {
+        if match_count == 0 {
+            writeln!(
+                out,
+                "{}",
+                label_style.paint(format!("No object with hash `{}`.", hash))
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Get the MD5 hash (in hexadecimal) of the content of an object.
+///
+/// The hash does not depend on the object ids, so the same object can be found in
+/// another file: references are hashed as `R` without their id. Streams are hashed
+/// with their decoded content, and without the keys that describe how they are stored.
+/// Dictionary keys are sorted, literal and hexadecimal strings are hashed the same way.
+pub fn get_object_hash(obj: &Object) -> String {
+    let mut serialized = Vec::new();
+    serialize_object(obj, &mut serialized);
+    format!("{:x}", md5::compute(&serialized))
+}
+
+fn serialize_object(obj: &Object, output: &mut Vec<u8>) {
+    match obj {
+        Object::Null => output.extend_from_slice(b"null"),
+        Object::Boolean(value) => output.extend_from_slice(value.to_string().as_bytes()),
+        Object::Integer(value) => output.extend_from_slice(value.to_string().as_bytes()),
+        Object::Real(value) => output.extend_from_slice(value.to_string().as_bytes()),
+        Object::Name(name) => {
+            output.push(b'/');
+            serialize_bytes(name, output);
+        }
+        Object::String(value, _) => {
+            output.push(b'(');
+            serialize_bytes(value, output);
+        }
+        Object::Array(list) => {
+            output.push(b'[');
+            for item in list {
+                serialize_object(item, output);
+                output.push(b' ');
+            }
+            output.push(b']');
+        }
+        Object::Dictionary(dict) => {
+            let mut entries: Vec<_> = dict.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            serialize_dictionary_entries(&entries, output);
+        }
+        Object::Stream(stream) => {
+            let mut entries: Vec<_> = stream
+                .dict
+                .iter()
+                .filter(|(key, _)| !STORAGE_KEYS.contains(&key.as_slice()))
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            serialize_dictionary_entries(&entries, output);
+            output.extend_from_slice(b"stream");
+            // lopdf returns an error for streams without filters.
+            let content = if stream.dict.has(b"Filter") {
+                stream
+                    .decompressed_content()
+                    .unwrap_or_else(|_| stream.content.clone())
+            } else {
+                stream.content.clone()
+            };
+            serialize_bytes(&content, output);
+        }
+        Object::Reference(_) => output.push(b'R'),
+    }
+}
+
+fn serialize_dictionary_entries(entries: &[(&Vec<u8>, &Object)], output: &mut Vec<u8>) {
+    output.extend_from_slice(b"<<");
+    for (key, value) in entries {
+        output.push(b'/');
+        serialize_bytes(key, output);
+        serialize_object(value, output);
+    }
+    output.extend_from_slice(b">>");
+}
+
+/// Add the length before the bytes, so the bytes can contain any delimiter.
+fn serialize_bytes(bytes: &[u8], output: &mut Vec<u8>) {
+    output.extend_from_slice(format!("{}:", bytes.len()).as_bytes());
+    output.extend_from_slice(bytes);
+}
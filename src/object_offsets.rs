@@ -0,0 +1,474 @@
+use lopdf::{Document, Object, ObjectId};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// Where an object is stored in the file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObjectLocation {
+    /// Stored directly in the file at this byte offset.
+    Offset(usize),
+    /// Stored inside an object stream, the stream itself starts at this byte offset.
+    InObjectStream { container: ObjectId, offset: usize },
+}
+
+impl ObjectLocation {
+    /// The byte offset in the file, for objects in object streams this is the offset of the stream.
+    pub fn offset(&self) -> usize {
+        match self {
+            ObjectLocation::Offset(offset) => *offset,
+            ObjectLocation::InObjectStream { offset, .. } => *offset,
+        }
+    }
+
+    /// Short human readable description of the location.
+    pub fn description(&self) -> String {
+        match self {
+            ObjectLocation::Offset(offset) => format!("offset: {}", offset),
+            ObjectLocation::InObjectStream { container, offset } => format!(
+                "in object stream ({},{}) at offset: {}",
+                container.0, container.1, offset
+            ),
+        }
+    }
+}
+
+/// Find the byte offset of every `<id> <generation> obj` header in the file.
+///
+/// Stream data is skipped so binary content can not be mistaken for an object header.
+/// Objects that are defined multiple times (like in incremental updates) are returned
+/// multiple times, in file order.
+pub fn scan_object_headers(buffer: &[u8]) -> Vec<(ObjectId, usize)> {
+    let mut headers = Vec::new();
+    let mut index = 0;
+    while index < buffer.len() {
+        let rest = &buffer[index..];
+        if rest.starts_with(b"stream")
+            && index > 0
+            && (is_whitespace(buffer[index - 1]) || buffer[index - 1] == b'>')
+            && matches!(rest.get(6), Some(b'\r') | Some(b'\n'))
+        {
+            // Skip stream data
+            match find_subslice(&buffer[index + 6..], b"endstream") {
+                Some(end) => index += 6 + end + 9,
+                None => break,
+            }
+            continue;
+        }
+        if rest.starts_with(b"obj") && rest.get(3).is_none_or(|next| !next.is_ascii_alphanumeric())
+        {
+            if let Some(header) = parse_object_header_before(buffer, index) {
+                headers.push(header);
+            }
+        }
+        index += 1;
+    }
+    headers
+}
+
+/// Get all object ids that have a header more than once in the file, with all their offsets.
+pub fn find_duplicate_objects(buffer: &[u8]) -> Vec<(ObjectId, Vec<usize>)> {
+    let mut occurrences: BTreeMap<ObjectId, Vec<usize>> = BTreeMap::new();
+    for (object_id, offset) in scan_object_headers(buffer) {
+        occurrences.entry(object_id).or_default().push(offset);
+    }
+    occurrences
+        .into_iter()
+        .filter(|(_, offsets)| offsets.len() > 1)
+        .collect()
+}
+
+/// Get the byte offset right after each `%%EOF` marker, including its end of line.
+///
+/// Each `%%EOF` marker ends a revision, incremental updates add new revisions.
+pub fn get_revision_ends(buffer: &[u8]) -> Vec<usize> {
+    let mut ends = Vec::new();
+    let mut index = 0;
+    while let Some(found) = find_subslice(&buffer[index..], b"%%EOF") {
+        index += found + 5;
+        while matches!(buffer.get(index), Some(b'\r') | Some(b'\n')) {
+            index += 1;
+        }
+        ends.push(index);
+    }
+    ends
+}
+
+/// Get the revision (starting at 0) the byte offset belongs to.
+///
+/// `revision_ends` are the ends of the revisions, from `get_revision_ends`.
+pub fn get_revision_index(revision_ends: &[usize], offset: usize) -> usize {
+    revision_ends.partition_point(|end| *end <= offset)
+}
+
+/// Get the location of every object in the document.
+///
+/// The offsets come from the object headers in the file, when an object is defined
+/// multiple times the last definition is used, as this is the one that is in effect after
+/// incremental updates. The cross-reference table decides if an object is stored in an
+/// object stream instead, so objects moved into an object stream by an update are found there.
+pub fn get_object_locations(
+    buffer: &[u8],
+    raw_doc: &Document,
+) -> BTreeMap<ObjectId, ObjectLocation> {
+    let header_offsets: BTreeMap<ObjectId, usize> =
+        scan_object_headers(buffer).into_iter().collect();
+    let mut locations: BTreeMap<ObjectId, ObjectLocation> = header_offsets
+        .iter()
+        .map(|(object_id, offset)| (*object_id, ObjectLocation::Offset(*offset)))
+        .collect();
+    for (object_number, container) in get_object_stream_containers(raw_doc, &header_offsets) {
+        // Objects in object streams always have generation 0.
+        let object_id = (object_number, 0);
+        let is_compressed = raw_doc
+            .reference_table
+            .get(object_number)
+            .map(|entry| entry.is_compressed());
+        // Without an entry (in a broken cross-reference table) the header is used if there is one.
+        if is_compressed == Some(true) || !locations.contains_key(&object_id) {
+            locations.insert(
+                object_id,
+                ObjectLocation::InObjectStream {
+                    container,
+                    offset: header_offsets[&container],
+                },
+            );
+        }
+    }
+    locations
+}
+
+/// Get the object stream that stores each object number, by reading the object streams.
+///
+/// When multiple object streams store the same object, the last one in the file is used.
+fn get_object_stream_containers(
+    raw_doc: &Document,
+    header_offsets: &BTreeMap<ObjectId, usize>,
+) -> BTreeMap<u32, ObjectId> {
+    let mut object_streams: Vec<(usize, ObjectId, &lopdf::Stream)> = raw_doc
+        .objects
+        .iter()
+        .filter_map(|(object_id, object)| match object {
+            Object::Stream(stream) if stream.dict.type_is(b"ObjStm") => {
+                Some((*header_offsets.get(object_id)?, *object_id, stream))
+            }
+            _ => None,
+        })
+        .collect();
+    object_streams.sort_by_key(|(offset, object_id, _)| (*offset, *object_id));
+    let mut containers = BTreeMap::new();
+    for (_, container, stream) in object_streams {
+        for object_number in get_object_stream_object_numbers(stream) {
+            containers.insert(object_number, container);
+        }
+    }
+    containers
+}
+
+/// Get all objects that are stored after `since_offset` in the file, sorted by offset.
+///
+/// In incrementally updated files these are the objects added or changed by later revisions.
+pub fn get_objects_since_offset(
+    locations: &BTreeMap<ObjectId, ObjectLocation>,
+    raw_doc: &Document,
+    since_offset: usize,
+) -> Vec<(ObjectId, ObjectLocation)> {
+    let mut objects: Vec<_> = locations
+        .iter()
+        .filter(|(object_id, location)| {
+            location.offset() > since_offset && raw_doc.objects.contains_key(object_id)
+        })
+        .map(|(object_id, location)| (*object_id, *location))
+        .collect();
+    objects.sort_by_key(|(object_id, location)| (location.offset(), *object_id));
+    objects
+}
+
+/// Check if the cross-reference table says this object is stored in an object stream.
+///
+/// lopdf decompresses object streams while loading, so when such an object is missing
+/// its object stream could not be decompressed or parsed.
+pub fn is_in_object_stream(raw_doc: &Document, object_id: ObjectId) -> bool {
+    raw_doc
+        .reference_table
+        .get(object_id.0)
+        .is_some_and(|entry| entry.is_compressed())
+}
+
+/// Explain why a referenced object is not in the document.
+pub fn get_missing_object_message(raw_doc: &Document, object_id: ObjectId) -> String {
+    if is_in_object_stream(raw_doc, object_id) {
+        format!(
+            "Error in PDF: Indirect Reference not found, ({},{}) is stored in an object stream \
+            that could not be decompressed.",
+            object_id.0, object_id.1
+        )
+    } else {
+        "Error in PDF: Indirect Reference not found.".to_owned()
+    }
+}
+
+/// Warn when the objects needed to navigate from the trailer (`Root` and `Pages`) are missing.
+pub fn check_root_objects(raw_doc: &Document) {
+    let check = |name: &str, obj: Option<&Object>| -> Option<ObjectId> {
+        let object_id = obj.and_then(|obj| obj.as_reference().ok())?;
+        if raw_doc.objects.contains_key(&object_id) {
+            return Some(object_id);
+        }
+        if is_in_object_stream(raw_doc, object_id) {
+            log::warn!(
+                "`{}` ({},{}) is stored in an object stream that could not be decompressed, \
+                most of the document can not be navigated.",
+                name,
+                object_id.0,
+                object_id.1
+            );
+        } else {
+            log::warn!(
+                "`{}` ({},{}) does not exist, most of the document can not be navigated.",
+                name,
+                object_id.0,
+                object_id.1
+            );
+        }
+        None
+    };
+    let catalog_id = match check("Root", raw_doc.trailer.get(b"Root").ok()) {
+        Some(catalog_id) => catalog_id,
+        None => return,
+    };
+    if let Ok(catalog) = raw_doc.get_dictionary(catalog_id) {
+        check("Pages", catalog.get(b"Pages").ok());
+    }
+}
+
+/// Read the object numbers from the header of an (uncompressed) object stream.
+pub fn get_object_stream_object_numbers(stream: &lopdf::Stream) -> Vec<u32> {
+    let first = stream
+        .dict
+        .get(b"First")
+        .and_then(Object::as_i64)
+        .unwrap_or(0)
+        .max(0) as usize;
+    let header = stream.content.get(..first).unwrap_or_default();
+    String::from_utf8_lossy(header)
+        .split_whitespace()
+        .map(|number| number.parse::<u32>().ok())
+        .collect::<Vec<_>>()
+        .chunks_exact(2)
+        .filter_map(|pair| pair[0])
+        .collect()
+}
+
+/// Parse `<id> <generation> ` right before `obj_index`, the position of the `obj` keyword.
+fn parse_object_header_before(buffer: &[u8], obj_index: usize) -> Option<(ObjectId, usize)> {
+    let (generation, generation_start) = parse_number_before(buffer, obj_index)?;
+    let (id, id_start) = parse_number_before(buffer, generation_start)?;
+    // The header should start on a new token.
+    if id_start > 0 && !is_whitespace(buffer[id_start - 1]) {
+        return None;
+    }
+    Some(((id, generation as u16), id_start))
+}
+
+/// Get the length of the stream data of the object stored at `offset`, `None` if it is no stream.
+///
+/// The data is everything between the end of line after `stream` and the first `endstream`.
+/// The end of line before `endstream` is not part of the data, but it can not be told apart
+/// from data ending with a line break. So the length is returned without and with it.
+pub fn get_stream_data_length(buffer: &[u8], offset: usize) -> Option<(usize, usize)> {
+    let (_, data) = get_stream_data_range(buffer, offset)?;
+    let data = &buffer[data];
+    let end_of_line_length = if data.ends_with(b"\r\n") {
+        2
+    } else if matches!(data.last(), Some(b'\n') | Some(b'\r')) {
+        1
+    } else {
+        0
+    };
+    Some((data.len() - end_of_line_length, data.len()))
+}
+
+/// Get the position of the `stream` keyword and the range of the stream data (with the end of
+/// line before `endstream`) of the object stored at `offset`, `None` if it is no stream.
+pub fn get_stream_data_range(buffer: &[u8], offset: usize) -> Option<(usize, Range<usize>)> {
+    let rest = buffer.get(offset..)?;
+    let stream_start = find_subslice(rest, b"stream")?;
+    // The object ends before a stream starts.
+    if find_subslice(rest, b"endobj").is_some_and(|end| end < stream_start) {
+        return None;
+    }
+    let mut data_start = stream_start + 6;
+    if rest[data_start..].starts_with(b"\r\n") {
+        data_start += 2;
+    } else if matches!(rest.get(data_start), Some(b'\n') | Some(b'\r')) {
+        data_start += 1;
+    }
+    let data_end = data_start + find_subslice(&rest[data_start..], b"endstream")?;
+    Some((
+        offset + stream_start,
+        offset + data_start..offset + data_end,
+    ))
+}
+
+/// Parse a number that ends with whitespace right before `end`.
+fn parse_number_before(buffer: &[u8], end: usize) -> Option<(u32, usize)> {
+    let mut digits_end = end;
+    while digits_end > 0 && is_whitespace(buffer[digits_end - 1]) {
+        digits_end -= 1;
+    }
+    if digits_end == end {
+        // There should be at least one whitespace character.
+        return None;
+    }
+    let mut digits_start = digits_end;
+    while digits_start > 0 && buffer[digits_start - 1].is_ascii_digit() {
+        digits_start -= 1;
+    }
+    if digits_start == digits_end || digits_end - digits_start > 10 {
+        return None;
+    }
+    let number = std::str::from_utf8(&buffer[digits_start..digits_end])
+        .ok()?
+        .parse()
+        .ok()?;
+    Some((number, digits_start))
+}
+
+fn is_whitespace(character: u8) -> bool {
+    matches!(character, b' ' | b'\t' | b'\r' | b'\n' | b'\x0c' | b'\0')
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file where object 3 is moved into an object stream by an incremental update.
+    ///
+    /// Returns the file with the offsets of the old object 3 and of the object stream.
+    fn create_updated_file() -> (Vec<u8>, usize, usize) {
+        let mut buffer = b"%PDF-1.5\n".to_vec();
+        let mut offsets = Vec::new();
+        for object in [
+            "1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n",
+            "2 0 obj\n<< /Type /Pages /Kids [] /Count 0 >>\nendobj\n",
+            "3 0 obj\n(old)\nendobj\n",
+        ] {
+            offsets.push(buffer.len());
+            buffer.extend_from_slice(object.as_bytes());
+        }
+        let xref_offset = buffer.len();
+        let xref_lines: String = offsets
+            .iter()
+            .map(|offset| format!("{:010} 00000 n \n", offset))
+            .collect();
+        buffer.extend_from_slice(
+            format!(
+                "xref\n0 4\n0000000000 65535 f \n{}trailer\n<< /Size 4 /Root 1 0 R >>\n\
+                startxref\n{}\n%%EOF\n",
+                xref_lines, xref_offset
+            )
+            .as_bytes(),
+        );
+
+        let object_stream_offset = buffer.len();
+        let content = "3 0 (new)";
+        buffer.extend_from_slice(
+            format!(
+                "4 0 obj\n<< /Type /ObjStm /N 1 /First 4 /Length {} >>\nstream\n{}\n\
+                endstream\nendobj\n",
+                content.len(),
+                content
+            )
+            .as_bytes(),
+        );
+        let xref_stream_offset = buffer.len();
+        // Object 3 is the first object in object stream 4, then objects 4 and 5 themselves.
+        let mut data = vec![2, 0, 0, 0, 4, 0, 0];
+        for offset in [object_stream_offset, xref_stream_offset] {
+            data.push(1);
+            data.extend_from_slice(&(offset as u32).to_be_bytes());
+            data.extend_from_slice(&[0, 0]);
+        }
+        buffer.extend_from_slice(
+            format!(
+                "5 0 obj\n<< /Type /XRef /Size 6 /Index [3 3] /W [1 4 2] /Root 1 0 R /Prev {} \
+                /Length {} >>\nstream\n",
+                xref_offset,
+                data.len()
+            )
+            .as_bytes(),
+        );
+        buffer.extend_from_slice(&data);
+        buffer.extend_from_slice(
+            format!(
+                "\nendstream\nendobj\nstartxref\n{}\n%%EOF\n",
+                xref_stream_offset
+            )
+            .as_bytes(),
+        );
+        (buffer, offsets[2], object_stream_offset)
+    }
+
+    #[test]
+    fn object_locations_use_the_latest_revision() {
+        let (buffer, old_offset, object_stream_offset) = create_updated_file();
+        let raw_doc = Document::load_mem(&buffer).unwrap();
+        let locations = get_object_locations(&buffer, &raw_doc);
+        assert_eq!(
+            locations.get(&(3, 0)),
+            Some(&ObjectLocation::InObjectStream {
+                container: (4, 0),
+                offset: object_stream_offset,
+            })
+        );
+        // The object changed by the update is found after the end of the first revision.
+        let objects = get_objects_since_offset(&locations, &raw_doc, old_offset + 1);
+        assert!(
+            objects.iter().any(|(object_id, _)| *object_id == (3, 0)),
+            "{:?}",
+            objects
+        );
+    }
+
+    #[test]
+    fn object_moved_out_of_object_stream() {
+        let (mut buffer, _, _) = create_updated_file();
+        let xref_stream_offset = find_subslice(&buffer, b"5 0 obj").unwrap();
+        let new_offset = buffer.len();
+        buffer.extend_from_slice(b"3 0 obj\n(newest)\nendobj\n");
+        let xref_offset = buffer.len();
+        buffer.extend_from_slice(
+            format!(
+                "xref\n3 1\n{:010} 00000 n \ntrailer\n<< /Size 6 /Root 1 0 R /Prev {} >>\n\
+                startxref\n{}\n%%EOF\n",
+                new_offset, xref_stream_offset, xref_offset
+            )
+            .as_bytes(),
+        );
+        let raw_doc = Document::load_mem(&buffer).unwrap();
+        let locations = get_object_locations(&buffer, &raw_doc);
+        assert_eq!(
+            locations.get(&(3, 0)),
+            Some(&ObjectLocation::Offset(new_offset))
+        );
+    }
+
+    #[test]
+    fn revision_index_of_offsets() {
+        let (buffer, old_offset, object_stream_offset) = create_updated_file();
+        let revision_ends = get_revision_ends(&buffer);
+        assert_eq!(revision_ends.len(), 2);
+        assert_eq!(revision_ends[1], buffer.len());
+        assert_eq!(get_revision_index(&revision_ends, old_offset), 0);
+        assert_eq!(get_revision_index(&revision_ends, object_stream_offset), 1);
+        // The first revision ends with its end of line.
+        assert_eq!(get_revision_index(&revision_ends, revision_ends[0] - 1), 0);
+        assert_eq!(get_revision_index(&revision_ends, revision_ends[0]), 1);
+    }
+}
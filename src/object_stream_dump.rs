@@ -0,0 +1,122 @@
+use crate::object_offsets::ObjectLocation;
+use lopdf::{Document, Error, ObjectId};
+
+/// Load the objects from the decoded content of an object stream.
+///
+/// `first` is the `First` value from the stream dictionary (the offset of the first object).
+/// When it is not given, it is guessed from where the list of numbers at the start ends.
+/// The returned locations are offsets in `dump`.
+///
+/// lopdf can only parse object streams while loading a document, so the dump is wrapped
+/// in a minimal PDF file.
+pub fn load_object_stream_dump(
+    dump: &[u8],
+    first: Option<usize>,
+) -> Result<(Document, Vec<(ObjectId, ObjectLocation)>), Error> {
+    let (header, guessed_first) = parse_object_stream_header(dump);
+    let first = first.unwrap_or(guessed_first);
+    if header.is_empty() {
+        return Err(Error::Syntax(
+            "No object numbers and offsets found at the start of the object stream.".to_owned(),
+        ));
+    }
+    if first > dump.len() {
+        return Err(Error::Offset(first));
+    }
+
+    // Use an object number that is not in the object stream for the stream itself.
+    let container_number = header
+        .iter()
+        .map(|(object_number, _)| *object_number)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let mut file_bytes = b"%PDF-1.5\n".to_vec();
+    let container_offset = file_bytes.len();
+    file_bytes.extend_from_slice(
+        format!(
+            "{} 0 obj\n<< /Type /ObjStm /N {} /First {} /Length {} >>\nstream\n",
+            container_number,
+            header.len(),
+            first,
+            dump.len()
+        )
+        .as_bytes(),
+    );
+    file_bytes.extend_from_slice(dump);
+    file_bytes.extend_from_slice(b"\nendstream\nendobj\n");
+    let xref_offset = file_bytes.len();
+    file_bytes.extend_from_slice(
+        format!(
+            "xref\n0 1\n0000000000 65535 f \n{} 1\n{:010} 00000 n \n\
+            trailer\n<< /Size {} >>\nstartxref\n{}\n%%EOF\n",
+            container_number,
+            container_offset,
+            container_number + 1,
+            xref_offset
+        )
+        .as_bytes(),
+    );
+
+    let mut raw_doc = Document::load_mem(&file_bytes)?;
+    raw_doc.objects.remove(&(container_number, 0));
+
+    let mut objects = Vec::new();
+    for (object_number, offset) in header {
+        let object_id = (object_number, 0);
+        if raw_doc.objects.contains_key(&object_id) {
+            objects.push((object_id, ObjectLocation::Offset(first + offset)));
+        } else {
+            log::warn!(
+                "Object {} at offset {} could not be parsed.",
+                object_number,
+                first + offset
+            );
+        }
+    }
+    Ok((raw_doc, objects))
+}
+
+/// Read the pairs of object numbers and offsets at the start of an object stream.
+///
+/// Returns the pairs and the position after the last number (skipping whitespace),
+/// which is where the first object normally starts.
+fn parse_object_stream_header(dump: &[u8]) -> (Vec<(u32, usize)>, usize) {
+    let mut numbers = Vec::new();
+    let mut position = 0;
+    // End of the last complete pair.
+    let mut header_end = 0;
+    loop {
+        while dump.get(position).is_some_and(u8::is_ascii_whitespace) {
+            position += 1;
+        }
+        let start = position;
+        while dump.get(position).is_some_and(u8::is_ascii_digit) {
+            position += 1;
+        }
+        let is_number_token = position > start
+            && dump
+                .get(position)
+                .is_none_or(|character| character.is_ascii_whitespace());
+        let number = match std::str::from_utf8(&dump[start..position])
+            .ok()
+            .and_then(|number| number.parse::<usize>().ok())
+        {
+            Some(number) if is_number_token => number,
+            _ => break,
+        };
+        numbers.push(number);
+        if numbers.len() % 2 == 0 {
+            header_end = position;
+        }
+    }
+    let pairs: Vec<(u32, usize)> = numbers
+        .chunks_exact(2)
+        .filter_map(|pair| Some((u32::try_from(pair[0]).ok()?, pair[1])))
+        .collect();
+    let mut first = header_end;
+    while dump.get(first).is_some_and(u8::is_ascii_whitespace) {
+        first += 1;
+    }
+    (pairs, first)
+}
@@ -0,0 +1,169 @@
+use lopdf::{Dictionary, Document, Error, Object, ObjectId};
+
+/// Attributes a page can inherit from its ancestor `Pages` nodes.
+///
+/// See p79 (Table 30) in PDF v1.7 Spec.
+pub static INHERITABLE_ATTRIBUTES: [&str; 4] = ["Resources", "MediaBox", "CropBox", "Rotate"];
+
+#[derive(Debug, Clone)]
+pub struct PageTreeNode {
+    pub object_id: ObjectId,
+    pub kind: PageTreeNodeKind,
+    /// The `/Count` value, only set for `Pages` nodes.
+    pub count: Option<i64>,
+    pub kids: Vec<PageTreeNode>,
+    /// Where each of the `INHERITABLE_ATTRIBUTES` comes from, in the same order.
+    pub attributes: Vec<AttributeSource>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageTreeNodeKind {
+    Pages,
+    Page,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttributeSource {
+    /// Defined on the node itself, no ancestor defines it.
+    Own,
+    /// Defined on the node itself, replacing the value of this ancestor.
+    Overrides(ObjectId),
+    /// Not defined on the node, the value of this ancestor is used.
+    Inherited(ObjectId),
+    /// Not defined on the node or any of its ancestors.
+    Missing,
+}
+
+impl PageTreeNode {
+    /// All `Page` nodes below (and including) this node, in page order.
+    pub fn leaf_pages(&self) -> Vec<&PageTreeNode> {
+        match self.kind {
+            PageTreeNodeKind::Page => vec![self],
+            PageTreeNodeKind::Pages => self.kids.iter().flat_map(|kid| kid.leaf_pages()).collect(),
+        }
+    }
+}
+
+/// Walk the page tree starting from `/Root /Pages`.
+pub fn load_page_tree(raw_doc: &Document) -> Result<PageTreeNode, Error> {
+    let root_id = raw_doc.catalog()?.get(b"Pages")?.as_reference()?;
+    let mut visited = Vec::new();
+    let inherited = [None; INHERITABLE_ATTRIBUTES.len()];
+    load_page_tree_node(raw_doc, root_id, &inherited, &mut visited).ok_or(Error::ObjectNotFound)
+}
+
+/// Find the `Page` node of a page, the first page is 1.
+///
+/// Pages are counted by walking the page tree, when `Count` disagrees a warning is logged.
+pub fn get_page_node(raw_doc: &Document, page_number: usize) -> Result<PageTreeNode, Error> {
+    let page_tree = load_page_tree(raw_doc)?;
+    let pages = page_tree.leaf_pages();
+    if let Some(count) = page_tree.count {
+        if count != pages.len() as i64 {
+            log::warn!(
+                "Page tree `Count` is {}, but {} pages were found. Using the pages found.",
+                count,
+                pages.len()
+            );
+        }
+    }
+    match page_number
+        .checked_sub(1)
+        .and_then(|index| pages.get(index))
+    {
+        Some(page) => Ok((*page).clone()),
+        None => {
+            log::error!(
+                "Page {} does not exist, the document has {} pages (starting at 1).",
+                page_number,
+                pages.len()
+            );
+            Err(Error::PageNumberNotFound(
+                u32::try_from(page_number).unwrap_or(u32::MAX),
+            ))
+        }
+    }
+}
+
+fn load_page_tree_node(
+    raw_doc: &Document,
+    object_id: ObjectId,
+    inherited: &[Option<ObjectId>; INHERITABLE_ATTRIBUTES.len()],
+    visited: &mut Vec<ObjectId>,
+) -> Option<PageTreeNode> {
+    if visited.contains(&object_id) {
+        log::warn!(
+            "Page tree contains a loop at ({},{}).",
+            object_id.0,
+            object_id.1
+        );
+        return None;
+    }
+    visited.push(object_id);
+    let dict = match raw_doc.get_dictionary(object_id) {
+        Ok(dict) => dict,
+        Err(_) => {
+            log::warn!(
+                "Page tree node ({},{}) is not a dictionary or does not exist.",
+                object_id.0,
+                object_id.1
+            );
+            return None;
+        }
+    };
+    // Some writers omit `/Type`, so fall back to checking for `/Kids`.
+    let kind = if dict.type_is(b"Pages") || (!dict.type_is(b"Page") && dict.has(b"Kids")) {
+        PageTreeNodeKind::Pages
+    } else {
+        PageTreeNodeKind::Page
+    };
+
+    let mut attributes = Vec::with_capacity(INHERITABLE_ATTRIBUTES.len());
+    let mut kid_inherited = *inherited;
+    for (index, attribute) in INHERITABLE_ATTRIBUTES.iter().enumerate() {
+        let has_own = dict.has(attribute.as_bytes());
+        attributes.push(match (has_own, inherited[index]) {
+            (true, None) => AttributeSource::Own,
+            (true, Some(ancestor)) => AttributeSource::Overrides(ancestor),
+            (false, Some(ancestor)) => AttributeSource::Inherited(ancestor),
+            (false, None) => AttributeSource::Missing,
+        });
+        if has_own {
+            kid_inherited[index] = Some(object_id);
+        }
+    }
+
+    let kids = match kind {
+        PageTreeNodeKind::Page => Vec::new(),
+        PageTreeNodeKind::Pages => get_kids(raw_doc, dict)
+            .iter()
+            .filter_map(|kid| match kid {
+                Object::Reference(kid_id) => {
+                    load_page_tree_node(raw_doc, *kid_id, &kid_inherited, visited)
+                }
+                _ => {
+                    log::warn!("Page tree `Kids` should only contain indirect references.");
+                    None
+                }
+            })
+            .collect(),
+    };
+
+    Some(PageTreeNode {
+        object_id,
+        kind,
+        count: dict
+            .get_deref(b"Count", raw_doc)
+            .and_then(Object::as_i64)
+            .ok(),
+        kids,
+        attributes,
+    })
+}
+
+fn get_kids<'a>(raw_doc: &'a Document, dict: &'a Dictionary) -> &'a [Object] {
+    dict.get_deref(b"Kids", raw_doc)
+        .and_then(Object::as_array)
+        .map(|kids| kids.as_slice())
+        .unwrap_or_default()
+}
@@ -0,0 +1,36 @@
+use std::io::IsTerminal;
+use std::process::{Child, Command, Stdio};
+
+/// Start a pager that shows everything written to its stdin.
+///
+/// Uses `$PAGER` and falls back to `less`.
+/// Returns `None` if no pager should be used (like when stdout is not a terminal)
+/// or the pager could not be started.
+pub fn start_pager() -> Option<Child> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let pager_command = std::env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| "less".to_owned());
+    let mut pager_parts = pager_command.split_whitespace();
+    let pager_program = pager_parts.next().unwrap_or("less");
+
+    match Command::new(pager_program)
+        .args(pager_parts)
+        // Let `less` keep the colors and quit when everything fits on one screen.
+        .env(
+            "LESS",
+            std::env::var("LESS").unwrap_or_else(|_| "FRX".to_owned()),
+        )
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(pager) => Some(pager),
+        Err(err) => {
+            log::warn!("Could not start pager `{}`: {}", pager_command, err);
+            None
+        }
+    }
+}
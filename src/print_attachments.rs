@@ -0,0 +1,146 @@
+use crate::destinations::get_name_tree_entries;
+use crate::print_tree::decode_text_string;
+use lopdf::{Dictionary, Document, Error, Object, Stream};
+use std::io::Write;
+use std::path::Path;
+use yansi::{Paint, Style};
+
+/// Print the embedded files in the `EmbeddedFiles` name tree.
+///
+/// When `extract_dir` is set, the (decoded) files are written to that directory.
+///
+/// See p102 (7.11.4 Embedded File Streams) in PDF v1.7 Spec.
+pub fn print_attachments(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    extract_dir: Option<&Path>,
+) -> Result<(), Error> {
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+    let extra_info_style = Style::default().italic();
+
+    let name_tree = raw_doc
+        .catalog()
+        .ok()
+        .and_then(|catalog| catalog.get(b"Names").ok())
+        .and_then(|names| raw_doc.dereference(names).ok())
+        .and_then(|(_, names)| names.as_dict().ok())
+        .and_then(|names| names.get(b"EmbeddedFiles").ok());
+    let entries = match name_tree {
+        Some(name_tree) => get_name_tree_entries(raw_doc, name_tree),
+        None => Vec::new(),
+    };
+
+    writeln!(out, "--- {} ---", Paint::cyan("Attachments").bold())?;
+    if entries.is_empty() {
+        writeln!(
+            out,
+            "{}",
+            extra_info_style.paint("No embedded files found.")
+        )?;
+        return Ok(());
+    }
+    if let Some(extract_dir) = extract_dir {
+        std::fs::create_dir_all(extract_dir)?;
+    }
+    for (index, (name, file_spec)) in entries.into_iter().enumerate() {
+        let name = decode_text_string(name);
+        let file_spec = match raw_doc
+            .dereference(file_spec)
+            .and_then(|(_, file_spec)| file_spec.as_dict())
+        {
+            Ok(file_spec) => file_spec,
+            Err(_) => {
+                log::warn!(
+                    "Embedded file `{}` has no file specification dictionary.",
+                    name
+                );
+                continue;
+            }
+        };
+        let file_name = get_file_name(file_spec).unwrap_or_else(|| name.clone());
+        let stream = match get_embedded_file_stream(raw_doc, file_spec) {
+            Some(stream) => stream,
+            None => {
+                writeln!(
+                    out,
+                    "{} {}",
+                    label_style.paint(&file_name),
+                    extra_info_style.paint("(no embedded file stream)")
+                )?;
+                continue;
+            }
+        };
+        let mime_type = stream
+            .dict
+            .get(b"Subtype")
+            .and_then(Object::as_name_str)
+            .unwrap_or("-");
+        // lopdf returns an error for streams without filters.
+        let content = if stream.dict.has(b"Filter") {
+            stream.decompressed_content()
+        } else {
+            Ok(stream.content.clone())
+        };
+        let size = match &content {
+            Ok(content) => format!("{} bytes", content.len()),
+            Err(_) => format!("{} bytes (could not be decoded)", stream.content.len()),
+        };
+        writeln!(
+            out,
+            "{} {} {}",
+            label_style.paint(&file_name),
+            value_style.paint(size),
+            extra_info_style.paint(format!("({})", mime_type)),
+        )?;
+
+        if let Some(extract_dir) = extract_dir {
+            let content = match content {
+                Ok(content) => content,
+                Err(err) => {
+                    log::warn!("Could not decode embedded file `{}`: {}", file_name, err);
+                    continue;
+                }
+            };
+            // Only use the last part of the name, it should not write outside the directory.
+            let output_name = Path::new(&file_name)
+                .file_name()
+                .map(|output_name| output_name.to_os_string())
+                .unwrap_or_else(|| format!("attachment_{}", index + 1).into());
+            let output_path = extract_dir.join(output_name);
+            std::fs::write(&output_path, content)?;
+            writeln!(
+                out,
+                "  {}",
+                extra_info_style.paint(format!("Extracted to `{}`", output_path.display()))
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Get the file name, preferring the Unicode name (`UF`).
+fn get_file_name(file_spec: &Dictionary) -> Option<String> {
+    [&b"UF"[..], b"F"]
+        .iter()
+        .find_map(|key| match file_spec.get(key) {
+            Ok(Object::String(file_name, _)) => Some(decode_text_string(file_name)),
+            _ => None,
+        })
+}
+
+fn get_embedded_file_stream<'a>(
+    raw_doc: &'a Document,
+    file_spec: &'a Dictionary,
+) -> Option<&'a Stream> {
+    let embedded_files = raw_doc
+        .dereference(file_spec.get(b"EF").ok()?)
+        .ok()?
+        .1
+        .as_dict()
+        .ok()?;
+    [&b"UF"[..], b"F"].iter().find_map(|key| {
+        let (_, stream) = raw_doc.dereference(embedded_files.get(key).ok()?).ok()?;
+        stream.as_stream().ok()
+    })
+}
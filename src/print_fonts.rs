@@ -0,0 +1,140 @@
+use lopdf::{Dictionary, Document, Error, Object};
+use std::io::Write;
+use yansi::{Color, Paint, Style};
+
+/// Print all fonts with their encoding and if they have a `ToUnicode` CMap.
+///
+/// Text extraction needs a `ToUnicode` CMap or an encoding with known glyph names,
+/// so this explains most fonts that extract as garbled text.
+/// Only fonts stored as indirect objects are listed.
+pub fn print_fonts(out: &mut dyn Write, raw_doc: &Document) -> Result<(), Error> {
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+    let extra_info_style = Style::default().italic();
+    let warning_style = Style::new(Color::Yellow).bold();
+
+    writeln!(out, "--- {} ---", Paint::cyan("Fonts").bold())?;
+    let mut font_count = 0;
+    for (object_id, object) in raw_doc.objects.iter() {
+        let font = match object {
+            Object::Dictionary(dict) if dict.type_is(b"Font") => dict,
+            _ => continue,
+        };
+        // Descendant fonts are listed as part of their Type0 font.
+        if is_descendant_font(font) {
+            continue;
+        }
+        font_count += 1;
+        let base_font = get_name(font, b"BaseFont").unwrap_or_else(|| "-".to_owned());
+        let subtype = get_name(font, b"Subtype").unwrap_or_else(|| "?".to_owned());
+        let encoding = get_encoding_description(raw_doc, font);
+        let has_to_unicode = font.has(b"ToUnicode");
+        let to_unicode = if has_to_unicode {
+            value_style.paint("yes")
+        } else {
+            warning_style.paint("no")
+        };
+        writeln!(
+            out,
+            "{} {} {}: encoding {}, ToUnicode {}",
+            value_style.paint(format!("({},{})", object_id.0, object_id.1)),
+            label_style.paint(base_font),
+            extra_info_style.paint(format!("<{}>", subtype)),
+            value_style.paint(encoding.description),
+            to_unicode,
+        )?;
+        if !has_to_unicode && !encoding.has_known_glyph_names {
+            writeln!(
+                out,
+                "  {}",
+                extra_info_style.paint(
+                    "Text can not be extracted reliably: no `ToUnicode` and no standard encoding."
+                )
+            )?;
+        }
+    }
+    if font_count == 0 {
+        writeln!(out, "{}", extra_info_style.paint("No fonts found."))?;
+    }
+    Ok(())
+}
+
+struct EncodingInfo {
+    /// Like `WinAnsiEncoding` or `custom (base StandardEncoding, 12 differences)`.
+    description: String,
+    /// If the characters can be mapped to Unicode without a `ToUnicode` CMap.
+    has_known_glyph_names: bool,
+}
+
+/// Describe the `Encoding` of a font.
+///
+/// See p262 (9.6.6 Character Encoding) and p294 (9.7.5 CMaps) in PDF v1.7 Spec.
+fn get_encoding_description(raw_doc: &Document, font: &Dictionary) -> EncodingInfo {
+    let is_type0 = get_name(font, b"Subtype").as_deref() == Some("Type0");
+    let encoding = font
+        .get(b"Encoding")
+        .and_then(|encoding| raw_doc.dereference(encoding))
+        .map(|(_, encoding)| encoding);
+    match encoding {
+        Ok(Object::Name(name)) => {
+            let name = String::from_utf8_lossy(name).to_string();
+            // `Identity-H` and `Identity-V` map to glyph ids, not to characters.
+            let has_known_glyph_names = !(is_type0 && name.starts_with("Identity"));
+            EncodingInfo {
+                description: name,
+                has_known_glyph_names,
+            }
+        }
+        Ok(Object::Dictionary(dict)) => {
+            let base_encoding = get_name(dict, b"BaseEncoding");
+            let difference_count = match dict.get(b"Differences") {
+                // Integers start a range of codes, every name is one code.
+                Ok(Object::Array(differences)) => differences
+                    .iter()
+                    .filter(|item| matches!(item, Object::Name(_)))
+                    .count(),
+                _ => 0,
+            };
+            let mut details = Vec::new();
+            if let Some(base_encoding) = base_encoding {
+                details.push(format!("base {}", base_encoding));
+            }
+            details.push(format!("{} differences", difference_count));
+            EncodingInfo {
+                description: format!("custom ({})", details.join(", ")),
+                has_known_glyph_names: true,
+            }
+        }
+        Ok(Object::Stream(stream)) => EncodingInfo {
+            description: match get_name(&stream.dict, b"CMapName") {
+                Some(cmap_name) => format!("embedded CMap {}", cmap_name),
+                None => "embedded CMap".to_owned(),
+            },
+            has_known_glyph_names: false,
+        },
+        Ok(_) => EncodingInfo {
+            description: "invalid".to_owned(),
+            has_known_glyph_names: false,
+        },
+        Err(_) => EncodingInfo {
+            description: "none (built-in encoding of the font)".to_owned(),
+            // Symbolic fonts often use their own codes.
+            has_known_glyph_names: !is_type0
+                && get_name(font, b"Subtype").as_deref() != Some("Type3"),
+        },
+    }
+}
+
+fn is_descendant_font(font: &Dictionary) -> bool {
+    matches!(
+        get_name(font, b"Subtype").as_deref(),
+        Some("CIDFontType0") | Some("CIDFontType2")
+    )
+}
+
+fn get_name(dict: &Dictionary, key: &[u8]) -> Option<String> {
+    dict.get(key)
+        .and_then(Object::as_name)
+        .ok()
+        .map(|name| String::from_utf8_lossy(name).to_string())
+}
@@ -0,0 +1,251 @@
+use crate::page_tree::{load_page_tree, AttributeSource};
+use crate::print_tree::get_type_label;
+use crate::references::get_references;
+use lopdf::{Dictionary, Document, Error, Object, ObjectId, StringFormat};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use yansi::{Paint, Style};
+
+#[derive(Debug, Default, Clone)]
+struct ObjectSize {
+    /// Where the object is referenced from, like `Contents` or `Font F1`.
+    usage: String,
+    /// Serialized bytes of this object and the objects it references, with their streams.
+    bytes: usize,
+    object_count: usize,
+    stream_count: usize,
+    /// Pages (1-based) that reference this object.
+    pages: Vec<usize>,
+}
+
+/// Print the objects referenced by pages that take up the most space.
+///
+/// The size of an object is its serialized size, with the (stored) bytes of its stream,
+/// plus the size of all objects it references, directly or indirectly.
+/// Every object is only counted once per object.
+pub fn print_object_sizes(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    limit: usize,
+) -> Result<(), Error> {
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+
+    let page_tree = load_page_tree(raw_doc)?;
+    let mut sizes: BTreeMap<ObjectId, ObjectSize> = BTreeMap::new();
+    for (page_index, page) in page_tree.leaf_pages().iter().enumerate() {
+        let page_dict = raw_doc.get_dictionary(page.object_id)?;
+        let mut top_level_ids = BTreeMap::new();
+        for (key, value) in page_dict.iter() {
+            if key != b"Parent" && key != b"Resources" {
+                add_top_level_ids(value, &String::from_utf8_lossy(key), &mut top_level_ids);
+            }
+        }
+        // Use the resources themselves (fonts, images, ...) instead of the `Resources` dictionary.
+        // `Resources` is the first of the `INHERITABLE_ATTRIBUTES`.
+        let resources_owner = match page.attributes[0] {
+            AttributeSource::Inherited(ancestor) => ancestor,
+            _ => page.object_id,
+        };
+        if let Ok(resources) = raw_doc
+            .get_dictionary(resources_owner)
+            .and_then(|owner| owner.get_deref(b"Resources", raw_doc))
+            .and_then(Object::as_dict)
+        {
+            for (category, category_dict) in resources.iter() {
+                if let Ok(category_dict) = raw_doc
+                    .dereference(category_dict)
+                    .and_then(|(_, category_dict)| category_dict.as_dict())
+                {
+                    for (name, value) in category_dict.iter() {
+                        let usage = format!(
+                            "{} {}",
+                            String::from_utf8_lossy(category),
+                            String::from_utf8_lossy(name)
+                        );
+                        add_top_level_ids(value, &usage, &mut top_level_ids);
+                    }
+                }
+            }
+        }
+        for (object_id, usage) in top_level_ids {
+            let size = sizes.entry(object_id).or_insert_with(|| {
+                let mut size = ObjectSize {
+                    usage,
+                    ..Default::default()
+                };
+                let mut visited = BTreeSet::new();
+                add_object_bytes(raw_doc, object_id, &mut visited, &mut size);
+                size
+            });
+            size.pages.push(page_index + 1);
+        }
+    }
+
+    let mut sorted_sizes: Vec<_> = sizes.into_iter().collect();
+    sorted_sizes.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then(a.0.cmp(&b.0)));
+
+    writeln!(out, "--- {} ---", Paint::cyan("Largest objects").bold())?;
+    for (object_id, size) in sorted_sizes.iter().take(limit) {
+        let type_label = raw_doc
+            .objects
+            .get(object_id)
+            .and_then(get_type_label)
+            .map(|type_label| format!(" <{}>", type_label))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{} {}{}: {} {}",
+            value_style.paint(format!("({},{})", object_id.0, object_id.1)),
+            label_style.paint(&size.usage),
+            type_label,
+            value_style.paint(format!("{} bytes", size.bytes)),
+            Style::default().italic().paint(format!(
+                "({} objects, {} streams, used on pages: {})",
+                size.object_count,
+                size.stream_count,
+                format_page_list(&size.pages)
+            ))
+        )?;
+    }
+    if sorted_sizes.len() > limit {
+        writeln!(
+            out,
+            "{}",
+            Style::default().dimmed().italic().paint(format!(
+                "... {} smaller objects",
+                sorted_sizes.len() - limit
+            ))
+        )?;
+    }
+    Ok(())
+}
+
+/// Add the references in `obj` with their usage, the first usage of an object is kept.
+fn add_top_level_ids(obj: &Object, usage: &str, top_level_ids: &mut BTreeMap<ObjectId, String>) {
+    for reference in get_references(obj) {
+        top_level_ids
+            .entry(reference.object_id)
+            .or_insert_with(|| usage.to_owned());
+    }
+}
+
+/// Add the size of `object_id` and all objects reachable from it to `size`.
+///
+/// References to pages are not followed, as they would pull in the whole document.
+fn add_object_bytes(
+    raw_doc: &Document,
+    object_id: ObjectId,
+    visited: &mut BTreeSet<ObjectId>,
+    size: &mut ObjectSize,
+) {
+    if !visited.insert(object_id) {
+        return;
+    }
+    let obj = match raw_doc.objects.get(&object_id) {
+        Some(obj) => obj,
+        None => return,
+    };
+    // Like `12 0 obj` and `endobj` around the object.
+    let header = format!("{} {} obj\n", object_id.0, object_id.1);
+    size.bytes += header.len() + get_serialized_size(obj) + "\nendobj\n".len();
+    size.object_count += 1;
+    if matches!(obj, Object::Stream(_)) {
+        size.stream_count += 1;
+    }
+    for reference in get_references(obj) {
+        if !is_page_tree_node(raw_doc, reference.object_id) {
+            add_object_bytes(raw_doc, reference.object_id, visited, size);
+        }
+    }
+}
+
+/// Get the amount of bytes the object takes when it is written to a file.
+///
+/// Streams are counted with their stored (compressed) content. Escapes in names and strings
+/// are not counted, so the size can be a bit smaller than in the file.
+fn get_serialized_size(obj: &Object) -> usize {
+    match obj {
+        Object::Null => "null".len(),
+        Object::Boolean(value) => value.to_string().len(),
+        Object::Integer(value) => value.to_string().len(),
+        Object::Real(value) => value.to_string().len(),
+        // Like `/Name`.
+        Object::Name(name) => 1 + name.len(),
+        // Like `(text)` or `<74657874>`.
+        Object::String(value, StringFormat::Literal) => 2 + value.len(),
+        Object::String(value, StringFormat::Hexadecimal) => 2 + 2 * value.len(),
+        // Like `[1 2 3]`.
+        Object::Array(list) => {
+            2 + list.iter().map(get_serialized_size).sum::<usize>() + list.len().saturating_sub(1)
+        }
+        Object::Dictionary(dict) => get_dictionary_size(dict),
+        // Like `<<...>>\nstream\n...\nendstream`.
+        Object::Stream(stream) => {
+            get_dictionary_size(&stream.dict)
+                + "\nstream\n".len()
+                + stream.content.len()
+                + "\nendstream".len()
+        }
+        // Like `12 0 R`.
+        Object::Reference(object_id) => format!("{} {} R", object_id.0, object_id.1).len(),
+    }
+}
+
+/// The size of `<</Key value ...>>`.
+fn get_dictionary_size(dict: &Dictionary) -> usize {
+    4 + dict
+        .iter()
+        .map(|(key, value)| 1 + key.len() + 1 + get_serialized_size(value))
+        .sum::<usize>()
+}
+
+fn is_page_tree_node(raw_doc: &Document, object_id: ObjectId) -> bool {
+    raw_doc
+        .get_dictionary(object_id)
+        .map(|dict| dict.type_is(b"Page") || dict.type_is(b"Pages"))
+        .unwrap_or(false)
+}
+
+/// Format a list of page numbers, shortening long lists.
+fn format_page_list(pages: &[usize]) -> String {
+    let page_strings: Vec<_> = pages.iter().map(|page| page.to_string()).collect();
+    if page_strings.len() > 5 {
+        format!(
+            "{}, ... ({} pages)",
+            page_strings[..4].join(", "),
+            page_strings.len()
+        )
+    } else {
+        page_strings.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, Stream};
+
+    #[test]
+    fn serialized_size_matches_pdf_syntax() {
+        let dict = dictionary! {
+            "Type" => "Font",
+            "Widths" => vec![Object::Integer(250), Object::Integer(1000)],
+            "FontFile" => Object::Reference((12, 0)),
+            "Name" => Object::string_literal("F1"),
+        };
+        let expected = "<</Type /Font/Widths [250 1000]/FontFile 12 0 R/Name (F1)>>".len();
+        assert_eq!(
+            get_serialized_size(&Object::Dictionary(dict.clone())),
+            expected
+        );
+
+        let stream = Stream::new(dict, vec![0; 100]);
+        // The `Length` entry is added by `Stream::new`.
+        let length_entry = "/Length 100".len();
+        assert_eq!(
+            get_serialized_size(&Object::Stream(stream)),
+            expected + length_entry + "\nstream\n".len() + 100 + "\nendstream".len()
+        );
+    }
+}
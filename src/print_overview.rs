@@ -0,0 +1,169 @@
+use crate::page_tree::load_page_tree;
+use crate::print_tree::{decode_text_string, TreeChars};
+use lopdf::{Dictionary, Document, Error, Object};
+use std::io::Write;
+use unicode_width::UnicodeWidthStr;
+use yansi::{Paint, Style};
+
+/// Print the most important facts about the file in a small box.
+pub fn print_overview(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    file_name: String,
+    file_bytes: &[u8],
+    tree_chars: &TreeChars,
+) -> Result<(), Error> {
+    let catalog = raw_doc.catalog().ok();
+    let info = raw_doc
+        .trailer
+        .get(b"Info")
+        .and_then(|info| raw_doc.dereference(info))
+        .and_then(|(_, info)| info.as_dict())
+        .ok();
+
+    let mut font_count = 0;
+    let mut image_count = 0;
+    let mut has_javascript = false;
+    for object in raw_doc.objects.values() {
+        let dict = match object {
+            Object::Dictionary(dict) => dict,
+            Object::Stream(stream) => &stream.dict,
+            _ => continue,
+        };
+        if dict.type_is(b"Font") {
+            font_count += 1;
+        }
+        if is_name(dict, b"Subtype", b"Image") {
+            image_count += 1;
+        }
+        if is_name(dict, b"S", b"JavaScript") || dict.has(b"JS") {
+            has_javascript = true;
+        }
+    }
+    if let Some(names) = catalog
+        .and_then(|catalog| catalog.get(b"Names").ok())
+        .and_then(|names| raw_doc.dereference(names).ok())
+        .and_then(|(_, names)| names.as_dict().ok())
+    {
+        has_javascript |= names.has(b"JavaScript");
+    }
+
+    let page_count = match load_page_tree(raw_doc) {
+        Ok(page_tree) => page_tree.leaf_pages().len().to_string(),
+        Err(_) => "unknown (page tree could not be loaded)".to_owned(),
+    };
+    let encryption = match raw_doc.trailer.get(b"Encrypt") {
+        Ok(encrypt) => match raw_doc
+            .dereference(encrypt)
+            .and_then(|(_, encrypt)| encrypt.as_dict())
+            .and_then(|encrypt| encrypt.get(b"Filter"))
+            .and_then(Object::as_name_str)
+        {
+            Ok(filter) => format!("yes ({})", filter),
+            Err(_) => "yes".to_owned(),
+        },
+        Err(_) => "no".to_owned(),
+    };
+    let has_forms = catalog.is_some_and(|catalog| catalog.has(b"AcroForm"));
+    let has_outline = catalog
+        .and_then(|catalog| catalog.get(b"Outlines").ok())
+        .and_then(|outlines| raw_doc.dereference(outlines).ok())
+        .and_then(|(_, outlines)| outlines.as_dict().ok())
+        .is_some_and(|outlines| outlines.has(b"First"));
+
+    let rows = vec![
+        ("Version", raw_doc.version.clone()),
+        ("Pages", page_count),
+        ("Encrypted", encryption),
+        ("Producer", get_info_string(raw_doc, info, b"Producer")),
+        ("Creator", get_info_string(raw_doc, info, b"Creator")),
+        ("Fonts", font_count.to_string()),
+        ("Images", image_count.to_string()),
+        ("JavaScript", yes_no(has_javascript)),
+        ("Forms", yes_no(has_forms)),
+        ("Outline", yes_no(has_outline)),
+        ("File size", format!("{} bytes", file_bytes.len())),
+    ];
+    print_box(out, &file_name, &rows, tree_chars)?;
+    Ok(())
+}
+
+/// Print the rows in a box like the legend, with the title in the top border.
+fn print_box(
+    out: &mut dyn Write,
+    title: &str,
+    rows: &[(&str, String)],
+    tree_chars: &TreeChars,
+) -> std::io::Result<()> {
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+
+    let label_width = rows
+        .iter()
+        .map(|(label, _)| label.width())
+        .max()
+        .unwrap_or(0);
+    let value_width = rows
+        .iter()
+        .map(|(_, value)| value.width())
+        .max()
+        .unwrap_or(0);
+    // Space, label, `: `, value and space.
+    let table_width = (label_width + value_width + 4).max(title.width() + 4);
+    let border_width = table_width - title.width() - 2;
+    writeln!(
+        out,
+        "{}{} {} {}{}",
+        tree_chars.box_top_left,
+        tree_chars.box_horizontal.repeat(border_width / 2),
+        Paint::cyan(title).bold(),
+        tree_chars
+            .box_horizontal
+            .repeat(border_width - border_width / 2),
+        tree_chars.box_top_right
+    )?;
+    for (label, value) in rows {
+        let text_width = label_width + 2 + value.width();
+        writeln!(
+            out,
+            "{} {}{}: {}{}{}",
+            tree_chars.box_vertical,
+            label_style.paint(label),
+            " ".repeat(label_width - label.width()),
+            value_style.paint(value),
+            " ".repeat(table_width - text_width - 1),
+            tree_chars.box_vertical
+        )?;
+    }
+    writeln!(
+        out,
+        "{}{}{}",
+        tree_chars.box_bottom_left,
+        tree_chars.box_horizontal.repeat(table_width),
+        tree_chars.box_bottom_right
+    )?;
+    Ok(())
+}
+
+fn get_info_string(raw_doc: &Document, info: Option<&Dictionary>, key: &[u8]) -> String {
+    match info
+        .and_then(|info| info.get(key).ok())
+        .and_then(|value| raw_doc.dereference(value).ok())
+    {
+        // Control characters (like new lines) would break the box.
+        Some((_, Object::String(value, _))) => {
+            decode_text_string(value).replace(char::is_control, " ")
+        }
+        _ => "-".to_owned(),
+    }
+}
+
+fn is_name(dict: &Dictionary, key: &[u8], name: &[u8]) -> bool {
+    dict.get(key)
+        .and_then(Object::as_name)
+        .is_ok_and(|value| value == name)
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_owned()
+}
@@ -1,55 +1,419 @@
-use lopdf::{Document, Error};
+use crate::encryption::{describe_permissions, get_encryption_info, is_encrypted, EncryptionInfo};
+use crate::json_tree::{get_unresolved_json_value, JsonValue};
+use crate::object_offsets::{find_duplicate_objects, get_revision_ends, get_revision_index};
+use crate::references::get_references;
+use lopdf::{Document, Error, Object, ObjectId};
+use serde::Serialize;
+use std::collections::{BTreeSet, VecDeque};
+use std::io::Write;
 use yansi::{Paint, Style};
 
-pub fn print_pdf_info(raw_doc: &Document) -> Result<(), Error> {
+pub fn print_pdf_info(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    file_bytes: &[u8],
+    decrypted: bool,
+) -> Result<(), Error> {
     let label_style = Style::default();
     let value_style = Style::default().bold();
 
-    println!("--- {} ---", Paint::cyan("PDF Info").bold());
-    println!(
+    writeln!(out, "--- {} ---", Paint::cyan("PDF Info").bold())?;
+    let version = match get_catalog_version(raw_doc) {
+        Some(catalog_version) if catalog_version != raw_doc.version => {
+            format!("header {}, catalog {}", raw_doc.version, catalog_version)
+        }
+        _ => raw_doc.version.clone(),
+    };
+    writeln!(
+        out,
         "{}: {}",
         label_style.paint("Version"),
-        value_style.paint(raw_doc.version.to_string())
-    );
-    println!(
+        value_style.paint(version)
+    )?;
+    print_header_info(out, file_bytes)?;
+    print_size_info(out, raw_doc, file_bytes)?;
+    writeln!(
+        out,
         "{}: {}",
         label_style.paint("Trailer"),
         value_style.paint(format!("{:#?}", raw_doc.trailer))
-    );
-    println!(
+    )?;
+    print_encryption_info(out, raw_doc, decrypted)?;
+    writeln!(
+        out,
         "{}: {}",
         label_style.paint("Reference Table length"),
         value_style.paint(raw_doc.reference_table.entries.len())
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "{}: {}",
         label_style.paint("Reference Table size"),
         value_style.paint(raw_doc.reference_table.size)
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "{}: {}",
         label_style.paint("Objects amount"),
         value_style.paint(raw_doc.objects.len())
-    );
-    println!(
+    )?;
+    let reachable_objects = get_reachable_objects(raw_doc);
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("Reachable objects"),
+        value_style.paint(reachable_objects.len())
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("Unreachable objects"),
+        value_style.paint(
+            raw_doc
+                .objects
+                .keys()
+                .filter(|object_id| !reachable_objects.contains(object_id))
+                .count()
+        )
+    )?;
+    print_duplicate_objects(out, file_bytes)?;
+    writeln!(
+        out,
         "{}: {}",
         label_style.paint("Max Object Id"),
         value_style.paint(raw_doc.max_id)
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "{}: {}",
         label_style.paint("Max Bookmark Id"),
         value_style.paint(raw_doc.max_bookmark_id)
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "{}: {}",
         label_style.paint("Bookmark amount"),
         value_style.paint(raw_doc.bookmarks.len())
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "{}: {}",
         label_style.paint("Bookmark Table size"),
         value_style.paint(raw_doc.bookmark_table.len())
-    );
+    )?;
+    Ok(())
+}
+
+/// The facts of `print_pdf_info` that scripts are interested in.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonInfo {
+    /// The version in the file header.
+    pub version: String,
+    /// The `Version` of the catalog, which overrides the header version when it is later.
+    pub catalog_version: Option<String>,
+    /// The trailer, references are not followed.
+    pub trailer: JsonValue,
+    pub encrypted: bool,
+    /// `None` when not encrypted or the `Encrypt` entry is not a dictionary.
+    pub encryption: Option<EncryptionInfo>,
+    /// Strings and streams were decrypted with the (empty) password.
+    pub decrypted: bool,
+    pub reference_table_length: usize,
+    pub reference_table_size: u32,
+    pub objects_amount: usize,
+    pub max_id: u32,
+    pub max_bookmark_id: u32,
+    pub bookmark_amount: usize,
+    pub bookmark_table_size: usize,
+}
+
+/// Print the general info about the PDF as JSON.
+pub fn print_pdf_info_json(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    decrypted: bool,
+) -> Result<(), Error> {
+    let json_info = JsonInfo {
+        version: raw_doc.version.clone(),
+        catalog_version: get_catalog_version(raw_doc),
+        trailer: get_unresolved_json_value(&Object::Dictionary(raw_doc.trailer.clone())),
+        encrypted: is_encrypted(raw_doc),
+        encryption: get_encryption_info(raw_doc),
+        decrypted,
+        reference_table_length: raw_doc.reference_table.entries.len(),
+        reference_table_size: raw_doc.reference_table.size,
+        objects_amount: raw_doc.objects.len(),
+        max_id: raw_doc.max_id,
+        max_bookmark_id: raw_doc.max_bookmark_id,
+        bookmark_amount: raw_doc.bookmarks.len(),
+        bookmark_table_size: raw_doc.bookmark_table.len(),
+    };
+    let json_string = serde_json::to_string_pretty(&json_info)
+        .map_err(|err| Error::Syntax(format!("Could not create JSON: {}", err)))?;
+    writeln!(out, "{}", json_string)?;
+    Ok(())
+}
+
+/// The `Version` name in the catalog, used by writers that append an update to a file
+/// instead of rewriting the header.
+///
+/// See 7.7.2 (Document Catalog) in PDF v1.7 Spec.
+fn get_catalog_version(raw_doc: &Document) -> Option<String> {
+    let catalog = raw_doc.catalog().ok()?;
+    let version = catalog.get_deref(b"Version", raw_doc).ok()?;
+    version
+        .as_name_str()
+        .ok()
+        .map(ToOwned::to_owned)
+        .or_else(|| {
+            log::warn!("Catalog `Version` is not a name.");
+            None
+        })
+}
+
+/// Print if the document is encrypted, with the security handler and the permissions.
+///
+/// Without `decrypted` strings and streams are printed as stored.
+fn print_encryption_info(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    decrypted: bool,
+) -> std::io::Result<()> {
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+
+    if !is_encrypted(raw_doc) {
+        writeln!(
+            out,
+            "{}: {}",
+            label_style.paint("Encrypted"),
+            value_style.paint("no")
+        )?;
+        return Ok(());
+    }
+    let encryption_info = match get_encryption_info(raw_doc) {
+        Some(encryption_info) => encryption_info,
+        None => {
+            writeln!(
+                out,
+                "{}: {}",
+                label_style.paint("Encrypted"),
+                Paint::red("yes (`Encrypt` is not a dictionary)").bold()
+            )?;
+            return Ok(());
+        }
+    };
+    let format_number = |number: Option<i64>| {
+        number
+            .map(|number| number.to_string())
+            .unwrap_or("-".to_owned())
+    };
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("Encrypted"),
+        value_style.paint(format!(
+            "yes (filter: {}, V: {}, R: {}, key length: {} bits)",
+            encryption_info.filter.as_deref().unwrap_or("-"),
+            format_number(encryption_info.version),
+            format_number(encryption_info.revision),
+            encryption_info.key_length
+        ))
+    )?;
+    if let Some(permission_flags) = encryption_info.permission_flags {
+        writeln!(
+            out,
+            "{}: {}",
+            label_style.paint("Permissions"),
+            value_style.paint(describe_permissions(permission_flags))
+        )?;
+    }
+    if decrypted {
+        writeln!(
+            out,
+            "{}",
+            Paint::green("Strings and streams are decrypted.").bold()
+        )?;
+    } else {
+        writeln!(
+            out,
+            "{}",
+            Paint::red("Strings and streams are not decrypted, they are shown as stored.").bold()
+        )?;
+    }
     Ok(())
 }
+
+/// Print the header line and the comment line after it, read from the raw file bytes.
+///
+/// A comment with at least 4 binary bytes (>= 128) after the header tells programs
+/// that the file contains binary data. See p35 (7.5.2 File Header) in PDF v1.7 Spec.
+fn print_header_info(out: &mut dyn Write, file_bytes: &[u8]) -> std::io::Result<()> {
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+
+    let mut lines = file_bytes.split(|byte| *byte == b'\n' || *byte == b'\r');
+    let header_line = lines.next().unwrap_or_default();
+    // Skip the empty line between `\r` and `\n`.
+    let next_line = lines.find(|line| !line.is_empty()).unwrap_or_default();
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("Header"),
+        value_style.paint(format!("{:?}", String::from_utf8_lossy(header_line)))
+    )?;
+    let binary_byte_count = next_line.iter().filter(|byte| **byte >= 128).count();
+    let binary_marker = if next_line.starts_with(b"%") && binary_byte_count >= 4 {
+        format!(
+            "present ({:02X?}, {})",
+            next_line,
+            // Shown as Latin-1, which is how the marker is usually written (`%âãÏÓ`).
+            next_line
+                .iter()
+                .map(|byte| *byte as char)
+                .collect::<String>()
+        )
+    } else if next_line.starts_with(b"%") {
+        format!(
+            "missing (comment after header has {} binary bytes, at least 4 expected)",
+            binary_byte_count
+        )
+    } else {
+        "missing".to_owned()
+    };
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("Binary marker"),
+        value_style.paint(binary_marker)
+    )?;
+    Ok(())
+}
+
+/// Print the size of the file and how much of it is used by streams.
+///
+/// Object streams are not counted as stream bytes, as they contain the structure of the file.
+fn print_size_info(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    file_bytes: &[u8],
+) -> std::io::Result<()> {
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+
+    let mut stored_stream_bytes = 0;
+    let mut decoded_stream_bytes = 0;
+    for object in raw_doc.objects.values() {
+        if let Object::Stream(stream) = object {
+            if stream.dict.type_is(b"ObjStm") {
+                continue;
+            }
+            stored_stream_bytes += stream.content.len();
+            decoded_stream_bytes += stream
+                .decompressed_content()
+                .map(|content| content.len())
+                .unwrap_or(stream.content.len());
+        }
+    }
+    let file_size = file_bytes.len();
+    let overhead = file_size.saturating_sub(stored_stream_bytes);
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("File size"),
+        value_style.paint(format!("{} bytes", file_size))
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("Stream bytes"),
+        value_style.paint(format!(
+            "{} bytes stored, {} bytes decoded",
+            stored_stream_bytes, decoded_stream_bytes
+        ))
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("Structure overhead"),
+        value_style.paint(format!(
+            "{} bytes ({:.1}% of file size)",
+            overhead,
+            if file_size == 0 {
+                0.0
+            } else {
+                overhead as f64 / file_size as f64 * 100.0
+            }
+        ))
+    )?;
+    Ok(())
+}
+
+/// Print the object ids that are defined more than once in the file.
+///
+/// Redefining objects in a later revision is normal for incremental updates,
+/// but within the same revision it is an indication of a corrupt file.
+fn print_duplicate_objects(out: &mut dyn Write, file_bytes: &[u8]) -> std::io::Result<()> {
+    let duplicates = find_duplicate_objects(file_bytes);
+    let revision_ends = get_revision_ends(file_bytes);
+    writeln!(
+        out,
+        "{}: {}",
+        Style::default().paint("Duplicate object ids"),
+        Style::default().bold().paint(duplicates.len())
+    )?;
+    for (object_id, offsets) in duplicates {
+        let revisions: Vec<usize> = offsets
+            .iter()
+            .map(|offset| get_revision_index(&revision_ends, *offset))
+            .collect();
+        let same_revision = revisions
+            .iter()
+            .enumerate()
+            .any(|(index, revision)| revisions[..index].contains(revision));
+        let note = if same_revision {
+            Paint::red("(multiple times in the same revision, file might be corrupt)").bold()
+        } else {
+            Paint::default("(in different revisions, from incremental updates)").italic()
+        };
+        writeln!(
+            out,
+            "  ({},{}) at offsets: {} {}",
+            object_id.0,
+            object_id.1,
+            offsets
+                .iter()
+                .map(|offset| offset.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            note
+        )?;
+    }
+    Ok(())
+}
+
+/// Get all objects that can be reached from the `Root` in the trailer.
+///
+/// Each object is only visited once, so reference cycles are no problem.
+fn get_reachable_objects(raw_doc: &Document) -> BTreeSet<ObjectId> {
+    let mut reachable = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    if let Ok(Object::Reference(root_id)) = raw_doc.trailer.get(b"Root") {
+        queue.push_back(*root_id);
+    }
+    while let Some(object_id) = queue.pop_front() {
+        let object = match raw_doc.objects.get(&object_id) {
+            Some(object) => object,
+            None => continue,
+        };
+        if !reachable.insert(object_id) {
+            continue;
+        }
+        queue.extend(
+            get_references(object)
+                .into_iter()
+                .map(|reference| reference.object_id),
+        );
+    }
+    reachable
+}
@@ -0,0 +1,190 @@
+use super::EXTRA_INFO_STYLE;
+use lopdf::content::Operation;
+use lopdf::Object;
+use yansi::Paint;
+
+/// A color together with the color space it is defined in.
+#[derive(Debug, Clone, PartialEq)]
+struct Color {
+    color_space: String,
+    components: Vec<f64>,
+    /// Name of the pattern, when the color space is `Pattern`.
+    pattern: Option<String>,
+}
+
+impl Color {
+    /// The initial color of a color space.
+    fn initial(color_space: &str) -> Self {
+        let components = match color_space {
+            "DeviceGray" | "CalGray" => vec![0.0],
+            "DeviceRGB" | "CalRGB" | "Lab" => vec![0.0, 0.0, 0.0],
+            "DeviceCMYK" => vec![0.0, 0.0, 0.0, 1.0],
+            _ => Vec::new(),
+        };
+        Color {
+            color_space: color_space.to_owned(),
+            components,
+            pattern: None,
+        }
+    }
+
+    fn description(&self) -> String {
+        let components: Vec<String> = self
+            .components
+            .iter()
+            .map(|component| format!("{}", component))
+            .collect();
+        match &self.pattern {
+            Some(pattern) if components.is_empty() => {
+                format!("{} /{}", self.color_space, pattern)
+            }
+            Some(pattern) => format!(
+                "{}({}) /{}",
+                self.color_space,
+                components.join(" "),
+                pattern
+            ),
+            None => format!("{}({})", self.color_space, components.join(" ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ColorState {
+    fill: Color,
+    stroke: Color,
+}
+
+impl Default for ColorState {
+    fn default() -> Self {
+        ColorState {
+            fill: Color::initial("DeviceGray"),
+            stroke: Color::initial("DeviceGray"),
+        }
+    }
+}
+
+/// Keeps track of the fill and stroke color while walking over a content stream.
+///
+/// Only the color operators and `q`/`Q` are interpreted, everything else is ignored.
+#[derive(Debug, Clone, Default)]
+pub struct ColorStateTracker {
+    current: ColorState,
+    saved: Vec<ColorState>,
+}
+
+impl ColorStateTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the color state with this operation.
+    pub fn apply(&mut self, operation: &Operation) {
+        let components = get_components(&operation.operands);
+        match operation.operator.as_ref() {
+            "q" => self.saved.push(self.current.clone()),
+            "Q" => match self.saved.pop() {
+                Some(saved) => self.current = saved,
+                None => log::warn!("Found `Q` without matching `q`, color state is not restored."),
+            },
+            "g" => self.current.fill = device_color("DeviceGray", components),
+            "G" => self.current.stroke = device_color("DeviceGray", components),
+            "rg" => self.current.fill = device_color("DeviceRGB", components),
+            "RG" => self.current.stroke = device_color("DeviceRGB", components),
+            "k" => self.current.fill = device_color("DeviceCMYK", components),
+            "K" => self.current.stroke = device_color("DeviceCMYK", components),
+            "cs" => {
+                if let Some(color_space) = get_last_name(&operation.operands) {
+                    self.current.fill = Color::initial(&color_space);
+                }
+            }
+            "CS" => {
+                if let Some(color_space) = get_last_name(&operation.operands) {
+                    self.current.stroke = Color::initial(&color_space);
+                }
+            }
+            "sc" | "scn" => {
+                self.current.fill.components = components;
+                self.current.fill.pattern = get_last_name(&operation.operands);
+            }
+            "SC" | "SCN" => {
+                self.current.stroke.components = components;
+                self.current.stroke.pattern = get_last_name(&operation.operands);
+            }
+            _ => {}
+        }
+    }
+
+    /// Describe the colors used by a painting operation, `None` for other operations.
+    pub fn get_painting_info(&self, operator: &str) -> Option<String> {
+        let fill = || format!("fill: {}", self.current.fill.description());
+        let stroke = || format!("stroke: {}", self.current.stroke.description());
+        match operator {
+            // Text showing (only the fill color is shown, the text rendering mode is ignored)
+            "Tj" | "TJ" | "'" | "\"" => Some(fill()),
+            // Path painting
+            "S" | "s" => Some(stroke()),
+            "f" | "F" | "f*" => Some(fill()),
+            "B" | "B*" | "b" | "b*" => Some(format!("{}, {}", fill(), stroke())),
+            _ => None,
+        }
+    }
+}
+
+/// A colored block with the approximate sRGB value of a color operator, like `██ (sRGB #ff0000)`.
+///
+/// The color space of `sc` and `scn` is set by an earlier `cs` and can be a resource
+/// (like an ICC profile), so only their components are listed.
+pub fn get_color_swatch(operation: &Operation) -> Option<String> {
+    let components = get_components(&operation.operands);
+    let rgb = match (operation.operator.as_ref(), components.as_slice()) {
+        ("g" | "G", [gray]) => [*gray, *gray, *gray],
+        ("rg" | "RG", [red, green, blue]) => [*red, *green, *blue],
+        // A naive conversion, without a color profile.
+        ("k" | "K", [cyan, magenta, yellow, black]) => [
+            (1.0 - cyan) * (1.0 - black),
+            (1.0 - magenta) * (1.0 - black),
+            (1.0 - yellow) * (1.0 - black),
+        ],
+        ("sc" | "scn" | "SC" | "SCN", _) if !components.is_empty() => {
+            let components: Vec<String> = components
+                .iter()
+                .map(|component| format!("{}", component))
+                .collect();
+            return Some(
+                EXTRA_INFO_STYLE
+                    .paint(format!("(components: {})", components.join(" ")))
+                    .to_string(),
+            );
+        }
+        _ => return None,
+    };
+    let [red, green, blue] = rgb.map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8);
+    Some(format!(
+        "{} {}",
+        Paint::rgb(red, green, blue, "██"),
+        EXTRA_INFO_STYLE.paint(format!("(sRGB #{:02x}{:02x}{:02x})", red, green, blue))
+    ))
+}
+
+fn device_color(color_space: &str, components: Vec<f64>) -> Color {
+    Color {
+        color_space: color_space.to_owned(),
+        components,
+        pattern: None,
+    }
+}
+
+fn get_components(operands: &[Object]) -> Vec<f64> {
+    operands
+        .iter()
+        .filter_map(|operand| operand.as_float().ok())
+        .collect()
+}
+
+fn get_last_name(operands: &[Object]) -> Option<String> {
+    match operands.last() {
+        Some(Object::Name(name)) => Some(String::from_utf8_lossy(name).to_string()),
+        _ => None,
+    }
+}
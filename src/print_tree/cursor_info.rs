@@ -1,21 +1,102 @@
 use super::TreeDisplaySettings;
 use crate::print_tree::TREE_STYLE;
-use lopdf::ObjectId;
-use std::{cell::Cell, rc::Rc};
+use lopdf::{Dictionary, ObjectId};
+use std::cell::{Cell, RefCell, RefMut};
+use std::io::Write;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthChar;
+use yansi::{Color, Style};
 
 static TAB_WIDTH: usize = 2;
-static ARROW_LAST_CHAR: &str = "└";
-static ARROW_CHAR: &str = "├";
-static INDENT_CHAR: &str = "│";
+/// Colors of the indentation lines with `depth_guides`, repeated for deeper levels.
+static DEPTH_GUIDE_COLORS: [Color; 5] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+];
 
-#[derive(Debug, Clone)]
-pub struct TreeCursorInfo {
+/// The characters used to draw the tree, the legend and other boxes.
+#[derive(Debug, Clone, Copy)]
+pub struct TreeChars {
+    pub arrow: &'static str,
+    pub arrow_last: &'static str,
+    pub indent: &'static str,
+    /// Between the line number and the tree.
+    pub line_number_separator: &'static str,
+    /// Around the count of hidden levels, like `·20·`.
+    pub hidden_levels_marker: &'static str,
+    /// At the end of lines that are cut off.
+    pub ellipsis: &'static str,
+    pub box_horizontal: &'static str,
+    pub box_vertical: &'static str,
+    pub box_top_left: &'static str,
+    pub box_top_right: &'static str,
+    pub box_bottom_left: &'static str,
+    pub box_bottom_right: &'static str,
+    /// Where a line between sections meets the left border.
+    pub box_left_joint: &'static str,
+    pub box_right_joint: &'static str,
+}
+
+impl TreeChars {
+    pub const UNICODE: TreeChars = TreeChars {
+        arrow: "├",
+        arrow_last: "└",
+        indent: "│",
+        line_number_separator: "┃",
+        hidden_levels_marker: "·",
+        ellipsis: "…",
+        box_horizontal: "━",
+        box_vertical: "┃",
+        box_top_left: "┏",
+        box_top_right: "┓",
+        box_bottom_left: "┗",
+        box_bottom_right: "┛",
+        box_left_joint: "┣",
+        box_right_joint: "┫",
+    };
+
+    /// For terminals and fonts without box-drawing characters.
+    /// The arrows are as wide as the indentation, so the tree stays aligned.
+    pub const ASCII: TreeChars = TreeChars {
+        arrow: "|-",
+        arrow_last: "`-",
+        indent: "|",
+        line_number_separator: "|",
+        hidden_levels_marker: ".",
+        ellipsis: "...",
+        box_horizontal: "-",
+        box_vertical: "|",
+        box_top_left: "+",
+        box_top_right: "+",
+        box_bottom_left: "+",
+        box_bottom_right: "+",
+        box_left_joint: "+",
+        box_right_joint: "+",
+    };
+}
+
+#[derive(Clone)]
+pub struct TreeCursorInfo<'a> {
     /// Keeps track of the depth in the tree.
     depth_info: Vec<DepthInfo>,
+    /// Indices of `depth_info` joined into the line of their parent, these are not indented.
+    collapsed_depths: Vec<usize>,
+    /// Indices of `depth_info` that are array items, these are named by their index.
+    array_item_depths: Vec<usize>,
     /// Keeps track of all parents `ObjectId`s to prevent loops.
     parent_refs: Vec<ObjectId>,
+    /// The amount of references followed to get here, after the `expand` path.
+    reference_depth: usize,
+    /// The closest `Resources` dictionary, used to resolve names in content streams.
+    resources: Option<Rc<Dictionary>>,
     /// Shared info among the all cursors in this tree.
     shared_info: Rc<Cell<SharedCursorInfo>>,
+    /// Where the tree is written to, shared among all cursors in this tree.
+    output: Rc<RefCell<&'a mut dyn Write>>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -27,13 +108,30 @@ pub struct DepthInfo {
 #[derive(Debug, Default, Clone, Copy)]
 struct SharedCursorInfo {
     settings: TreeCursorSettings,
+    /// The number of the next line.
     line_number: u64,
+    /// Time after which printing is aborted.
+    deadline: Option<Instant>,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct TreeCursorSettings {
     pub print_line_numbers: bool,
     pub line_number_padding: u8,
+    /// The number of the first line.
+    pub line_number_start: u64,
+    /// Abort printing when it takes longer than this.
+    pub timeout: Option<Duration>,
+    /// When the indentation would get wider than this, the outer levels are replaced
+    /// with their count, like `·20·`.
+    pub max_indent_width: Option<usize>,
+    /// Lines wider than this are cut off with `…`.
+    pub max_line_width: Option<usize>,
+    /// Items less deep than this are not printed, the deeper items start at the left margin.
+    pub min_depth: usize,
+    /// Give the indentation lines of each level another color.
+    pub depth_guides: bool,
+    pub tree_chars: TreeChars,
 }
 
 impl Default for TreeCursorSettings {
@@ -41,6 +139,13 @@ impl Default for TreeCursorSettings {
         TreeCursorSettings {
             print_line_numbers: true,
             line_number_padding: 4,
+            line_number_start: 1,
+            timeout: None,
+            max_indent_width: None,
+            max_line_width: None,
+            min_depth: 0,
+            depth_guides: false,
+            tree_chars: TreeChars::UNICODE,
         }
     }
 }
@@ -49,21 +154,27 @@ impl SharedCursorInfo {
     pub fn new(settings: &TreeCursorSettings) -> Self {
         Self {
             settings: *settings,
-            ..Default::default()
+            line_number: settings.line_number_start,
+            deadline: settings.timeout.map(|timeout| Instant::now() + timeout),
         }
     }
 }
 
-impl TreeCursorInfo {
-    /// Create a new Tree Cursor.
+impl<'a> TreeCursorInfo<'a> {
+    /// Create a new Tree Cursor that writes to `output`.
     ///
     /// This should be used to create a new independent tree.
-    pub fn new(settings: &TreeCursorSettings) -> Self {
+    pub fn new(settings: &TreeCursorSettings, output: &'a mut dyn Write) -> Self {
         let shared_info = Rc::new(Cell::new(SharedCursorInfo::new(settings)));
         Self {
             depth_info: Vec::new(),
+            collapsed_depths: Vec::new(),
+            array_item_depths: Vec::new(),
             parent_refs: Vec::new(),
+            reference_depth: 0,
+            resources: None,
             shared_info,
+            output: Rc::new(RefCell::new(output)),
         }
     }
 
@@ -73,10 +184,62 @@ impl TreeCursorInfo {
         new_cursor
     }
 
+    /// Add a level that is printed on the line of its parent, used by `collapse_single_child`.
+    pub fn add_collapsed_depth(&self, name: Option<String>) -> Self {
+        let mut new_cursor = self.add_depth(DepthInfo {
+            name,
+            indent_line: false,
+        });
+        new_cursor.collapsed_depths.push(self.depth_info.len());
+        new_cursor
+    }
+
+    /// Add a level for an array item, named by its index so `expand` can select it.
+    pub fn add_array_item_depth(&self, index: usize, indent_line: bool) -> Self {
+        let mut new_cursor = self.add_depth(DepthInfo {
+            name: Some(index.to_string()),
+            indent_line,
+        });
+        new_cursor.array_item_depths.push(self.depth_info.len());
+        new_cursor
+    }
+
     pub fn get_depth_count(&self) -> usize {
         self.depth_info.len()
     }
 
+    /// Get the depth used to compare with `max_depth`.
+    ///
+    /// With `relative_depth` the depth is counted from the end of the `expand` path.
+    pub fn get_max_depth_count(&self, settings: &TreeDisplaySettings) -> usize {
+        let depth = self.get_depth_count();
+        let expand_count = match &settings.expand {
+            Some(expand_list) if settings.relative_depth => expand_list.len(),
+            _ => return depth,
+        };
+        let mut named_count = 0;
+        for (index, item) in self.depth_info.iter().enumerate() {
+            if item.name.is_some() {
+                named_count += 1;
+                if named_count == expand_count {
+                    return depth - (index + 1);
+                }
+            }
+        }
+        // Still walking the `expand` path.
+        0
+    }
+
+    pub fn get_tree_chars(&self) -> TreeChars {
+        self.shared_info.get().settings.tree_chars
+    }
+
+    /// The amount of numbered lines printed by all cursors of this tree.
+    pub fn get_line_count(&self) -> u64 {
+        let shared_info = self.shared_info.get();
+        shared_info.line_number - shared_info.settings.line_number_start
+    }
+
     pub fn get_path(&self) -> Vec<String> {
         let mut path = Vec::new();
         for item in &self.depth_info {
@@ -87,6 +250,19 @@ impl TreeCursorInfo {
         path
     }
 
+    /// The last dictionary key of the path, array indices are skipped.
+    pub fn get_last_key(&self) -> Option<String> {
+        self.depth_info
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(index, _)| !self.array_item_depths.contains(index))
+            .find_map(|(_, item)| item.name.clone())
+    }
+
+    /// The next item of the `expand` path, `Err` when this cursor is not on the path.
+    ///
+    /// Array items are selected by their index, like `Kids.0`.
     pub fn next_expand_label(&self, settings: &TreeDisplaySettings) -> Result<Option<String>, ()> {
         if let Some(expand_list) = &settings.expand {
             let path = self.get_path();
@@ -113,55 +289,226 @@ impl TreeCursorInfo {
         }
     }
 
+    /// With `only_path`, the children of the item at the end of the `expand` path are not printed.
+    pub fn is_past_only_path(&self, settings: &TreeDisplaySettings) -> bool {
+        settings.only_path
+            && settings.expand.is_some()
+            && matches!(self.next_expand_label(settings), Ok(None))
+    }
+
     pub fn check_parent_visited(&self, check: &ObjectId) -> bool {
         self.parent_refs.contains(check)
     }
 
+    /// The object that is being printed, `None` when it is not an indirect object.
+    pub fn get_current_object_id(&self) -> Option<ObjectId> {
+        self.parent_refs.last().copied()
+    }
+
     pub fn add_parent_object_id(&mut self, parent: ObjectId) {
         self.parent_refs.push(parent)
     }
 
-    pub fn print_subitem(&self, text: String, last: bool) {
+    pub fn get_reference_depth(&self) -> usize {
+        self.reference_depth
+    }
+
+    pub fn add_reference_depth(&mut self) {
+        self.reference_depth += 1;
+    }
+
+    pub fn set_resources(&mut self, resources: Dictionary) {
+        self.resources = Some(Rc::new(resources));
+    }
+
+    pub fn get_resources(&self) -> Option<&Dictionary> {
+        self.resources.as_deref()
+    }
+
+    /// The output of this tree, for lines that are not part of the tree like headers.
+    pub fn output(&self) -> RefMut<'_, dyn Write + 'a> {
+        RefMut::map(self.output.borrow_mut(), |output| &mut **output)
+    }
+
+    /// Returns a `TimedOut` error when printing takes longer than the `timeout`.
+    pub fn check_timeout(&self) -> std::io::Result<()> {
+        let shared_info = self.shared_info.get();
+        match shared_info.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!(
+                    "Printing took longer than {} seconds, the output is incomplete.",
+                    shared_info.settings.timeout.unwrap_or_default().as_secs()
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Items less deep than `min_depth` are not printed.
+    pub fn is_hidden(&self) -> bool {
+        self.depth_info.len() < self.shared_info.get().settings.min_depth
+    }
+
+    pub fn print_subitem(&self, text: String, last: bool) -> std::io::Result<()> {
+        self.check_timeout()?;
         let mut shared_info = self.shared_info.get();
 
+        // Hidden levels are still walked, their items are just not printed.
+        if self.is_hidden() {
+            return Ok(());
+        }
+        let min_depth = shared_info.settings.min_depth;
+
         let line_number = if shared_info.settings.print_line_numbers {
+            // Return line number prefix
+            let number_string = shared_info.line_number.to_string();
             // Increment line number
             shared_info.line_number += 1;
             self.shared_info.replace(shared_info);
-            // Return line number prefix
-            let number_string = shared_info.line_number.to_string();
             let padding_wanted = shared_info.settings.line_number_padding as usize;
             let padding_count = if padding_wanted > number_string.len() {
                 padding_wanted - number_string.len()
             } else {
                 0
             };
-            format!("{}{}┃", " ".repeat(padding_count), number_string)
+            format!(
+                "{}{}{}",
+                " ".repeat(padding_count),
+                number_string,
+                shared_info.settings.tree_chars.line_number_separator
+            )
         } else {
             "".to_owned()
         };
 
-        let arrow = if last { ARROW_LAST_CHAR } else { ARROW_CHAR };
+        let tree_chars = shared_info.settings.tree_chars;
+        let arrow = if last {
+            tree_chars.arrow_last
+        } else {
+            tree_chars.arrow
+        };
         // Create indentation
         let mut indentation = String::new();
-        for item in &self.depth_info {
+        let shown_depth_info: Vec<&DepthInfo> = self
+            .depth_info
+            .iter()
+            .enumerate()
+            .skip(min_depth)
+            .filter(|(index, _)| !self.collapsed_depths.contains(index))
+            .map(|(_, item)| item)
+            .collect();
+        let mut depth_info = shown_depth_info.as_slice();
+        // The level of the first shown indentation line, to pick its color.
+        let mut first_level = min_depth;
+        if let Some(max_indent_width) = shared_info.settings.max_indent_width {
+            if depth_info.len() * TAB_WIDTH > max_indent_width {
+                // Keep the lines of the levels closest to the item, the others are counted.
+                let marker = tree_chars.hidden_levels_marker;
+                let hidden_marker_width = format!("{}{}{}", marker, depth_info.len(), marker)
+                    .chars()
+                    .count();
+                let shown_levels = max_indent_width.saturating_sub(hidden_marker_width) / TAB_WIDTH;
+                let hidden_levels = depth_info.len() - shown_levels;
+                indentation.push_str(
+                    &TREE_STYLE
+                        .paint(format!("{}{}{}", marker, hidden_levels, marker))
+                        .to_string(),
+                );
+                depth_info = &depth_info[hidden_levels..];
+                first_level += hidden_levels;
+            }
+        }
+        let settings = shared_info.settings;
+        for (index, item) in depth_info.iter().enumerate() {
             if TAB_WIDTH < 2 {
                 indentation.push_str(&" ".repeat(TAB_WIDTH - 2));
             }
             if item.indent_line {
-                indentation.push_str(&TREE_STYLE.paint(INDENT_CHAR).to_string());
+                let style = get_guide_style(&settings, first_level + index);
+                indentation.push_str(&style.paint(tree_chars.indent).to_string());
             } else {
                 indentation.push(' ');
             }
             indentation.push(' ');
         }
 
-        println!(
-            "{}{}{} {}",
+        let arrow_style = get_guide_style(&settings, first_level + depth_info.len());
+        let prefix = format!(
+            "{}{}{} ",
             line_number,
             indentation,
-            TREE_STYLE.paint(arrow),
-            text
+            arrow_style.paint(arrow)
         );
+        let text = match shared_info.settings.max_line_width {
+            Some(max_line_width) => {
+                let text_width = max_line_width.saturating_sub(get_visible_width(&prefix));
+                truncate_to_width(&text, text_width, tree_chars.ellipsis).unwrap_or(text)
+            }
+            None => text,
+        };
+        writeln!(self.output(), "{}{}", prefix, text)
+    }
+}
+
+/// The style of the indentation line of a level, all levels are the same without `depth_guides`.
+fn get_guide_style(settings: &TreeCursorSettings, level: usize) -> Style {
+    if settings.depth_guides {
+        Style::new(DEPTH_GUIDE_COLORS[level % DEPTH_GUIDE_COLORS.len()])
+    } else {
+        *TREE_STYLE
+    }
+}
+
+/// Get the width of the text in the terminal, without the color codes.
+fn get_visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(character) = chars.next() {
+        if character == '\u{1b}' {
+            // Skip the color code, it ends with `m`.
+            chars.by_ref().find(|next| *next == 'm');
+        } else {
+            width += character.width().unwrap_or(0);
+        }
+    }
+    width
+}
+
+/// Cut off the text with `…` when it is wider than `max_width`, `None` when it fits.
+///
+/// Color codes are kept, the style is reset after the `…`.
+fn truncate_to_width(text: &str, max_width: usize, ellipsis: &str) -> Option<String> {
+    if get_visible_width(text) <= max_width {
+        return None;
+    }
+    let mut truncated = String::new();
+    let mut width = 0;
+    let mut has_color = false;
+    let mut chars = text.chars();
+    while let Some(character) = chars.next() {
+        if character == '\u{1b}' {
+            truncated.push(character);
+            for next in chars.by_ref() {
+                truncated.push(next);
+                if next == 'm' {
+                    break;
+                }
+            }
+            has_color = true;
+            continue;
+        }
+        let character_width = character.width().unwrap_or(0);
+        // Keep room for the `…`.
+        if width + character_width + get_visible_width(ellipsis) > max_width {
+            break;
+        }
+        width += character_width;
+        truncated.push(character);
+    }
+    truncated.push_str(ellipsis);
+    if has_color {
+        truncated.push_str("\u{1b}[0m");
     }
+    Some(truncated)
 }
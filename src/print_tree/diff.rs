@@ -0,0 +1,313 @@
+use super::cursor_info::{DepthInfo, TreeCursorInfo};
+use super::{
+    get_object_print_info, TreeCursorSettings, TreeDisplaySettings, EXPAND_INFO_STYLE,
+    EXTRA_INFO_STYLE, VALUE_STYLE,
+};
+use lopdf::{Dictionary, Document, Error, Object, ObjectId};
+use std::collections::{BTreeSet, VecDeque};
+use std::io::Write;
+use yansi::{Color, Paint, Style};
+
+/// Walks over both documents at the same time and prints the differences.
+struct Diff<'a> {
+    display_settings: &'a TreeDisplaySettings,
+    raw_doc: &'a Document,
+    other_doc: &'a Document,
+    /// Pairs of objects that are already compared, shared objects are only reported once.
+    compared: BTreeSet<(ObjectId, ObjectId)>,
+    /// Pairs of objects to compare next, with the cursor and parents to reach them.
+    queue: VecDeque<(ObjectId, ObjectId, TreeCursorInfo<'a>, Vec<ObjectId>)>,
+    /// The printed differences.
+    differences: Vec<String>,
+}
+
+/// Print the paths where the structure of `other_doc` differs from `raw_doc`, starting at the trailer.
+///
+/// Added keys are marked with `+`, removed keys with `-` and changed values with `~`.
+/// References are followed, so objects are compared by their content instead of their id.
+/// `Parent` references are not followed unless `display_parent` is set, like in the tree.
+pub fn print_document_diff(
+    out: &mut dyn Write,
+    display_settings: &TreeDisplaySettings,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+    other_doc: &Document,
+    file_name: String,
+    other_file_name: String,
+) -> Result<(), Error> {
+    writeln!(
+        out,
+        "{} {}",
+        Paint::default(file_name).bold(),
+        EXTRA_INFO_STYLE.paint(format!("(compared with `{}`)", other_file_name))
+    )?;
+    let mut diff = Diff {
+        display_settings,
+        raw_doc,
+        other_doc,
+        compared: BTreeSet::new(),
+        queue: VecDeque::new(),
+        differences: Vec::new(),
+    };
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, out);
+    diff.compare_dictionaries(&raw_doc.trailer, &other_doc.trailer, &cursor, &[]);
+    while let Some((object_id, other_id, object_cursor, other_parents)) = diff.queue.pop_front() {
+        match (
+            raw_doc.get_object(object_id),
+            other_doc.get_object(other_id),
+        ) {
+            (Ok(obj), Ok(other_obj)) => {
+                diff.compare_resolved_objects(obj, other_obj, &object_cursor, &other_parents)
+            }
+            (Err(_), Err(_)) => {}
+            _ => diff.add_changed(
+                &object_cursor,
+                &Object::Reference(object_id),
+                &Object::Reference(other_id),
+            ),
+        }
+    }
+
+    if diff.differences.is_empty() {
+        cursor.print_subitem(
+            EXPAND_INFO_STYLE
+                .paint("... (no differences found)")
+                .to_string(),
+            true,
+        )?;
+    }
+    let difference_count = diff.differences.len();
+    for (index, text) in diff.differences.into_iter().enumerate() {
+        cursor.print_subitem(text, index + 1 == difference_count)?;
+    }
+    Ok(())
+}
+
+impl<'a> Diff<'a> {
+    /// Compare two objects, `other_parents` are the objects followed in `other_doc`
+    /// (the cursor keeps track of the ones in `raw_doc`).
+    ///
+    /// Two references are compared later, so objects are compared breadth first
+    /// and differences are reported with the shortest path to them.
+    fn compare_objects(
+        &mut self,
+        obj: &Object,
+        other_obj: &Object,
+        cursor: &TreeCursorInfo<'a>,
+        other_parents: &[ObjectId],
+    ) {
+        let (obj, other_obj) = match (obj, other_obj) {
+            (Object::Reference(object_id), Object::Reference(other_id)) => {
+                // Stop at loops, and at objects that are already compared somewhere else.
+                if cursor.check_parent_visited(object_id)
+                    || other_parents.contains(other_id)
+                    || !self.compared.insert((*object_id, *other_id))
+                {
+                    return;
+                }
+                let mut cursor = cursor.clone();
+                cursor.add_parent_object_id(*object_id);
+                let mut other_parents = other_parents.to_vec();
+                other_parents.push(*other_id);
+                self.queue
+                    .push_back((*object_id, *other_id, cursor, other_parents));
+                return;
+            }
+            (Object::Reference(object_id), _) => match self.raw_doc.get_object(*object_id) {
+                Ok(obj) => (obj, other_obj),
+                Err(_) => return self.add_changed(cursor, obj, other_obj),
+            },
+            (_, Object::Reference(other_id)) => match self.other_doc.get_object(*other_id) {
+                Ok(other_obj) => (obj, other_obj),
+                Err(_) => return self.add_changed(cursor, obj, other_obj),
+            },
+            _ => (obj, other_obj),
+        };
+        self.compare_resolved_objects(obj, other_obj, cursor, other_parents);
+    }
+
+    /// Compare two objects that are not references.
+    fn compare_resolved_objects(
+        &mut self,
+        obj: &Object,
+        other_obj: &Object,
+        cursor: &TreeCursorInfo<'a>,
+        other_parents: &[ObjectId],
+    ) {
+        match (obj, other_obj) {
+            (Object::Dictionary(dict), Object::Dictionary(other_dict)) => {
+                self.compare_dictionaries(dict, other_dict, cursor, other_parents)
+            }
+            (Object::Stream(stream), Object::Stream(other_stream)) => {
+                self.compare_dictionaries(&stream.dict, &other_stream.dict, cursor, other_parents);
+                if stream.content != other_stream.content {
+                    self.add_difference(
+                        cursor,
+                        '~',
+                        format!(
+                            "stream content: {} → {} bytes",
+                            stream.content.len(),
+                            other_stream.content.len()
+                        ),
+                    );
+                }
+            }
+            (Object::Array(list), Object::Array(other_list)) => {
+                if self.is_too_deep(cursor) {
+                    return;
+                }
+                let next_expand_label = match cursor.next_expand_label(self.display_settings) {
+                    Ok(label) => label,
+                    Err(_) => return,
+                };
+                for index in 0..list.len().max(other_list.len()) {
+                    let label = index.to_string();
+                    if next_expand_label
+                        .as_ref()
+                        .is_some_and(|expand| expand != &label)
+                    {
+                        continue;
+                    }
+                    let item_cursor = cursor.add_depth(DepthInfo {
+                        name: Some(label),
+                        indent_line: false,
+                    });
+                    match (list.get(index), other_list.get(index)) {
+                        (Some(item), Some(other_item)) => {
+                            self.compare_objects(item, other_item, &item_cursor, other_parents)
+                        }
+                        (Some(item), None) => self.add_removed(&item_cursor, item),
+                        (None, Some(other_item)) => self.add_added(&item_cursor, other_item),
+                        (None, None) => {}
+                    }
+                }
+            }
+            _ => {
+                if !scalars_equal(obj, other_obj) {
+                    self.add_changed(cursor, obj, other_obj);
+                }
+            }
+        }
+    }
+
+    fn compare_dictionaries(
+        &mut self,
+        dict: &Dictionary,
+        other_dict: &Dictionary,
+        cursor: &TreeCursorInfo<'a>,
+        other_parents: &[ObjectId],
+    ) {
+        if self.is_too_deep(cursor) {
+            return;
+        }
+        let next_expand_label = match cursor.next_expand_label(self.display_settings) {
+            Ok(label) => label,
+            Err(_) => return,
+        };
+        // Keys in the order of the first document, followed by the added keys.
+        let keys = dict.iter().map(|(key, _)| key).chain(
+            other_dict
+                .iter()
+                .map(|(key, _)| key)
+                .filter(|key| !dict.has(key)),
+        );
+        for key in keys {
+            let label = String::from_utf8_lossy(key).to_string();
+            if next_expand_label
+                .as_ref()
+                .is_some_and(|expand| expand != &label)
+            {
+                continue;
+            }
+            let key_cursor = cursor.add_depth(DepthInfo {
+                name: Some(label.clone()),
+                indent_line: false,
+            });
+            match (dict.get(key), other_dict.get(key)) {
+                (Ok(value), Ok(other_value)) => {
+                    // `Parent` points back up the tree.
+                    if label == "Parent"
+                        && !self.display_settings.display_parent
+                        && matches!(value, Object::Reference(_))
+                    {
+                        continue;
+                    }
+                    self.compare_objects(value, other_value, &key_cursor, other_parents)
+                }
+                (Ok(value), Err(_)) => self.add_removed(&key_cursor, value),
+                (Err(_), Ok(other_value)) => self.add_added(&key_cursor, other_value),
+                (Err(_), Err(_)) => {}
+            }
+        }
+    }
+
+    fn is_too_deep(&self, cursor: &TreeCursorInfo) -> bool {
+        cursor.get_max_depth_count(self.display_settings) >= self.display_settings.max_depth
+    }
+
+    fn add_added(&mut self, cursor: &TreeCursorInfo, other_obj: &Object) {
+        let description = get_value_description(self.display_settings, other_obj);
+        self.add_difference(cursor, '+', description);
+    }
+
+    fn add_removed(&mut self, cursor: &TreeCursorInfo, obj: &Object) {
+        let description = get_value_description(self.display_settings, obj);
+        self.add_difference(cursor, '-', description);
+    }
+
+    fn add_changed(&mut self, cursor: &TreeCursorInfo, obj: &Object, other_obj: &Object) {
+        let description = format!(
+            "{} → {}",
+            get_value_description(self.display_settings, obj),
+            get_value_description(self.display_settings, other_obj)
+        );
+        self.add_difference(cursor, '~', description);
+    }
+
+    fn add_difference(&mut self, cursor: &TreeCursorInfo, marker: char, description: String) {
+        let marker_style = match marker {
+            '+' => Style::new(Color::Green).bold(),
+            '-' => Style::new(Color::Red).bold(),
+            _ => Style::new(Color::Yellow).bold(),
+        };
+        let path = cursor.get_path();
+        self.differences.push(format!(
+            "{} {}: {}",
+            marker_style.paint(marker),
+            if path.is_empty() {
+                ".".to_owned()
+            } else {
+                path.join(".")
+            },
+            description
+        ));
+    }
+}
+
+/// The symbol and value of an object, like `Z  12`, arrays and dictionaries get their length.
+fn get_value_description(display_settings: &TreeDisplaySettings, obj: &Object) -> String {
+    let print_info = get_object_print_info(obj, display_settings);
+    let value = match obj {
+        Object::Array(list) => format!("(length: {} values)", list.len()),
+        Object::Dictionary(dict) => format!("(length: {} keys)", dict.len()),
+        _ => print_info.value,
+    };
+    format!(
+        "{:<2} {}",
+        print_info.symbol_style.paint(print_info.symbol),
+        VALUE_STYLE.paint(value)
+    )
+}
+
+/// Compare values that are not arrays, dictionaries or streams.
+fn scalars_equal(obj: &Object, other_obj: &Object) -> bool {
+    match (obj, other_obj) {
+        (Object::Null, Object::Null) => true,
+        (Object::Boolean(value), Object::Boolean(other_value)) => value == other_value,
+        (Object::Integer(value), Object::Integer(other_value)) => value == other_value,
+        (Object::Real(value), Object::Real(other_value)) => value == other_value,
+        (Object::Name(value), Object::Name(other_value)) => value == other_value,
+        (Object::String(value, _), Object::String(other_value, _)) => value == other_value,
+        _ => false,
+    }
+}
@@ -0,0 +1,240 @@
+use lopdf::content::{Content, Operation};
+use lopdf::{Error, Object, Stream, StringFormat};
+
+/// Full names of the abbreviated keys in inline image dictionaries.
+///
+/// See p222 (Table 93) in PDF v1.7 Spec.
+static INLINE_IMAGE_KEYS: [(&str, &str); 9] = [
+    ("BPC", "bitsPerComponent"),
+    ("CS", "colorSpace"),
+    ("D", "decode"),
+    ("DP", "decodeParms"),
+    ("F", "filter"),
+    ("H", "height"),
+    ("IM", "imageMask"),
+    ("I", "interpolate"),
+    ("W", "width"),
+];
+
+/// Decode the operations of a content stream, keeping the data of inline images.
+///
+/// lopdf tries to parse the image data after `ID` as operations, which stops decoding.
+/// Instead the image data is added as the last operand of the `ID` operation,
+/// after the key value pairs of the image dictionary.
+/// See p222 (8.9.7 Inline Images) in PDF v1.7 Spec.
+pub fn decode_content_stream(stream: &Stream) -> Result<Content<Vec<Operation>>, Error> {
+    let content = &stream.content;
+    let mut operations = Vec::new();
+    let mut start = 0;
+    while let Some(data_start) = find_image_data_start(content, start) {
+        let mut part = Content::decode(&content[start..data_start])?.operations;
+        let (data_end, end_operator_start) = match part.last() {
+            Some(operation) if operation.operator == "ID" => {
+                find_image_data_end(content, data_start, &operation.operands)
+            }
+            // Not an inline image after all, decode it like lopdf does.
+            _ => break,
+        };
+        if let Some(operation) = part.last_mut() {
+            operation.operands.push(Object::String(
+                content[data_start..data_end].to_vec(),
+                StringFormat::Hexadecimal,
+            ));
+        }
+        operations.append(&mut part);
+        start = end_operator_start;
+    }
+    operations.append(&mut Content::decode(&content[start..])?.operations);
+    Ok(Content { operations })
+}
+
+/// Get the labeled arguments of an `ID` operation decoded by `decode_content_stream`.
+pub fn get_inline_image_arguments(operands: &[Object]) -> Vec<(String, Object)> {
+    let (dict_operands, data) = match operands.last() {
+        Some(Object::String(data, _)) if operands.len() % 2 == 1 => {
+            (&operands[..operands.len() - 1], Some(data))
+        }
+        _ => (operands, None),
+    };
+    let mut arguments: Vec<(String, Object)> = dict_operands
+        .chunks(2)
+        .filter_map(|pair| match pair {
+            [Object::Name(key), value] => {
+                let key = String::from_utf8_lossy(key);
+                let label = INLINE_IMAGE_KEYS
+                    .iter()
+                    .find(|(short_key, long_key)| {
+                        *short_key == key || long_key.eq_ignore_ascii_case(&key)
+                    })
+                    .map(|(_, long_key)| long_key.to_string())
+                    .unwrap_or_else(|| key.to_string());
+                Some((label, value.clone()))
+            }
+            _ => {
+                log::warn!("Inline image dictionary should contain name and value pairs.");
+                None
+            }
+        })
+        .collect();
+    if let Some(data) = data {
+        arguments.push(("dataLength".to_owned(), Object::Integer(data.len() as i64)));
+        arguments.push((
+            "data".to_owned(),
+            Object::String(data.clone(), StringFormat::Hexadecimal),
+        ));
+    }
+    arguments
+}
+
+/// Find the start of the image data after the next `ID` operator.
+///
+/// Strings and comments are skipped, so an `ID` in them is not mistaken for the operator.
+fn find_image_data_start(content: &[u8], start: usize) -> Option<usize> {
+    let mut index = start;
+    while index < content.len() {
+        match content[index] {
+            byte if is_whitespace(byte) => index += 1,
+            b'%' => {
+                while index < content.len() && !matches!(content[index], b'\r' | b'\n') {
+                    index += 1;
+                }
+            }
+            b'(' => index = skip_literal_string(content, index),
+            b'<' if content.get(index + 1) != Some(&b'<') => {
+                while index < content.len() && content[index] != b'>' {
+                    index += 1;
+                }
+                index += 1;
+            }
+            byte if is_delimiter(byte) => index += 1,
+            _ => {
+                let token_start = index;
+                while index < content.len()
+                    && !is_whitespace(content[index])
+                    && !is_delimiter(content[index])
+                {
+                    index += 1;
+                }
+                if &content[token_start..index] == b"ID" {
+                    // A single white-space character follows `ID`.
+                    return Some((index + 1).min(content.len()));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Find where the image data ends and where the `EI` operator starts.
+///
+/// For unfiltered images the length is known from the image dictionary, otherwise
+/// the data ends at the first `EI` surrounded by white-space.
+fn find_image_data_end(
+    content: &[u8],
+    data_start: usize,
+    dict_operands: &[Object],
+) -> (usize, usize) {
+    if let Some(length) = get_unfiltered_data_length(dict_operands) {
+        let data_end = data_start + length;
+        if let Some(rest) = content.get(data_end..) {
+            let ei_start = data_end + rest.iter().take_while(|byte| is_whitespace(**byte)).count();
+            if is_end_operator_at(content, ei_start) {
+                return (data_end, ei_start);
+            }
+        }
+    }
+    let mut index = data_start;
+    while index + 2 <= content.len() {
+        if index > data_start
+            && is_whitespace(content[index - 1])
+            && is_end_operator_at(content, index)
+        {
+            return (index - 1, index);
+        }
+        index += 1;
+    }
+    log::warn!("Inline image data is not followed by `EI`.");
+    (content.len(), content.len())
+}
+
+/// The data length of an image without filters: every row is padded to a whole byte.
+fn get_unfiltered_data_length(dict_operands: &[Object]) -> Option<usize> {
+    let mut width = None;
+    let mut height = None;
+    let mut bits_per_component = None;
+    let mut components = Some(1);
+    for pair in dict_operands.chunks(2) {
+        let (key, value) = match pair {
+            [Object::Name(key), value] => (key.as_slice(), value),
+            _ => return None,
+        };
+        match key {
+            b"F" | b"Filter" => return None,
+            b"W" | b"Width" => width = value.as_i64().ok(),
+            b"H" | b"Height" => height = value.as_i64().ok(),
+            b"BPC" | b"BitsPerComponent" => bits_per_component = value.as_i64().ok(),
+            b"IM" | b"ImageMask" if matches!(value, Object::Boolean(true)) => {
+                bits_per_component = Some(1)
+            }
+            b"CS" | b"ColorSpace" => {
+                components = match value {
+                    Object::Name(name) => match name.as_slice() {
+                        b"G" | b"DeviceGray" | b"I" | b"Indexed" => Some(1),
+                        b"RGB" | b"DeviceRGB" => Some(3),
+                        b"CMYK" | b"DeviceCMYK" => Some(4),
+                        // Named resources, the amount of components is not known here.
+                        _ => None,
+                    },
+                    // Indexed color spaces are arrays starting with `I` or `Indexed`.
+                    Object::Array(list) => match list.first() {
+                        Some(Object::Name(name)) if name == b"I" || name == b"Indexed" => Some(1),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            _ => {}
+        }
+    }
+    let row_bits = width? * bits_per_component? * components?;
+    usize::try_from((row_bits + 7) / 8 * height?).ok()
+}
+
+fn is_end_operator_at(content: &[u8], index: usize) -> bool {
+    content.get(index..index + 2) == Some(b"EI")
+        && content
+            .get(index + 2)
+            .is_none_or(|next| is_whitespace(*next) || is_delimiter(*next))
+}
+
+/// Skip a literal string with balanced parentheses, returns the index after it.
+fn skip_literal_string(content: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut index = start;
+    while index < content.len() {
+        match content[index] {
+            b'\\' => index += 1,
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    content.len()
+}
+
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b'\0' | b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+fn is_delimiter(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
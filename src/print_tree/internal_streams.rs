@@ -0,0 +1,143 @@
+use super::cursor_info::TreeCursorInfo;
+use super::pdf_objects::get_skip_marker;
+use super::{TreeDisplaySettings, ERROR_STYLE, EXTRA_INFO_STYLE, SKIPPED_STYLE, VALUE_STYLE};
+use crate::object_offsets::get_object_stream_object_numbers;
+use lopdf::{Object, Stream};
+
+/// Print a summary of cross-reference streams (`/Type /XRef`) and object streams
+/// (`/Type /ObjStm`) instead of decoding them as content streams.
+///
+/// Returns `false` if the stream is not one of these.
+pub fn print_internal_stream_summary(
+    display_settings: &TreeDisplaySettings,
+    stream: &Stream,
+    cursor: &TreeCursorInfo,
+) -> std::io::Result<bool> {
+    if stream.dict.type_is(b"XRef") {
+        print_xref_stream_summary(stream, cursor)?;
+        Ok(true)
+    } else if stream.dict.type_is(b"ObjStm") {
+        print_object_stream_summary(display_settings, stream, cursor)?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Count the entry types in a cross-reference stream.
+///
+/// See p107 (7.5.8.3 Cross-Reference Stream Data) in PDF v1.7 Spec.
+fn print_xref_stream_summary(stream: &Stream, cursor: &TreeCursorInfo) -> std::io::Result<()> {
+    let content = stream
+        .decompressed_content()
+        .unwrap_or_else(|_| stream.content.clone());
+    let get_numbers = |key: &[u8]| -> Vec<usize> {
+        match stream.dict.get(key) {
+            Ok(Object::Array(list)) => list
+                .iter()
+                .map(|item| item.as_i64().unwrap_or(0).max(0) as usize)
+                .collect(),
+            _ => Vec::new(),
+        }
+    };
+    let widths = get_numbers(b"W");
+    if widths.len() != 3 {
+        cursor.print_subitem(
+            ERROR_STYLE
+                .paint("Cross-reference stream without valid `W` array.")
+                .to_string(),
+            true,
+        )?;
+        return Ok(());
+    }
+    let mut index = get_numbers(b"Index");
+    if index.is_empty() {
+        let size = stream
+            .dict
+            .get(b"Size")
+            .and_then(Object::as_i64)
+            .unwrap_or(0);
+        index = vec![0, size.max(0) as usize];
+    }
+
+    let entry_width: usize = widths.iter().sum();
+    let entry_count = content.len().checked_div(entry_width).unwrap_or(0);
+    // Free, in file and in object stream entries.
+    let mut type_counts = [0; 3];
+    for entry in content.chunks_exact(entry_width.max(1)).take(entry_count) {
+        // The type defaults to 1 when its width is 0.
+        let entry_type = if widths[0] == 0 {
+            1
+        } else {
+            entry[..widths[0]]
+                .iter()
+                .fold(0usize, |value, byte| (value << 8) | *byte as usize)
+        };
+        if let Some(count) = type_counts.get_mut(entry_type) {
+            *count += 1;
+        }
+    }
+    let ranges: Vec<String> = index
+        .chunks_exact(2)
+        .map(|range| format!("{}..{}", range[0], range[0].saturating_add(range[1])))
+        .collect();
+
+    cursor.print_subitem(
+        format!(
+            "Cross-reference stream: {} {}",
+            VALUE_STYLE.paint(format!("{} entries", entry_count)),
+            EXTRA_INFO_STYLE.paint(format!(
+                "({} free, {} in file, {} in object streams)",
+                type_counts[0], type_counts[1], type_counts[2]
+            ))
+        ),
+        false,
+    )?;
+    cursor.print_subitem(
+        format!("Object numbers: {}", VALUE_STYLE.paint(ranges.join(", "))),
+        true,
+    )?;
+    Ok(())
+}
+
+/// List the object numbers stored in an object stream.
+fn print_object_stream_summary(
+    display_settings: &TreeDisplaySettings,
+    stream: &Stream,
+    cursor: &TreeCursorInfo,
+) -> std::io::Result<()> {
+    let object_numbers = get_object_stream_object_numbers(stream);
+    let first = stream
+        .dict
+        .get(b"First")
+        .and_then(Object::as_i64)
+        .unwrap_or(0);
+    cursor.print_subitem(
+        format!(
+            "Object stream: {} {}",
+            VALUE_STYLE.paint(format!("{} objects", object_numbers.len())),
+            EXTRA_INFO_STYLE.paint(format!("(first object at offset {})", first))
+        ),
+        false,
+    )?;
+    let mut shown: Vec<String> = object_numbers
+        .iter()
+        .map(|object_number| VALUE_STYLE.paint(object_number).to_string())
+        .collect();
+    if let Some(display_limit) = display_settings.array_display_limit {
+        let display_limit = display_limit.max(2);
+        if shown.len() > display_limit {
+            let skipped = shown.len() - display_limit;
+            let last = shown.pop().unwrap_or_default();
+            shown.truncate(display_limit - 1);
+            shown.push(
+                SKIPPED_STYLE
+                    .paint(get_skip_marker(display_settings, skipped, "items"))
+                    .to_string(),
+            );
+            shown.push(last);
+        }
+    }
+    cursor.print_subitem(format!("Object numbers: {}", shown.join(", ")), true)?;
+    Ok(())
+}
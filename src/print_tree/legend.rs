@@ -1,50 +1,201 @@
-use super::get_object_print_info;
+use super::cursor_info::TreeChars;
+use super::stream_operations::OperatorCategory;
+use super::{get_object_print_info, HELPER_CHARS_STYLE, TYPE_STYLE};
 use crate::print_tree::TreeDisplaySettings;
+use crate::StreamDisplay;
 use lopdf::{Dictionary, Object, Stream, StringFormat};
+use std::io::Write;
+use unicode_width::UnicodeWidthStr;
 
-lazy_static::lazy_static! {
-    static ref DISPLAY_SETTINGS: TreeDisplaySettings = TreeDisplaySettings::default();
+/// Objects shown in the legend, with a short name for the compact legend.
+fn get_legend_objects() -> Vec<(Object, &'static str)> {
+    vec![
+        (Object::Null, "null"),
+        (Object::Boolean(true), "bool"),
+        (Object::Integer(0), "int"),
+        (Object::Real(0.0), "real"),
+        (Object::Name(vec![]), "name"),
+        (Object::String(vec![], StringFormat::Literal), "str"),
+        (Object::String(vec![], StringFormat::Hexadecimal), "hex"),
+        (Object::Array(vec![]), "array"),
+        (Object::Dictionary(Dictionary::new()), "dict"),
+        (
+            Object::Stream(Stream::new(Dictionary::new(), vec![])),
+            "stream",
+        ),
+        (Object::Reference((0, 0)), "ref"),
+    ]
 }
 
-pub fn print_legend() {
+/// Operator categories with a color, with an example operator and a short name.
+static LEGEND_OPERATOR_CATEGORIES: [(OperatorCategory, &str, &str, &str); 4] = [
+    (
+        OperatorCategory::PathConstruction,
+        "m",
+        "Path_Construction",
+        "path",
+    ),
+    (
+        OperatorCategory::PathPainting,
+        "S",
+        "Path_Painting",
+        "paint",
+    ),
+    (OperatorCategory::Text, "Tj", "Text", "text"),
+    (
+        OperatorCategory::GraphicsState,
+        "q",
+        "Graphics_State",
+        "state",
+    ),
+];
+
+pub fn print_legend(
+    out: &mut dyn Write,
+    display_settings: &TreeDisplaySettings,
+    tree_chars: &TreeChars,
+) -> std::io::Result<()> {
+    if display_settings.compact_legend {
+        print_compact_legend(out, display_settings)?;
+        return Ok(());
+    }
     let table_width = 30;
-    println!(
-        "┏{} Legend {}┓",
-        "━".repeat((table_width - 8) / 2),
-        "━".repeat((table_width - 8) / 2)
-    );
-    print_table_line(table_width, &Object::Null);
-    print_table_line(table_width, &Object::Boolean(true));
-    print_table_line(table_width, &Object::Integer(0));
-    print_table_line(table_width, &Object::Real(0.0));
-    print_table_line(table_width, &Object::Name(vec![]));
-    print_table_line(table_width, &Object::String(vec![], StringFormat::Literal));
-    print_table_line(
-        table_width,
-        &Object::String(vec![], StringFormat::Hexadecimal),
+    writeln!(
+        out,
+        "{}{} Legend {}{}",
+        tree_chars.box_top_left,
+        tree_chars.box_horizontal.repeat((table_width - 8) / 2),
+        tree_chars.box_horizontal.repeat((table_width - 8) / 2),
+        tree_chars.box_top_right
+    )?;
+    for (obj, _) in get_legend_objects() {
+        print_table_line(out, table_width, &obj, display_settings, tree_chars)?;
+    }
+    let stream_display = format!(
+        "   Stream content: {}",
+        get_stream_display_description(display_settings)
     );
-    print_table_line(table_width, &Object::Array(vec![]));
-    print_table_line(table_width, &Object::Dictionary(Dictionary::new()));
-    print_table_line(
-        table_width,
-        &Object::Stream(Stream::new(Dictionary::new(), vec![])),
-    );
-    print_table_line(table_width, &Object::Reference((0, 0)));
-    println!("┗{}┛", "━".repeat(table_width));
+    writeln!(
+        out,
+        "{}{}{}{}",
+        tree_chars.box_vertical,
+        stream_display,
+        " ".repeat(table_width.saturating_sub(stream_display.width())),
+        tree_chars.box_vertical
+    )?;
+    if display_settings.stream_enhanced_operations {
+        writeln!(
+            out,
+            "{}{} Operators {}{}",
+            tree_chars.box_left_joint,
+            tree_chars.box_horizontal.repeat((table_width - 11) / 2),
+            tree_chars
+                .box_horizontal
+                .repeat(table_width - 11 - (table_width - 11) / 2),
+            tree_chars.box_right_joint
+        )?;
+        for (category, operator, name, _) in LEGEND_OPERATOR_CATEGORIES.iter() {
+            let plain_text = format!("{:<2} {}", operator, name);
+            writeln!(
+                out,
+                "{} {:<2} {}{}{}",
+                tree_chars.box_vertical,
+                category.style().paint(operator),
+                name,
+                " ".repeat(table_width - plain_text.width() - 1),
+                tree_chars.box_vertical
+            )?;
+        }
+    }
+    writeln!(
+        out,
+        "{}{}{}",
+        tree_chars.box_bottom_left,
+        tree_chars.box_horizontal.repeat(table_width),
+        tree_chars.box_bottom_right
+    )?;
+    Ok(())
+}
+
+/// Print the legend on one line, like `Legend: Nu=null b=bool Z=int ...`.
+fn print_compact_legend(
+    out: &mut dyn Write,
+    display_settings: &TreeDisplaySettings,
+) -> std::io::Result<()> {
+    let mut items: Vec<String> = get_legend_objects()
+        .iter()
+        .map(|(obj, short_name)| {
+            let obj_print_info = get_object_print_info(obj, display_settings);
+            format!(
+                "{}={}",
+                obj_print_info.symbol_style.paint(obj_print_info.symbol),
+                short_name
+            )
+        })
+        .collect();
+    if display_settings.stream_enhanced_operations {
+        items.extend(LEGEND_OPERATOR_CATEGORIES.iter().map(
+            |(category, operator, _, short_name)| {
+                format!("{}={}", category.style().paint(operator), short_name)
+            },
+        ));
+    }
+    items.push(format!(
+        "streams={}",
+        get_stream_display_description(display_settings)
+    ));
+    writeln!(out, "Legend: {}", items.join(" "))?;
+    Ok(())
+}
+
+/// How the content of streams is printed, the `display-stream` setting.
+fn get_stream_display_description(display_settings: &TreeDisplaySettings) -> &'static str {
+    match display_settings.display_stream {
+        StreamDisplay::NoDisplay => "hidden",
+        StreamDisplay::Hex => "hex",
+        StreamDisplay::Tree => "tree",
+    }
 }
 
-pub fn print_table_line(table_width: usize, obj: &Object) {
-    let obj_print_info = get_object_print_info(obj, &DISPLAY_SETTINGS);
+/// Print the symbol and type name of an object, with the type name styled like in the
+/// tree when `display_type_names` is set.
+pub fn print_table_line(
+    out: &mut dyn Write,
+    table_width: usize,
+    obj: &Object,
+    display_settings: &TreeDisplaySettings,
+    tree_chars: &TreeChars,
+) -> std::io::Result<()> {
+    let obj_print_info = get_object_print_info(obj, display_settings);
+    let (type_name, plain_type_name) = if display_settings.display_type_names {
+        (
+            format!(
+                "{}{}",
+                HELPER_CHARS_STYLE.paint(":"),
+                TYPE_STYLE.paint(obj_print_info.type_name)
+            ),
+            format!(":{}", obj_print_info.type_name),
+        )
+    } else {
+        (
+            obj_print_info.type_name.to_owned(),
+            obj_print_info.type_name.to_owned(),
+        )
+    };
     let styled_text = format!(
         "{:<2} {}",
         obj_print_info.symbol_style.paint(obj_print_info.symbol),
-        obj_print_info.type_name
+        type_name
     );
-    let plain_text = format!("{:<2} {}", obj_print_info.symbol, obj_print_info.type_name);
-    let text_len = plain_text.chars().count();
-    println!(
-        "┃ {}{}┃",
+    let plain_text = format!("{:<2} {}", obj_print_info.symbol, plain_type_name);
+    let text_len = plain_text.width();
+    writeln!(
+        out,
+        "{} {}{}{}",
+        tree_chars.box_vertical,
         styled_text,
-        " ".repeat(table_width - text_len - 1)
-    );
+        " ".repeat(table_width - text_len - 1),
+        tree_chars.box_vertical
+    )?;
+    Ok(())
 }
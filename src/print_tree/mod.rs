@@ -1,15 +1,47 @@
+use std::io::Write;
+mod color_state;
 mod cursor_info;
+mod diff;
+mod inline_images;
+mod internal_streams;
 mod legend;
+mod operator_list;
+mod outline;
+mod page_tree;
+mod parents;
 mod pdf_content_stream;
 mod pdf_objects;
+mod search;
+mod stream_list;
 mod stream_operations;
+mod text_position;
 mod tree_display_settings;
 
-pub use cursor_info::TreeCursorSettings;
-use cursor_info::{DepthInfo, TreeCursorInfo};
+use crate::destinations::resolve_destination;
+use crate::encryption::describe_permissions;
+use crate::object_offsets::{get_missing_object_message, ObjectLocation};
+use crate::page_tree::{get_page_node, AttributeSource, INHERITABLE_ATTRIBUTES};
+use crate::TreeStart;
+pub(crate) use cursor_info::{DepthInfo, TreeCursorInfo};
+pub use cursor_info::{TreeChars, TreeCursorSettings};
+pub use diff::print_document_diff;
+pub use inline_images::decode_content_stream;
 use legend::print_legend;
-use lopdf::{Dictionary, Document, Error, Object};
-pub use pdf_objects::{get_object_print_info, ObjectPrintInfo};
+use lopdf::{Dictionary, Document, Error, Object, ObjectId};
+pub use operator_list::print_operator_list;
+pub use outline::print_outline;
+pub use page_tree::print_page_tree;
+pub use parents::print_parents;
+pub use pdf_content_stream::is_content_stream;
+use pdf_objects::get_skip_marker;
+pub use pdf_objects::{
+    decode_text_string, get_object_print_info, get_redacted_string, get_type_label,
+    get_value_formatter, ValueFormatter, FORMATTABLE_TYPE_NAMES,
+};
+use pdf_objects::{get_box_size_info, is_inline_number_array, PAGE_BOX_KEYS};
+pub use search::{print_search_results, SearchSettings};
+pub use stream_list::print_stream_list;
+pub use stream_operations::get_operator_help;
 pub use tree_display_settings::TreeDisplaySettings;
 use yansi::{Color, Paint, Style};
 
@@ -21,33 +53,218 @@ lazy_static::lazy_static! {
     pub(self) static ref EXPAND_INFO_STYLE: Style = Style::new(Color::Default).dimmed().italic();
     pub(self) static ref EXTRA_INFO_STYLE: Style = Style::new(Color::Default).italic();
     pub(self) static ref SKIPPED_STYLE: Style = Style::new(Color::Blue).italic();
+    pub(self) static ref HIGHLIGHT_STYLE: Style = Style::new(Color::Default).bold().invert();
     pub(self) static ref ERROR_STYLE: Style = Style::new(Color::Red).bold();
 }
 
 pub fn print_pdf_tree(
+    out: &mut dyn Write,
     display_settings: &TreeDisplaySettings,
     tree_cursor_settings: &TreeCursorSettings,
     raw_doc: &Document,
     file_name: String,
+) -> Result<(), Error> {
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, out);
+    let result = print_pdf_tree_items(display_settings, raw_doc, file_name, cursor.clone());
+    if tree_cursor_settings.print_line_numbers {
+        // Also printed when printing failed, to see how far it got.
+        writeln!(
+            cursor.output(),
+            "{}",
+            EXTRA_INFO_STYLE.paint(format!("{} lines", cursor.get_line_count()))
+        )?;
+    }
+    result
+}
+
+fn print_pdf_tree_items(
+    display_settings: &TreeDisplaySettings,
+    raw_doc: &Document,
+    file_name: String,
+    cursor: TreeCursorInfo,
 ) -> Result<(), Error> {
     let trailer = &raw_doc.trailer;
-    let cursor = TreeCursorInfo::new(tree_cursor_settings);
+
+    // Check the page exists before printing anything.
+    let page = match display_settings.page {
+        Some(page_number) => Some((page_number, get_page_node(raw_doc, page_number)?)),
+        None => None,
+    };
 
     if display_settings.display_legend {
-        print_legend();
+        print_legend(
+            &mut *cursor.output(),
+            display_settings,
+            &cursor.get_tree_chars(),
+        )?;
+    }
+
+    if let Some((page_number, page)) = page {
+        let page_dict = raw_doc.get_dictionary(page.object_id)?;
+        let mut cursor = cursor;
+        cursor.add_parent_object_id(page.object_id);
+        writeln!(
+            cursor.output(),
+            "{} {}",
+            Paint::default(file_name).bold(),
+            EXTRA_INFO_STYLE.paint(format!(
+                "(Page {}, ({},{}))",
+                page_number, page.object_id.0, page.object_id.1
+            ))
+        )?;
+        let inherited: Vec<String> = INHERITABLE_ATTRIBUTES
+            .iter()
+            .zip(&page.attributes)
+            .filter_map(|(attribute, source)| match source {
+                AttributeSource::Inherited(ancestor) => Some(format!(
+                    "{} from ({},{})",
+                    attribute, ancestor.0, ancestor.1
+                )),
+                _ => None,
+            })
+            .collect();
+        if !inherited.is_empty() {
+            writeln!(
+                cursor.output(),
+                "{}",
+                EXTRA_INFO_STYLE.paint(format!("Inherits: {}", inherited.join(", ")))
+            )?;
+        }
+        return print_pdf_dictionary(display_settings, page_dict, raw_doc, &cursor);
+    }
+
+    if let Some(tree_start) = display_settings.tree_start {
+        match get_tree_start_dictionary(raw_doc, tree_start) {
+            Ok((object_id, start_dict)) => {
+                let mut cursor = cursor;
+                if let Some(object_id) = object_id {
+                    cursor.add_parent_object_id(object_id);
+                }
+                let label = match tree_start {
+                    TreeStart::Catalog => "Root".to_owned(),
+                    TreeStart::Info => "Info".to_owned(),
+                    TreeStart::Pages => "Pages".to_owned(),
+                    TreeStart::Object(object_id) => {
+                        format!("Object ({},{})", object_id.0, object_id.1)
+                    }
+                };
+                writeln!(
+                    cursor.output(),
+                    "{} {}",
+                    Paint::default(file_name).bold(),
+                    EXTRA_INFO_STYLE.paint(format!("({})", label))
+                )?;
+                return print_pdf_dictionary(display_settings, start_dict, raw_doc, &cursor);
+            }
+            Err(err) => {
+                log::error!(
+                    "Could not find the start of the tree, printing from the trailer: {}",
+                    err
+                );
+            }
+        }
     }
 
-    println!("{}", Paint::default(file_name).bold());
+    writeln!(cursor.output(), "{}", Paint::default(file_name).bold())?;
     print_pdf_dictionary(display_settings, trailer, raw_doc, &cursor)?;
     Ok(())
 }
 
+/// Get the dictionary to start the tree at, with its object id if it is an indirect object.
+pub fn get_tree_start_dictionary(
+    raw_doc: &Document,
+    tree_start: TreeStart,
+) -> Result<(Option<ObjectId>, &Dictionary), Error> {
+    let start = match tree_start {
+        TreeStart::Catalog => raw_doc.trailer.get(b"Root")?,
+        TreeStart::Info => raw_doc.trailer.get(b"Info")?,
+        TreeStart::Pages => raw_doc.catalog()?.get(b"Pages")?,
+        TreeStart::Object(object_id) => {
+            return Ok((Some(object_id), raw_doc.get_dictionary(object_id)?));
+        }
+    };
+    let (object_id, start) = raw_doc.dereference(start)?;
+    Ok((object_id, start.as_dict()?))
+}
+
+/// Print the given objects in a tree structure, instead of starting from the trailer.
+pub fn print_pdf_object_list(
+    out: &mut dyn Write,
+    display_settings: &TreeDisplaySettings,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+    file_name: String,
+    objects: &[(ObjectId, ObjectLocation)],
+) -> Result<(), Error> {
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, out);
+
+    if display_settings.display_legend {
+        print_legend(
+            &mut *cursor.output(),
+            display_settings,
+            &cursor.get_tree_chars(),
+        )?;
+    }
+
+    writeln!(cursor.output(), "{}", Paint::default(file_name).bold())?;
+    if objects.is_empty() {
+        cursor.print_subitem(
+            EXPAND_INFO_STYLE
+                .paint("... (no objects found)")
+                .to_string(),
+            true,
+        )?;
+    }
+    let object_count = objects.len();
+    for (index, (object_id, location)) in objects.iter().enumerate() {
+        let obj = match raw_doc.objects.get(object_id) {
+            Some(obj) => obj,
+            None => continue,
+        };
+        let is_last = index + 1 == object_count;
+        let label = format!("({},{})", object_id.0, object_id.1);
+        let mut new_cursor = cursor.add_depth(DepthInfo {
+            name: Some(label.clone()),
+            indent_line: !is_last,
+        });
+        new_cursor.add_parent_object_id(*object_id);
+        cursor.print_subitem(
+            format!(
+                "{} {}",
+                get_pdf_object_info(display_settings, Some(label), obj)?,
+                EXTRA_INFO_STYLE.paint(format!("({})", location.description()))
+            ),
+            is_last,
+        )?;
+        print_pdf_object_content(display_settings, obj, raw_doc, &new_cursor)?;
+    }
+    Ok(())
+}
+
 pub fn get_pdf_object_info(
     display_settings: &TreeDisplaySettings,
     label: Option<String>,
     obj: &Object,
 ) -> Result<String, Error> {
-    let obj_print_info = get_object_print_info(obj, display_settings);
+    let mut obj_print_info = get_object_print_info(obj, display_settings);
+    if label.is_some() && matches!(obj, Object::Null) {
+        // A key with a `null` value is in the file, but readers treat it as if it was absent.
+        obj_print_info.extra_info =
+            Some("(key present with null value, treated as absent)".to_owned());
+    }
+    if display_settings.display_box_sizes {
+        if let Some(box_size) = label
+            .as_deref()
+            .filter(|label| PAGE_BOX_KEYS.contains(label))
+            .and_then(|_| get_box_size_info(obj))
+        {
+            obj_print_info.extra_info = Some(format!(
+                "{} {}",
+                obj_print_info.extra_info.unwrap_or_default(),
+                box_size
+            ));
+        }
+    }
 
     let type_name_styled = if display_settings.display_type_names {
         format!(
@@ -59,6 +276,11 @@ pub fn get_pdf_object_info(
         "".to_owned()
     };
     if let Some(label) = label {
+        let label = if display_settings.highlight_keys.contains(&label) {
+            HIGHLIGHT_STYLE.paint(label).to_string()
+        } else {
+            label
+        };
         if !obj_print_info.value.is_empty() {
             // Print with values
             Ok(format!(
@@ -110,10 +332,25 @@ pub fn print_pdf_object_content(
         Object::Real(_) => {}
         Object::Name(_) => {}
         Object::String(_, _) => {}
+        Object::Array(array_value) if is_inline_number_array(display_settings, array_value) => {
+            // Already printed in the value.
+        }
+        Object::Array(_) if cursor.is_past_only_path(display_settings) => {}
         Object::Array(array_value) => {
+            let next_expand_label = match cursor.next_expand_label(display_settings) {
+                Ok(label) => label,
+                Err(_) => {
+                    log::debug!("Took wrong path in tree somewhere.");
+                    return Ok(());
+                }
+            };
             let array_count = array_value.len();
             for (index, item) in array_value.iter().enumerate() {
-                if let Some(display_limit) = display_settings.array_display_limit {
+                if let Some(expand_label) = &next_expand_label {
+                    if expand_label != &index.to_string() {
+                        continue;
+                    }
+                } else if let Some(display_limit) = display_settings.array_display_limit {
                     if index < display_limit.max(2) - 1 || index == array_count - 1 {
                         // print first x items || print last item
                     } else if index == array_count - 2 {
@@ -121,10 +358,10 @@ pub fn print_pdf_object_content(
                         let skipped_items = array_count - display_limit.max(2);
                         cursor.print_subitem(
                             SKIPPED_STYLE
-                                .paint(format!("...skipped {} items...", skipped_items))
+                                .paint(get_skip_marker(display_settings, skipped_items, "items"))
                                 .to_string(),
                             false,
-                        );
+                        )?;
                         continue;
                     } else {
                         // print nothing (skipped)
@@ -132,12 +369,22 @@ pub fn print_pdf_object_content(
                     }
                 }
 
-                let is_last = index + 1 == array_count;
-                let new_cursor = cursor.add_depth(DepthInfo {
-                    name: None,
-                    indent_line: !is_last,
-                });
-                cursor.print_subitem(get_pdf_object_info(display_settings, None, item)?, is_last);
+                let is_last = index + 1 == array_count || next_expand_label.is_some();
+                if let Some(resolved_info) =
+                    get_resolved_scalar_info(display_settings, None, item, raw_doc)?
+                {
+                    cursor.print_subitem(resolved_info, is_last)?;
+                    continue;
+                }
+                let new_cursor = cursor.add_array_item_depth(index, !is_last);
+                cursor.print_subitem(
+                    format!(
+                        "{}{}",
+                        get_pdf_object_info(display_settings, None, item)?,
+                        get_direct_marker(display_settings, item)
+                    ),
+                    is_last,
+                )?;
                 print_pdf_object_content(display_settings, item, raw_doc, &new_cursor)?;
             }
         }
@@ -145,8 +392,14 @@ pub fn print_pdf_object_content(
             // Do not use new cursor here.
             print_pdf_dictionary(display_settings, dict_value, raw_doc, cursor)?;
         }
+        Object::Stream(_) if cursor.is_past_only_path(display_settings) => {}
         Object::Stream(stream_value) => {
-            pdf_content_stream::print_content_stream(display_settings, stream_value, cursor)?;
+            pdf_content_stream::print_content_stream(
+                display_settings,
+                stream_value,
+                raw_doc,
+                cursor,
+            )?;
         }
         Object::Reference(object_id) => {
             let mut new_cursor = cursor.add_depth(DepthInfo {
@@ -158,22 +411,65 @@ pub fn print_pdf_object_content(
                 None => {
                     cursor.print_subitem(
                         ERROR_STYLE
-                            .paint("Error in PDF: Indirect Reference not found.")
+                            .paint(get_missing_object_message(raw_doc, *object_id))
                             .to_string(),
                         true,
-                    );
+                    )?;
                     return Ok(());
                 }
             };
-            let print_ref_content = if display_settings.display_parent {
-                true
-            } else {
-                // false if: this reference is to its parent.
-                // true if: to a different reference.
-                !cursor.check_parent_visited(object_id)
-            };
+            if cursor.get_max_depth_count(display_settings) > display_settings.max_depth {
+                // Without this check a loop of references would never end
+                // when the cycle guard is disabled.
+                cursor.print_subitem(
+                    EXPAND_INFO_STYLE
+                        .paint("... (reached `max-depth`)")
+                        .to_string(),
+                    true,
+                )?;
+                return Ok(());
+            }
+            // References on the `expand` path (including the last one) do not count
+            // for `follow_refs_depth`.
+            let on_expand_path = display_settings
+                .expand
+                .as_ref()
+                .is_some_and(|expand| cursor.get_path().len() <= expand.len());
+            if !on_expand_path {
+                if display_settings
+                    .follow_refs_depth
+                    .is_some_and(|follow_refs_depth| {
+                        cursor.get_reference_depth() >= follow_refs_depth
+                    })
+                {
+                    cursor.print_subitem(
+                        EXPAND_INFO_STYLE
+                            .paint("... (display with a higher `follow-refs-depth`)")
+                            .to_string(),
+                        true,
+                    )?;
+                    return Ok(());
+                }
+                new_cursor.add_reference_depth();
+            }
+            let print_ref_content =
+                if display_settings.display_parent || display_settings.no_cycle_guard {
+                    true
+                } else {
+                    // false if: this reference is to its parent.
+                    // true if: to a different reference.
+                    !cursor.check_parent_visited(object_id)
+                };
             if print_ref_content {
-                cursor.print_subitem(get_pdf_object_info(display_settings, None, ref_obj)?, true);
+                let mut object_info = get_pdf_object_info(display_settings, None, ref_obj)?;
+                if display_settings.mark_indirect {
+                    object_info = format!(
+                        "{} {}",
+                        object_info,
+                        EXTRA_INFO_STYLE.paint("(via reference)")
+                    );
+                }
+                cursor.print_subitem(object_info, true)?;
                 new_cursor.add_parent_object_id(*object_id);
                 print_pdf_object_content(display_settings, ref_obj, raw_doc, &new_cursor)?;
             } else {
@@ -183,7 +479,7 @@ pub fn print_pdf_object_content(
                         .paint("... (display with `display-parent` flag)")
                         .to_string(),
                     true,
-                );
+                )?;
             }
         }
     }
@@ -197,18 +493,32 @@ pub fn print_pdf_dictionary(
     cursor: &TreeCursorInfo,
 ) -> Result<(), Error> {
     // Return when we should not go deeper.
-    if cursor.get_depth_count() >= display_settings.max_depth {
+    if cursor.get_max_depth_count(display_settings) >= display_settings.max_depth {
         if !dict.is_empty() {
             cursor.print_subitem(
                 EXPAND_INFO_STYLE
                     .paint("... (reached `max-depth`)")
                     .to_string(),
                 true,
-            );
+            )?;
         }
         return Ok(());
     }
 
+    if cursor.is_past_only_path(display_settings) {
+        return Ok(());
+    }
+
+    // Remember the resources, so content streams further down can resolve names.
+    let mut cursor = cursor.clone();
+    if let Ok((_, Object::Dictionary(resources))) = dict
+        .get(b"Resources")
+        .and_then(|resources| raw_doc.dereference(resources))
+    {
+        cursor.set_resources(resources.clone());
+    }
+    let cursor = &cursor;
+
     // Get next expand item
     let next_expand_label = match cursor.next_expand_label(display_settings) {
         Ok(x) => x,
@@ -218,9 +528,15 @@ pub fn print_pdf_dictionary(
         }
     };
 
-    let dict_count = dict.len();
-    for (index, (label, obj)) in dict.iter().enumerate() {
-        let label = String::from_utf8_lossy(label).to_string();
+    let mut entries: Vec<(String, &Object)> = dict
+        .iter()
+        .map(|(label, obj)| (String::from_utf8_lossy(label).to_string(), obj))
+        .collect();
+    if display_settings.sort_keys {
+        entries.sort_by(|(first, _), (second, _)| first.cmp(second));
+    }
+    let dict_count = entries.len();
+    for (index, (label, obj)) in entries.into_iter().enumerate() {
         // Check if item should be expended.
         let mut pre_expand = false;
         if let Some(expand_label) = &next_expand_label {
@@ -236,21 +552,297 @@ pub fn print_pdf_dictionary(
             name: Some(label.clone()),
             indent_line: !is_last,
         });
+        let (labels, obj, dict, new_cursor) = if display_settings.collapse_single_child {
+            get_single_child_chain(display_settings, label, obj, dict, raw_doc, new_cursor)
+        } else {
+            (vec![label], obj, dict, new_cursor)
+        };
+        // The key of `obj`, the printed label joins the keys of a collapsed chain.
+        let label = labels[labels.len() - 1].clone();
+        let printed_label = if labels.len() > 1 {
+            labels
+                .iter()
+                .map(|label| {
+                    if display_settings.highlight_keys.contains(label) {
+                        HIGHLIGHT_STYLE.paint(label).to_string()
+                    } else {
+                        label.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(&HELPER_CHARS_STYLE.paint(" > ").to_string())
+        } else {
+            label.clone()
+        };
 
-        cursor.print_subitem(
-            get_pdf_object_info(display_settings, Some(label.clone()), obj)?,
-            is_last,
+        if let Some(resolved_info) =
+            get_resolved_scalar_info(display_settings, Some(printed_label.clone()), obj, raw_doc)?
+        {
+            cursor.print_subitem(resolved_info, is_last)?;
+            continue;
+        }
+        let mut object_info = format!(
+            "{}{}",
+            get_pdf_object_info(display_settings, Some(printed_label), obj)?,
+            get_direct_marker(display_settings, obj)
         );
+        if let Some(destination_info) = get_destination_info(&label, dict, obj, raw_doc) {
+            object_info = format!("{} {}", object_info, destination_info);
+        }
+        if label == "Rotate" {
+            object_info = format!("{} {}", object_info, get_rotate_info(obj, raw_doc));
+        }
+        if let Some(permissions_info) = get_permissions_info(&label, dict, obj) {
+            object_info = format!("{} {}", object_info, permissions_info);
+        }
+        // With `overview_tree` only the keys are printed once past the `expand` path.
+        if display_settings.overview_tree && next_expand_label.is_none() {
+            if let Some(child_count) = get_child_count(obj, raw_doc) {
+                object_info = format!(
+                    "{} {}",
+                    object_info,
+                    EXTRA_INFO_STYLE.paint(format!("({} children)", child_count))
+                );
+            }
+            cursor.print_subitem(object_info, is_last)?;
+            continue;
+        }
+        cursor.print_subitem(object_info, is_last)?;
         if !display_settings.display_font && &label == "Font" {
             cursor.print_subitem(
                 EXPAND_INFO_STYLE
                     .paint("... (display with `display-font` flag)")
                     .to_string(),
                 true,
-            );
+            )?;
             continue;
         }
         print_pdf_object_content(display_settings, obj, raw_doc, &new_cursor)?;
     }
     Ok(())
 }
+
+/// Follow the dictionaries with a single entry below `obj`, for `collapse_single_child`.
+///
+/// Returns the keys of the chain, the last value with the dictionary it is in and the cursor
+/// to print the content of that value with. Arrays and streams end the chain.
+fn get_single_child_chain<'a, 'o>(
+    display_settings: &TreeDisplaySettings,
+    label: String,
+    obj: &'a Object,
+    dict: &'a Dictionary,
+    raw_doc: &'a Document,
+    cursor: TreeCursorInfo<'o>,
+) -> (Vec<String>, &'a Object, &'a Dictionary, TreeCursorInfo<'o>) {
+    let mut labels = vec![label];
+    let (mut obj, mut dict, mut cursor) = (obj, dict, cursor);
+    // The content of `Font` is not printed without `display_font`.
+    while display_settings.display_font || labels[labels.len() - 1] != "Font" {
+        let (child_dict, dict_cursor) =
+            match get_collapsible_dictionary(display_settings, obj, raw_doc, &cursor) {
+                Some(child) => child,
+                None => break,
+            };
+        let (child_label, child_obj) = match child_dict.iter().next() {
+            Some((child_label, child_obj)) => {
+                (String::from_utf8_lossy(child_label).to_string(), child_obj)
+            }
+            None => break,
+        };
+        match dict_cursor.next_expand_label(display_settings) {
+            Ok(Some(expand_label)) if expand_label != child_label => break,
+            Err(_) => break,
+            _ => {}
+        }
+        cursor = dict_cursor.add_collapsed_depth(Some(child_label.clone()));
+        labels.push(child_label);
+        obj = child_obj;
+        dict = child_dict;
+    }
+    (labels, obj, dict, cursor)
+}
+
+/// The dictionary of `obj` (directly or by reference) when it has a single entry and
+/// `print_pdf_dictionary` would print it, with the cursor to print its entry with.
+fn get_collapsible_dictionary<'a, 'o>(
+    display_settings: &TreeDisplaySettings,
+    obj: &'a Object,
+    raw_doc: &'a Document,
+    cursor: &TreeCursorInfo<'o>,
+) -> Option<(&'a Dictionary, TreeCursorInfo<'o>)> {
+    let (dict, mut dict_cursor) = match obj {
+        Object::Dictionary(dict) => (dict, cursor.clone()),
+        Object::Reference(object_id) => {
+            let dict = match raw_doc.objects.get(object_id) {
+                Some(Object::Dictionary(dict)) => dict,
+                _ => return None,
+            };
+            // Same checks as for references in `print_pdf_object_content`.
+            if cursor.get_max_depth_count(display_settings) > display_settings.max_depth
+                || cursor.check_parent_visited(object_id)
+            {
+                return None;
+            }
+            let mut dict_cursor = cursor.add_collapsed_depth(None);
+            let on_expand_path = display_settings
+                .expand
+                .as_ref()
+                .is_some_and(|expand| cursor.get_path().len() <= expand.len());
+            if !on_expand_path {
+                if display_settings
+                    .follow_refs_depth
+                    .is_some_and(|follow_refs_depth| {
+                        cursor.get_reference_depth() >= follow_refs_depth
+                    })
+                {
+                    return None;
+                }
+                dict_cursor.add_reference_depth();
+            }
+            dict_cursor.add_parent_object_id(*object_id);
+            (dict, dict_cursor)
+        }
+        _ => return None,
+    };
+    if dict.len() != 1
+        || dict_cursor.get_max_depth_count(display_settings) >= display_settings.max_depth
+        || dict_cursor.is_past_only_path(display_settings)
+    {
+        return None;
+    }
+    if let Ok((_, Object::Dictionary(resources))) = dict
+        .get(b"Resources")
+        .and_then(|resources| raw_doc.dereference(resources))
+    {
+        dict_cursor.set_resources(resources.clone());
+    }
+    Some((dict, dict_cursor))
+}
+
+/// Describe the page rotation of a `Rotate` value, like `90° clockwise`.
+///
+/// See p79 (7.7.3.3 Page Objects) in PDF v1.7 Spec.
+fn get_rotate_info(obj: &Object, raw_doc: &Document) -> String {
+    let rotate = match raw_doc.dereference(obj) {
+        Ok((_, Object::Integer(rotate))) => *rotate,
+        _ => {
+            return ERROR_STYLE
+                .paint("(invalid: must be an integer)")
+                .to_string()
+        }
+    };
+    if rotate % 90 != 0 {
+        return ERROR_STYLE
+            .paint("(invalid: must be a multiple of 90)")
+            .to_string();
+    }
+    let description = match rotate.rem_euclid(360) {
+        0 => "not rotated",
+        90 => "90° clockwise",
+        180 => "180°, upside down",
+        _ => "90° counterclockwise",
+    };
+    EXTRA_INFO_STYLE
+        .paint(format!("({})", description))
+        .to_string()
+}
+
+/// Describe the permissions of the `P` entry in an encryption dictionary.
+fn get_permissions_info(label: &str, dict: &Dictionary, obj: &Object) -> Option<String> {
+    // Encryption dictionaries have a `Filter` and the `O` and `U` password entries.
+    let is_encryption_dict = dict.has(b"Filter") && dict.has(b"O") && dict.has(b"U");
+    if label != "P" || !is_encryption_dict {
+        return None;
+    }
+    let p_value = obj.as_i64().ok()?;
+    Some(
+        EXTRA_INFO_STYLE
+            .paint(format!("({})", describe_permissions(p_value)))
+            .to_string(),
+    )
+}
+
+/// Get the amount of items in a dictionary, stream dictionary or referenced array.
+/// `None` for other objects, direct arrays already print their length.
+fn get_child_count(obj: &Object, raw_doc: &Document) -> Option<usize> {
+    match obj {
+        Object::Dictionary(dict) => Some(dict.len()),
+        Object::Stream(stream) => Some(stream.dict.len()),
+        Object::Reference(object_id) => match raw_doc.objects.get(object_id)? {
+            Object::Array(list) => Some(list.len()),
+            Object::Reference(_) => None,
+            ref_obj => get_child_count(ref_obj, raw_doc),
+        },
+        _ => None,
+    }
+}
+
+/// With `mark_indirect`, mark dictionaries and arrays that are stored directly in their parent.
+///
+/// Dereferenced objects are marked with `(via reference)` instead.
+fn get_direct_marker(display_settings: &TreeDisplaySettings, obj: &Object) -> String {
+    if display_settings.mark_indirect && matches!(obj, Object::Dictionary(_) | Object::Array(_)) {
+        format!(" {}", EXTRA_INFO_STYLE.paint("(direct)"))
+    } else {
+        String::new()
+    }
+}
+
+/// For the destination of a Link annotation, outline item or `GoTo` action,
+/// get the page it points to.
+fn get_destination_info(
+    label: &str,
+    dict: &Dictionary,
+    obj: &Object,
+    raw_doc: &Document,
+) -> Option<String> {
+    let is_goto_action = || {
+        dict.get(b"S")
+            .and_then(Object::as_name)
+            .is_ok_and(|action| action == b"GoTo")
+    };
+    if label != "Dest" && !(label == "D" && is_goto_action()) {
+        return None;
+    }
+    let info = match resolve_destination(raw_doc, obj) {
+        Some(destination) => EXTRA_INFO_STYLE
+            .paint(format!("({})", destination.description()))
+            .to_string(),
+        None => ERROR_STYLE
+            .paint("(destination could not be resolved)")
+            .to_string(),
+    };
+    Some(info)
+}
+
+/// With `resolve_scalars`, get the info of the value a reference points to if it is a simple value.
+///
+/// Returns `None` when the setting is off, the object is no reference or the value is not simple.
+fn get_resolved_scalar_info(
+    display_settings: &TreeDisplaySettings,
+    label: Option<String>,
+    obj: &Object,
+    raw_doc: &Document,
+) -> Result<Option<String>, Error> {
+    if !display_settings.resolve_scalars {
+        return Ok(None);
+    }
+    let object_id = match obj {
+        Object::Reference(object_id) => object_id,
+        _ => return Ok(None),
+    };
+    match raw_doc.objects.get(object_id) {
+        Some(
+            ref_obj @ (Object::Boolean(_)
+            | Object::Integer(_)
+            | Object::Real(_)
+            | Object::Name(_)
+            | Object::String(_, _)),
+        ) => Ok(Some(format!(
+            "{} {}",
+            get_pdf_object_info(display_settings, label, ref_obj)?,
+            EXTRA_INFO_STYLE.paint(format!("(indirect from ({},{}))", object_id.0, object_id.1))
+        ))),
+        _ => Ok(None),
+    }
+}
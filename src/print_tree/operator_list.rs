@@ -0,0 +1,42 @@
+use super::cursor_info::TreeChars;
+use super::stream_operations::{get_operator_help, KNOWN_OPERATORS};
+use super::{EXTRA_INFO_STYLE, HELPER_CHARS_STYLE};
+use std::io::Write;
+use unicode_width::UnicodeWidthStr;
+use yansi::Paint;
+
+/// Print all known content stream operators with their arguments and description.
+pub fn print_operator_list(out: &mut dyn Write, tree_chars: &TreeChars) -> std::io::Result<()> {
+    let rows: Vec<(&str, String, &str)> = KNOWN_OPERATORS
+        .iter()
+        .filter_map(|operator| {
+            let (description, arguments) = get_operator_help(operator)?;
+            Some((*operator, arguments.join(" "), description))
+        })
+        .collect();
+    let operator_width = rows
+        .iter()
+        .map(|(operator, _, _)| operator.width())
+        .max()
+        .unwrap_or(0);
+    let arguments_width = rows
+        .iter()
+        .map(|(_, arguments, _)| arguments.width())
+        .max()
+        .unwrap_or(0);
+
+    writeln!(out, "--- {} ---", Paint::cyan("Operators").bold())?;
+    for (operator, arguments, description) in rows {
+        writeln!(
+            out,
+            "{}{} {}{} {} {}",
+            Paint::default(operator).bold(),
+            " ".repeat(operator_width - operator.width()),
+            EXTRA_INFO_STYLE.paint(&arguments),
+            " ".repeat(arguments_width - arguments.width()),
+            HELPER_CHARS_STYLE.paint(tree_chars.box_vertical),
+            description
+        )?;
+    }
+    Ok(())
+}
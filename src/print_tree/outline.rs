@@ -0,0 +1,163 @@
+use super::cursor_info::{DepthInfo, TreeCursorInfo};
+use super::{
+    decode_text_string, TreeCursorSettings, ERROR_STYLE, EXPAND_INFO_STYLE, EXTRA_INFO_STYLE,
+    VALUE_STYLE,
+};
+use crate::destinations::resolve_destination;
+use lopdf::{Dictionary, Document, Error, Object, ObjectId};
+use std::io::Write;
+use yansi::Paint;
+
+/// Print the outline (bookmarks) with the title and destination page of each item.
+///
+/// Children are found through `First` and siblings through `Next`. Named destinations
+/// are looked up in the `Dests` name tree.
+///
+/// See 12.3.3 (Document Outline) in PDF v1.7 Spec.
+pub fn print_outline(
+    out: &mut dyn Write,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+    file_name: String,
+) -> Result<(), Error> {
+    writeln!(out, "{}", Paint::default(file_name).bold())?;
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, out);
+
+    let outlines = raw_doc
+        .catalog()?
+        .get(b"Outlines")
+        .ok()
+        .and_then(|outlines| raw_doc.dereference(outlines).ok());
+    match outlines {
+        Some((outlines_id, Object::Dictionary(outlines))) => {
+            let mut cursor = cursor;
+            if let Some(outlines_id) = outlines_id {
+                cursor.add_parent_object_id(outlines_id);
+            }
+            if !print_outline_items(raw_doc, outlines, &cursor)? {
+                cursor.print_subitem(
+                    EXPAND_INFO_STYLE
+                        .paint("... (outline has no items)")
+                        .to_string(),
+                    true,
+                )?;
+            }
+        }
+        Some(_) => cursor.print_subitem(
+            ERROR_STYLE
+                .paint("(`Outlines` is not a dictionary)")
+                .to_string(),
+            true,
+        )?,
+        None => cursor.print_subitem(
+            EXPAND_INFO_STYLE
+                .paint("... (document has no outline)")
+                .to_string(),
+            true,
+        )?,
+    }
+    Ok(())
+}
+
+/// Print the children of an outline item (or of the outline dictionary).
+///
+/// Returns `false` when there are no children.
+fn print_outline_items(
+    raw_doc: &Document,
+    parent: &Dictionary,
+    cursor: &TreeCursorInfo,
+) -> std::io::Result<bool> {
+    // Collect the siblings first, to know which one is the last.
+    let mut items: Vec<(ObjectId, &Dictionary)> = Vec::new();
+    let mut next = parent.get(b"First").and_then(Object::as_reference).ok();
+    let mut loop_found = false;
+    while let Some(item_id) = next {
+        // `Next` or `First` can point back to an item that is already printed.
+        if cursor.check_parent_visited(&item_id) || items.iter().any(|(id, _)| *id == item_id) {
+            loop_found = true;
+            break;
+        }
+        match raw_doc.get_dictionary(item_id) {
+            Ok(item) => {
+                items.push((item_id, item));
+                next = item.get(b"Next").and_then(Object::as_reference).ok();
+            }
+            Err(_) => {
+                log::warn!(
+                    "Outline item ({},{}) is not a dictionary.",
+                    item_id.0,
+                    item_id.1
+                );
+                break;
+            }
+        }
+    }
+
+    let item_count = items.len();
+    for (index, (item_id, item)) in items.into_iter().enumerate() {
+        let is_last = index + 1 == item_count && !loop_found;
+        cursor.print_subitem(get_outline_item_text(raw_doc, item_id, item), is_last)?;
+        let mut child_cursor = cursor.add_depth(DepthInfo {
+            name: None,
+            indent_line: !is_last,
+        });
+        child_cursor.add_parent_object_id(item_id);
+        print_outline_items(raw_doc, item, &child_cursor)?;
+    }
+    if loop_found {
+        log::warn!("Outline items refer back to each other, stopped following them.");
+        cursor.print_subitem(
+            ERROR_STYLE
+                .paint("(loop: item refers back to an earlier item)")
+                .to_string(),
+            true,
+        )?;
+    }
+    Ok(item_count > 0)
+}
+
+/// The title of an outline item with the page of its `Dest` or `GoTo` action.
+fn get_outline_item_text(raw_doc: &Document, item_id: ObjectId, item: &Dictionary) -> String {
+    let title = item
+        .get(b"Title")
+        .and_then(|title| raw_doc.dereference(title));
+    let title = match title {
+        Ok((_, Object::String(title, _))) => VALUE_STYLE
+            .paint(format!("'{}'", decode_text_string(title)))
+            .to_string(),
+        _ => ERROR_STYLE.paint("(no title)").to_string(),
+    };
+    let destination = match item.get(b"Dest") {
+        Ok(destination) => Some(destination),
+        // Only `GoTo` actions point to a page in this document.
+        Err(_) => item
+            .get(b"A")
+            .and_then(|action| raw_doc.dereference(action))
+            .and_then(|(_, action)| action.as_dict())
+            .ok()
+            .filter(|action| {
+                action
+                    .get(b"S")
+                    .and_then(Object::as_name)
+                    .is_ok_and(|action_type| action_type == b"GoTo")
+            })
+            .and_then(|action| action.get(b"D").ok()),
+    };
+    let destination = match destination {
+        Some(destination) => match resolve_destination(raw_doc, destination) {
+            Some(destination) => EXTRA_INFO_STYLE
+                .paint(destination.description())
+                .to_string(),
+            None => ERROR_STYLE
+                .paint("(destination could not be resolved)")
+                .to_string(),
+        },
+        None => EXPAND_INFO_STYLE.paint("(no destination)").to_string(),
+    };
+    format!(
+        "{} {} {}",
+        title,
+        destination,
+        EXPAND_INFO_STYLE.paint(format!("({},{})", item_id.0, item_id.1))
+    )
+}
@@ -0,0 +1,106 @@
+use super::cursor_info::{DepthInfo, TreeCursorInfo};
+use super::{TreeCursorSettings, ERROR_STYLE, EXPAND_INFO_STYLE, EXTRA_INFO_STYLE, VALUE_STYLE};
+use crate::page_tree::{
+    load_page_tree, AttributeSource, PageTreeNode, PageTreeNodeKind, INHERITABLE_ATTRIBUTES,
+};
+use lopdf::{Document, Error};
+use std::io::Write;
+use yansi::Paint;
+
+/// Print the `Pages` hierarchy and where each page gets its inheritable attributes from.
+pub fn print_page_tree(
+    out: &mut dyn Write,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+    file_name: String,
+) -> Result<(), Error> {
+    let page_tree = load_page_tree(raw_doc)?;
+    writeln!(out, "{}", Paint::default(file_name).bold())?;
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, out);
+    let mut page_number = 0;
+    print_page_tree_node(&page_tree, &cursor, true, &mut page_number)?;
+    Ok(())
+}
+
+fn print_page_tree_node(
+    node: &PageTreeNode,
+    cursor: &TreeCursorInfo,
+    is_last: bool,
+    page_number: &mut usize,
+) -> std::io::Result<()> {
+    let new_cursor = cursor.add_depth(DepthInfo {
+        name: None,
+        indent_line: !is_last,
+    });
+    let object_id = VALUE_STYLE.paint(format!("({},{})", node.object_id.0, node.object_id.1));
+
+    match node.kind {
+        PageTreeNodeKind::Pages => {
+            let page_count = node.leaf_pages().len();
+            let count = match node.count {
+                Some(count) if count == page_count as i64 => VALUE_STYLE.paint(count).to_string(),
+                Some(count) => ERROR_STYLE
+                    .paint(format!("{} (found {} pages)", count, page_count))
+                    .to_string(),
+                None => ERROR_STYLE
+                    .paint(format!("missing (found {} pages)", page_count))
+                    .to_string(),
+            };
+            let defined: Vec<_> = INHERITABLE_ATTRIBUTES
+                .iter()
+                .zip(&node.attributes)
+                .filter(|(_, source)| {
+                    matches!(source, AttributeSource::Own | AttributeSource::Overrides(_))
+                })
+                .map(|(attribute, _)| *attribute)
+                .collect();
+            let defined = if defined.is_empty() {
+                "".to_owned()
+            } else {
+                EXTRA_INFO_STYLE
+                    .paint(format!("(defines: {})", defined.join(", ")))
+                    .to_string()
+            };
+            cursor.print_subitem(
+                format!("Pages {} Count = {} {}", object_id, count, defined),
+                is_last,
+            )?;
+
+            let kid_count = node.kids.len();
+            for (index, kid) in node.kids.iter().enumerate() {
+                print_page_tree_node(kid, &new_cursor, index + 1 == kid_count, page_number)?;
+            }
+        }
+        PageTreeNodeKind::Page => {
+            *page_number += 1;
+            cursor.print_subitem(format!("Page {} {}", page_number, object_id), is_last)?;
+
+            let attribute_count = INHERITABLE_ATTRIBUTES.len();
+            for (index, (attribute, source)) in INHERITABLE_ATTRIBUTES
+                .iter()
+                .zip(&node.attributes)
+                .enumerate()
+            {
+                let source = match source {
+                    AttributeSource::Own => "own".to_owned(),
+                    AttributeSource::Overrides(ancestor) => EXTRA_INFO_STYLE
+                        .paint(format!("own, overrides ({},{})", ancestor.0, ancestor.1))
+                        .to_string(),
+                    AttributeSource::Inherited(ancestor) => EXTRA_INFO_STYLE
+                        .paint(format!("inherited from ({},{})", ancestor.0, ancestor.1))
+                        .to_string(),
+                    // `MediaBox` is required, the others have a default.
+                    AttributeSource::Missing if *attribute == "MediaBox" => {
+                        ERROR_STYLE.paint("missing (required)").to_string()
+                    }
+                    AttributeSource::Missing => EXPAND_INFO_STYLE.paint("not set").to_string(),
+                };
+                new_cursor.print_subitem(
+                    format!("{}: {}", attribute, source),
+                    index + 1 == attribute_count,
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use super::cursor_info::TreeCursorInfo;
+use super::{TreeCursorSettings, ERROR_STYLE, EXPAND_INFO_STYLE, EXTRA_INFO_STYLE, VALUE_STYLE};
+use crate::references::{get_references, Reference};
+use lopdf::{Document, Error, Object, ObjectId};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use yansi::Paint;
+
+/// Stop searching for more paths after this many partial paths are checked.
+static MAX_SEARCH_STEPS: usize = 100_000;
+
+/// Where a reference is found: in the trailer or in an object, under a key path like `Kids.0`.
+#[derive(Debug, Clone)]
+struct IncomingReference {
+    source: Option<ObjectId>,
+    key_path: String,
+}
+
+/// Print the paths from the trailer to `object_id`, shortest first.
+///
+/// Paths never visit the same object twice and `Parent` references are not followed.
+pub fn print_parents(
+    out: &mut dyn Write,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+    file_name: String,
+    object_id: ObjectId,
+    limit: usize,
+) -> Result<(), Error> {
+    writeln!(
+        out,
+        "{} {}",
+        Paint::default(file_name).bold(),
+        EXTRA_INFO_STYLE.paint(format!("(paths to ({},{}))", object_id.0, object_id.1))
+    )?;
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, out);
+    if !raw_doc.objects.contains_key(&object_id) {
+        cursor.print_subitem(
+            ERROR_STYLE
+                .paint("Object does not exist in this document.")
+                .to_string(),
+            true,
+        )?;
+        return Ok(());
+    }
+
+    let incoming = get_incoming_references(raw_doc);
+    let paths = find_paths(&incoming, object_id, limit);
+    if paths.is_empty() {
+        cursor.print_subitem(
+            EXPAND_INFO_STYLE
+                .paint("... (object can not be reached from the trailer)")
+                .to_string(),
+            true,
+        )?;
+        return Ok(());
+    }
+    let path_count = paths.len();
+    for (index, path) in paths.iter().enumerate() {
+        let steps: Vec<String> = path
+            .iter()
+            .map(|(key_path, target)| {
+                format!(
+                    "{} {}",
+                    key_path,
+                    VALUE_STYLE.paint(format!("({},{})", target.0, target.1))
+                )
+            })
+            .collect();
+        cursor.print_subitem(steps.join(" → "), index + 1 == path_count)?;
+    }
+    if path_count == limit {
+        log::info!(
+            "Only the first {} paths are printed, use `limit` to print more.",
+            limit
+        );
+    }
+    Ok(())
+}
+
+/// Find for every object where it is referenced from.
+fn get_incoming_references(raw_doc: &Document) -> BTreeMap<ObjectId, Vec<IncomingReference>> {
+    let mut incoming: BTreeMap<ObjectId, Vec<IncomingReference>> = BTreeMap::new();
+    let mut add_references = |source: Option<ObjectId>, obj: &Object| {
+        for Reference {
+            object_id: target,
+            key_path,
+        } in get_references(obj)
+        {
+            // `Parent` points back up the tree, it does not explain why an object is used.
+            if key_path == "Parent" {
+                continue;
+            }
+            incoming
+                .entry(target)
+                .or_default()
+                .push(IncomingReference { source, key_path });
+        }
+    };
+    add_references(None, &Object::Dictionary(raw_doc.trailer.clone()));
+    for (object_id, obj) in &raw_doc.objects {
+        add_references(Some(*object_id), obj);
+    }
+    incoming
+}
+
+/// Search backwards from the target to the trailer, breadth first so shorter paths come first.
+///
+/// Each path is a list of `(key_path, object_id)` steps, starting at the trailer.
+fn find_paths(
+    incoming: &BTreeMap<ObjectId, Vec<IncomingReference>>,
+    target: ObjectId,
+    limit: usize,
+) -> Vec<Vec<(String, ObjectId)>> {
+    let mut paths = Vec::new();
+    // The steps found so far (from the target upwards) and the object to continue from.
+    let mut queue: VecDeque<(Vec<(String, ObjectId)>, ObjectId)> = VecDeque::new();
+    queue.push_back((Vec::new(), target));
+    let mut search_steps = 0;
+    while let Some((steps, current)) = queue.pop_front() {
+        search_steps += 1;
+        if search_steps > MAX_SEARCH_STEPS {
+            log::warn!("Stopped searching for paths, the document has too many references.");
+            break;
+        }
+        for reference in incoming.get(&current).into_iter().flatten() {
+            let mut new_steps = steps.clone();
+            new_steps.push((reference.key_path.clone(), current));
+            match reference.source {
+                None => {
+                    new_steps.reverse();
+                    paths.push(new_steps);
+                    if paths.len() >= limit {
+                        return paths;
+                    }
+                }
+                Some(source) => {
+                    // Do not visit the same object twice.
+                    if new_steps.iter().all(|(_, object_id)| *object_id != source) {
+                        queue.push_back((new_steps, source));
+                    }
+                }
+            }
+        }
+    }
+    paths
+}
@@ -1,83 +1,395 @@
+use super::color_state::{get_color_swatch, ColorStateTracker};
 use super::cursor_info::DepthInfo;
+use super::inline_images::decode_content_stream;
+use super::internal_streams::print_internal_stream_summary;
+use super::pdf_objects::{get_skip_marker, get_type_label};
 use super::stream_operations::{operation_info, OperationInfoValue};
+use super::text_position::{format_number, TextPositionTracker};
 use super::{cursor_info::TreeCursorInfo, TreeDisplaySettings};
-use super::{get_object_print_info, EXPAND_INFO_STYLE, EXTRA_INFO_STYLE, VALUE_STYLE};
+use super::{
+    get_object_print_info, get_pdf_object_info, ERROR_STYLE, EXPAND_INFO_STYLE, EXTRA_INFO_STYLE,
+    SKIPPED_STYLE, VALUE_STYLE,
+};
+use crate::exit_reason::set_failed_object;
+use crate::object_offsets::get_missing_object_message;
 use lopdf::content::Operation;
-use lopdf::{Error, Object, Stream};
+use lopdf::{Dictionary, Document, Error, Object, Stream};
+use unicode_width::UnicodeWidthStr;
+
+/// Streams stored under these keys are decoded as content streams.
+static CONTENT_STREAM_KEYS: [&str; 5] = ["Contents", "N", "R", "D", "AP"];
 
 pub fn print_content_stream(
     display_settings: &TreeDisplaySettings,
     stream: &Stream,
+    raw_doc: &Document,
     cursor: &TreeCursorInfo,
 ) -> Result<(), Error> {
-    // Check is last in path is "Contents" or some other known names
-    let last_path_label = cursor.get_path().pop();
-    if last_path_label == Some("Contents".to_owned())
-        || last_path_label == Some("N".to_owned())
-        || last_path_label == Some("R".to_owned())
-        || last_path_label == Some("D".to_owned())
-        || last_path_label == Some("AP".to_owned())
-        || display_settings.force_stream_decoding
-    {
+    if is_content_stream(display_settings, cursor) {
+        if display_settings.no_decompress {
+            cursor.print_subitem(
+                EXPAND_INFO_STYLE
+                    .paint("... (content not decoded, streams are not decompressed with `no-decompress`)")
+                    .to_string(),
+                false,
+            )?;
+            return Ok(());
+        }
+        // Internal streams contain no operations, show what is in them instead.
+        if print_internal_stream_summary(display_settings, stream, cursor)? {
+            return Ok(());
+        }
+        // Form XObjects carry their own resources, otherwise use the ones of the page.
+        let resources = match stream.dict.get_deref(b"Resources", raw_doc) {
+            Ok(Object::Dictionary(resources)) => Some(resources),
+            _ => cursor.get_resources(),
+        };
         // Decode stream
-        let decoded_stream = stream.decode_content()?;
-        for operation in decoded_stream.operations {
-            print_operation_string(display_settings, &operation, cursor)?;
+        let decoded_stream = decode_content_stream(stream).inspect_err(|_| {
+            if let Some(object_id) = cursor.get_current_object_id() {
+                set_failed_object(object_id);
+            }
+        })?;
+        if decoded_stream.operations.is_empty() && has_non_trivial_content(stream) {
+            log::warn!(
+                "Content stream decoded to zero operations but is not empty, \
+                the stream might have an unsupported filter or is not a content stream."
+            );
+            cursor.print_subitem(
+                ERROR_STYLE
+                    .paint(format!(
+                        "No operations found in {} bytes of content (filter problem or not a content stream?)",
+                        stream.content.len()
+                    ))
+                    .to_string(),
+                true,
+            )?;
+        }
+        let operations = &decoded_stream.operations;
+        let mut color_state = ColorStateTracker::new();
+        let mut text_position = TextPositionTracker::new();
+        // Get the colors in effect for painting operations and the position of text.
+        let mut get_note = |operation: &Operation| {
+            let mut notes = Vec::new();
+            if display_settings.track_colors {
+                color_state.apply(operation);
+                notes.extend(color_state.get_painting_info(&operation.operator));
+            }
+            if display_settings.text_positions {
+                text_position.apply(operation);
+                notes.extend(text_position.get_text_position_info(&operation.operator));
+            }
+            if notes.is_empty() {
+                None
+            } else {
+                Some(notes.join(", "))
+            }
+        };
+        // The path can end with the index of an operation, then only that operation is printed.
+        let expand_index = match cursor.next_expand_label(display_settings) {
+            Ok(Some(label)) => match label.parse::<usize>() {
+                Ok(expand_index) => Some(expand_index),
+                Err(_) => {
+                    log::debug!("Expand path does not contain an operation index.");
+                    return Ok(());
+                }
+            },
+            Ok(None) => None,
+            Err(_) => return Ok(()),
+        };
+        // Operations between `q` and `Q` (and other pairs) are printed one level deeper.
+        // Only done when all operations are printed, otherwise pairs can be incomplete.
+        let nest_operations = display_settings.stream_nesting
+            && display_settings.stream_enhanced_operations
+            && expand_index.is_none()
+            && display_settings.operator_filter.is_none();
+        // The opening operators that are not closed yet, with the cursor for the operations inside.
+        let mut open_groups: Vec<(&str, TreeCursorInfo)> = Vec::new();
+        let mut skipped_operations = 0;
+        let mut index = 0;
+        while index < operations.len() {
+            let operation = &operations[index];
+            let note = get_note(operation);
+            if expand_index.is_some_and(|expand_index| expand_index != index) {
+                index += 1;
+                continue;
+            }
+            if let Some(operator_filter) = &display_settings.operator_filter {
+                if !operator_filter.contains(&operation.operator) {
+                    skipped_operations += 1;
+                    index += 1;
+                    continue;
+                }
+            }
+            let mut unmatched_operator = None;
+            if nest_operations {
+                if let Some(opening_operators) = get_opening_operators(&operation.operator) {
+                    match open_groups.last() {
+                        Some((opening_operator, _))
+                            if opening_operators.contains(opening_operator) =>
+                        {
+                            open_groups.pop();
+                        }
+                        _ => unmatched_operator = Some(opening_operators.join("`/`")),
+                    }
+                }
+            }
+            let cursor = open_groups
+                .last()
+                .map_or(cursor, |(_, group_cursor)| group_cursor);
+            print_skipped_operations(display_settings, cursor, &mut skipped_operations)?;
+            let is_nesting_operator = nest_operations
+                && (is_opening_operator(&operation.operator)
+                    || get_opening_operators(&operation.operator).is_some());
+            let run_length = if display_settings.collapse_repeats
+                && expand_index.is_none()
+                && !is_nesting_operator
+            {
+                operations[index..]
+                    .iter()
+                    .take_while(|next| next.operator == operation.operator)
+                    .count()
+            } else {
+                1
+            };
+            if run_length < 3 {
+                print_operation_string(
+                    display_settings,
+                    index,
+                    operation,
+                    note,
+                    resources,
+                    raw_doc,
+                    cursor,
+                )?;
+                if let Some(opening_operators) = unmatched_operator {
+                    log::warn!(
+                        "Content stream contains `{}` without a matching `{}`.",
+                        operation.operator,
+                        opening_operators
+                    );
+                    cursor.print_subitem(
+                        ERROR_STYLE
+                            .paint(format!(
+                                "(`{}` has no matching `{}`)",
+                                operation.operator, opening_operators
+                            ))
+                            .to_string(),
+                        false,
+                    )?;
+                }
+                if nest_operations && is_opening_operator(&operation.operator) {
+                    let group_cursor = cursor.add_depth(DepthInfo {
+                        name: None,
+                        indent_line: true,
+                    });
+                    open_groups.push((operation.operator.as_str(), group_cursor));
+                }
+                index += 1;
+                continue;
+            }
+            // Only print the first and last operation of the run.
+            print_operation_string(
+                display_settings,
+                index,
+                operation,
+                note,
+                resources,
+                raw_doc,
+                cursor,
+            )?;
+            let mut last_note = None;
+            for run_operation in &operations[index + 1..index + run_length] {
+                last_note = get_note(run_operation);
+            }
+            let last_operation = &operations[index + run_length - 1];
+            cursor.print_subitem(
+                SKIPPED_STYLE
+                    .paint(format!(
+                        "...{} ×{} ({} not shown)...",
+                        operation.operator,
+                        run_length,
+                        run_length - 2
+                    ))
+                    .to_string(),
+                false,
+            )?;
+            print_operation_string(
+                display_settings,
+                index + run_length - 1,
+                last_operation,
+                last_note,
+                resources,
+                raw_doc,
+                cursor,
+            )?;
+            index += run_length;
+        }
+        // Report the groups that are still open, innermost first.
+        while let Some((opening_operator, group_cursor)) = open_groups.pop() {
+            log::warn!(
+                "Content stream contains `{}` that is not closed.",
+                opening_operator
+            );
+            group_cursor.print_subitem(
+                ERROR_STYLE
+                    .paint(format!("(`{}` is not closed)", opening_operator))
+                    .to_string(),
+                true,
+            )?;
         }
+        print_skipped_operations(display_settings, cursor, &mut skipped_operations)?;
     } else {
         cursor.print_subitem(
             EXPAND_INFO_STYLE
                 .paint("... (no content stream, force decoding with `force-stream-decoding` flag)")
                 .to_string(),
             false,
-        );
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Check if the stream at `cursor` is decoded as a content stream.
+///
+/// This depends on the key it is stored under, like `Contents`, and the
+/// `decode-stream-keys` and `force-stream-decoding` settings.
+pub fn is_content_stream(display_settings: &TreeDisplaySettings, cursor: &TreeCursorInfo) -> bool {
+    let last_path_label = cursor.get_last_key().unwrap_or_default();
+    CONTENT_STREAM_KEYS.contains(&last_path_label.as_str())
+        || display_settings
+            .decode_stream_keys
+            .contains(&last_path_label)
+        || display_settings.force_stream_decoding
+}
+
+/// Operators that start a group of operations that has to be closed again.
+///
+/// See 8.4.2 (Graphics State Stack), 9.4 (Text Objects)
+/// and 14.6 (Marked Content) in PDF v1.7 Spec.
+fn is_opening_operator(operator: &str) -> bool {
+    matches!(operator, "q" | "BT" | "BMC" | "BDC")
+}
+
+/// The operators that open the group an operator closes, `None` when it does not close a group.
+fn get_opening_operators(operator: &str) -> Option<&'static [&'static str]> {
+    match operator {
+        "Q" => Some(&["q"]),
+        "ET" => Some(&["BT"]),
+        "EMC" => Some(&["BMC", "BDC"]),
+        _ => None,
     }
+}
 
+/// Print how many operations were hidden by `operator_filter` and reset the count.
+fn print_skipped_operations(
+    display_settings: &TreeDisplaySettings,
+    cursor: &TreeCursorInfo,
+    skipped_operations: &mut usize,
+) -> std::io::Result<()> {
+    if *skipped_operations > 0 {
+        cursor.print_subitem(
+            SKIPPED_STYLE
+                .paint(get_skip_marker(
+                    display_settings,
+                    *skipped_operations,
+                    "operations",
+                ))
+                .to_string(),
+            false,
+        )?;
+        *skipped_operations = 0;
+    }
     Ok(())
 }
 
+/// Check if the (decompressed) stream contains more than just whitespace.
+fn has_non_trivial_content(stream: &Stream) -> bool {
+    let content = stream
+        .decompressed_content()
+        .unwrap_or_else(|_| stream.content.clone());
+    content
+        .iter()
+        .any(|byte| !byte.is_ascii_whitespace() && *byte != 0)
+}
+
 /// Convert an operation to the correct printing format.
 ///
 /// Each operation has special meanings, this allows to more informed printing.
 /// If `stream_enhanced_operations` is `true` this formatting will be enhanced.
 /// If `false` the formatter will just print the raw values.
+/// The `note` is added after the operator, like the colors used by painting operations.
 fn print_operation_string(
     display_settings: &TreeDisplaySettings,
+    operation_index: usize,
     operation: &Operation,
+    note: Option<String>,
+    resources: Option<&Dictionary>,
+    raw_doc: &Document,
     cursor: &TreeCursorInfo,
 ) -> Result<(), Error> {
     if display_settings.stream_enhanced_operations {
-        print_enhanced_operation(display_settings, operation, cursor)?;
+        print_enhanced_operation(
+            display_settings,
+            operation_index,
+            operation,
+            note,
+            resources,
+            raw_doc,
+            cursor,
+        )?;
     } else {
-        print_basic_operation(display_settings, operation, cursor)?;
+        print_basic_operation(display_settings, operation_index, operation, note, cursor)?;
     }
     Ok(())
 }
 
 fn print_basic_operation(
     display_settings: &TreeDisplaySettings,
+    operation_index: usize,
     operation: &Operation,
+    note: Option<String>,
     cursor: &TreeCursorInfo,
 ) -> Result<(), Error> {
-    let operands_string = get_operands_string(display_settings, &operation.operands)?;
+    let operands_string = get_operands_string(display_settings, &operation.operands, 0)?;
     cursor.print_subitem(
-        format!("{}({})", operation.operator, operands_string),
+        format!(
+            "{}{}({}){}",
+            get_operation_index_prefix(display_settings, operation_index),
+            operation.operator,
+            operands_string,
+            format_note(note)
+        ),
         false,
-    );
+    )?;
     Ok(())
 }
 
+/// Format operands, `depth` is the amount of arrays and dictionaries these operands are in.
 fn get_operands_string(
     display_settings: &TreeDisplaySettings,
     operands: &[Object],
+    depth: usize,
 ) -> Result<String, Error> {
     let mut results = Vec::new();
 
     for object in operands {
         match &object {
+            Object::Array(_) | Object::Dictionary(_)
+                if depth >= display_settings.max_operand_array_depth =>
+            {
+                let obj_print_info = get_object_print_info(object, display_settings);
+                let placeholder = if let Object::Array(_) = object {
+                    "[...]"
+                } else {
+                    "{...}"
+                };
+                results.push(obj_print_info.symbol_style.paint(placeholder).to_string());
+            }
             Object::Array(list) => {
                 let obj_print_info = get_object_print_info(object, display_settings);
-                let array_string = get_operands_string(display_settings, list)?;
+                let array_string = get_operands_string(display_settings, list, depth + 1)?;
                 results.push(format!(
                     "{}{}{}",
                     obj_print_info.symbol_style.paint("["),
@@ -92,7 +404,11 @@ fn get_operands_string(
                     temp_result.push(format!(
                         "{}:{}",
                         String::from_utf8_lossy(key),
-                        get_operands_string(display_settings, &[value.clone()])?,
+                        get_operands_string(
+                            display_settings,
+                            std::slice::from_ref(value),
+                            depth + 1
+                        )?,
                     ));
                 }
                 results.push(format!(
@@ -134,55 +450,441 @@ fn get_operands_string(
 
 fn print_enhanced_operation(
     display_settings: &TreeDisplaySettings,
+    operation_index: usize,
     operation: &Operation,
+    note: Option<String>,
+    resources: Option<&Dictionary>,
+    raw_doc: &Document,
     cursor: &TreeCursorInfo,
 ) -> Result<(), Error> {
     let operation_info = operation_info(operation, display_settings);
+    let index_prefix = get_operation_index_prefix(display_settings, operation_index);
 
     match operation_info {
         Ok(operation_info) => {
+            let extra_info = match operation_info.operator {
+                "Do" => get_image_xobject_info(operation, resources, raw_doc)
+                    .map(|info| format!(" {}", EXTRA_INFO_STYLE.paint(format!("({})", info)))),
+                "g" | "G" | "rg" | "RG" | "k" | "K" | "sc" | "scn" | "SC" | "SCN"
+                    if display_settings.stream_enhanced_operator_info =>
+                {
+                    get_color_swatch(operation).map(|swatch| format!(" {}", swatch))
+                }
+                _ => None,
+            }
+            .unwrap_or_default()
+                + &format_note(note.clone());
+            // Print the operands as stored, to compare them with the labeled arguments.
+            let raw_operands = if display_settings.show_raw_operands {
+                format!(
+                    " {}({})",
+                    EXTRA_INFO_STYLE.paint("raw:"),
+                    get_operands_string(display_settings, &operation.operands, 0)?
+                )
+            } else {
+                String::new()
+            };
+            let operator = operation_info
+                .category
+                .style()
+                .paint(operation_info.operator);
             if display_settings.stream_enhanced_operator_info {
                 cursor.print_subitem(
                     format!(
-                        "{}: {}",
-                        operation_info.operator,
-                        EXTRA_INFO_STYLE.paint(operation_info.description)
+                        "{}{}: {}{}{}",
+                        index_prefix,
+                        operator,
+                        EXTRA_INFO_STYLE.paint(operation_info.description),
+                        raw_operands,
+                        extra_info
                     ),
                     false,
-                );
+                )?;
             } else {
-                cursor.print_subitem(operation_info.operator.to_string(), false);
+                cursor.print_subitem(
+                    format!("{}{}{}{}", index_prefix, operator, raw_operands, extra_info),
+                    false,
+                )?;
             }
 
+            // Use the index in the path, so an operation can be selected with `expand`.
             let new_cursor = cursor.add_depth(DepthInfo {
-                name: Some(operation_info.operator.to_owned()),
+                name: Some(operation_index.to_string()),
                 indent_line: true,
             });
             match operation_info.values {
                 OperationInfoValue::Arguments(values) => {
+                    // Align the values by padding the keys to the longest key.
+                    let key_width = values
+                        .iter()
+                        .map(|(key, _)| key.width() + 1)
+                        .max()
+                        .unwrap_or_default();
                     for (key, value) in values {
                         let obj_print_info = get_object_print_info(&value, display_settings);
+                        // Show the items of arrays (like the `d` dash array) instead of their length.
+                        let value_string = match value {
+                            Object::Array(_) => get_operands_string(
+                                display_settings,
+                                std::slice::from_ref(&value),
+                                0,
+                            )?,
+                            _ => VALUE_STYLE.paint(obj_print_info.value).to_string(),
+                        };
                         new_cursor.print_subitem(
                             format!(
-                                "{}: {:<2} {}",
+                                "{}:{} {:<2} {}",
                                 key,
+                                " ".repeat(key_width - key.width() - 1),
                                 obj_print_info.symbol_style.paint(obj_print_info.symbol),
-                                VALUE_STYLE.paint(obj_print_info.value),
+                                value_string,
                             ),
                             false,
-                        );
+                        )?;
+                        if display_settings.inline_stream_refs {
+                            let ref_cursor = new_cursor.add_depth(DepthInfo {
+                                name: Some(key.clone()),
+                                indent_line: true,
+                            });
+                            print_inline_reference(
+                                display_settings,
+                                operation_info.operator,
+                                &value,
+                                resources,
+                                raw_doc,
+                                &ref_cursor,
+                            )?;
+                        }
                     }
                 }
                 OperationInfoValue::FormattedString(formatted_string) => {
-                    new_cursor.print_subitem(formatted_string, false);
+                    new_cursor.print_subitem(formatted_string, false)?;
                 }
             }
+            if !operation_info.extra_operands.is_empty() {
+                print_extra_operands(
+                    display_settings,
+                    operation_info.operator,
+                    &operation_info.extra_operands,
+                    &new_cursor,
+                )?;
+            }
+            if display_settings.stream_enhanced_operator_info
+                && matches!(operation_info.operator, "cm" | "Tm")
+            {
+                print_matrix(operation, &new_cursor)?;
+            }
         }
         Err(err) => {
             log::warn!("PDF Error: {}", err);
-            print_basic_operation(display_settings, operation, cursor)?;
+            print_basic_operation(display_settings, operation_index, operation, note, cursor)?;
         }
     }
 
     Ok(())
 }
+
+/// Print the operands an operator does not support, a stream with them is malformed.
+fn print_extra_operands(
+    display_settings: &TreeDisplaySettings,
+    operator: &str,
+    extra_operands: &[Object],
+    cursor: &TreeCursorInfo,
+) -> std::io::Result<()> {
+    cursor.print_subitem(
+        format!(
+            "{} {}",
+            ERROR_STYLE.paint("extra_operands:"),
+            EXTRA_INFO_STYLE.paint(format!(
+                "({} value(s) not supported by `{}`)",
+                extra_operands.len(),
+                operator
+            ))
+        ),
+        false,
+    )?;
+    let extra_cursor = cursor.add_depth(DepthInfo {
+        name: Some("extra_operands".to_owned()),
+        indent_line: true,
+    });
+    for (index, value) in extra_operands.iter().enumerate() {
+        let obj_print_info = get_object_print_info(value, display_settings);
+        extra_cursor.print_subitem(
+            format!(
+                "{}: {:<2} {}",
+                ERROR_STYLE.paint(index),
+                obj_print_info.symbol_style.paint(obj_print_info.symbol),
+                VALUE_STYLE.paint(obj_print_info.value),
+            ),
+            index + 1 == extra_operands.len(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Print the matrix of `cm` and `Tm` as `[a b 0; c d 0; e f 1]`, with the kind of transformation.
+fn print_matrix(operation: &Operation, cursor: &TreeCursorInfo) -> std::io::Result<()> {
+    let values: Vec<f64> = operation
+        .operands
+        .iter()
+        .filter_map(|operand| operand.as_float().ok())
+        .collect();
+    let matrix = match values.as_slice() {
+        [a, b, c, d, e, f] => [*a, *b, *c, *d, *e, *f],
+        _ => return Ok(()),
+    };
+    cursor.print_subitem(
+        format!(
+            "matrix: {}",
+            EXTRA_INFO_STYLE.paint(format!("({})", get_matrix_kind(&matrix)))
+        ),
+        false,
+    )?;
+    let [a, b, c, d, e, f] = matrix.map(format_number);
+    let rows = [
+        [a, b, "0".to_owned()],
+        [c, d, "0".to_owned()],
+        [e, f, "1".to_owned()],
+    ];
+    // Align the columns by padding to the longest number.
+    let column_width = rows
+        .iter()
+        .flatten()
+        .map(|cell| cell.len())
+        .max()
+        .unwrap_or(1);
+    let matrix_cursor = cursor.add_depth(DepthInfo {
+        name: None,
+        indent_line: true,
+    });
+    for (index, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| format!("{:>width$}", cell, width = column_width))
+            .collect();
+        matrix_cursor.print_subitem(
+            format!("[ {} ]", VALUE_STYLE.paint(cells.join("  "))),
+            index == rows.len() - 1,
+        )?;
+    }
+    Ok(())
+}
+
+/// Describe common transformations, like a translation, a scale or a rotation.
+///
+/// See 8.3.3 (Common Transformations) in PDF v1.7 Spec.
+fn get_matrix_kind(matrix: &[f64; 6]) -> String {
+    // Values in content streams are often rounded, like `0.7071` for a rotation.
+    let is_equal = |first: f64, second: f64| (first - second).abs() < 1e-3;
+    let [a, b, c, d, e, f] = *matrix;
+    let translation = if is_equal(e, 0.0) && is_equal(f, 0.0) {
+        None
+    } else {
+        Some(format!(
+            "translation by {}, {}",
+            format_number(e),
+            format_number(f)
+        ))
+    };
+    let linear_part = if is_equal(b, 0.0) && is_equal(c, 0.0) {
+        if is_equal(a, 1.0) && is_equal(d, 1.0) {
+            None
+        } else if is_equal(a, d) {
+            Some(format!("uniform scale by {}", format_number(a)))
+        } else {
+            Some(format!(
+                "scale by {}, {}",
+                format_number(a),
+                format_number(d)
+            ))
+        }
+    } else if is_equal(a, d) && is_equal(b, -c) {
+        let scale = (a * a + b * b).sqrt();
+        let angle = b.atan2(a).to_degrees();
+        if is_equal(scale, 1.0) {
+            Some(format!("rotation by about {}°", format_number(angle)))
+        } else {
+            Some(format!(
+                "rotation by about {}° with uniform scale by {}",
+                format_number(angle),
+                format_number(scale)
+            ))
+        }
+    } else {
+        Some("skew or other transformation".to_owned())
+    };
+    match (linear_part, translation) {
+        (None, None) => "identity".to_owned(),
+        (Some(linear_part), None) => linear_part,
+        (None, Some(translation)) => translation,
+        (Some(linear_part), Some(translation)) => format!("{}, {}", linear_part, translation),
+    }
+}
+
+/// With `operation_indices`, the index of the operation in the stream, like `#340 `.
+fn get_operation_index_prefix(
+    display_settings: &TreeDisplaySettings,
+    operation_index: usize,
+) -> String {
+    if display_settings.operation_indices {
+        format!(
+            "{} ",
+            EXTRA_INFO_STYLE.paint(format!("#{}", operation_index))
+        )
+    } else {
+        String::new()
+    }
+}
+
+fn format_note(note: Option<String>) -> String {
+    note.map(|note| format!(" {}", EXTRA_INFO_STYLE.paint(format!("({})", note))))
+        .unwrap_or_default()
+}
+
+/// Print a one line summary of the object an operand refers to.
+///
+/// Operands can be an indirect reference or a name defined in the `Resources` (like `/Im1 Do`).
+/// References back to one of the parents are not printed unless `display_parent` is set.
+fn print_inline_reference(
+    display_settings: &TreeDisplaySettings,
+    operator: &str,
+    operand: &Object,
+    resources: Option<&Dictionary>,
+    raw_doc: &Document,
+    cursor: &TreeCursorInfo,
+) -> Result<(), Error> {
+    let resource = match (operand, resource_category(operator), resources) {
+        (Object::Reference(_), _, _) => operand,
+        (Object::Name(name), Some(category), Some(resources)) => {
+            match resources
+                .get_deref(category, raw_doc)
+                .and_then(Object::as_dict)
+                .and_then(|category_dict| category_dict.get(name))
+            {
+                Ok(resource) => resource,
+                Err(_) => return Ok(()),
+            }
+        }
+        _ => return Ok(()),
+    };
+
+    let ref_obj = match resource {
+        Object::Reference(object_id) => {
+            if !display_settings.display_parent
+                && !display_settings.no_cycle_guard
+                && cursor.check_parent_visited(object_id)
+            {
+                cursor.print_subitem(
+                    EXPAND_INFO_STYLE
+                        .paint("... (display with `display-parent` flag)")
+                        .to_string(),
+                    true,
+                )?;
+                return Ok(());
+            }
+            match raw_doc.objects.get(object_id) {
+                Some(ref_obj) => ref_obj,
+                None => {
+                    cursor.print_subitem(
+                        ERROR_STYLE
+                            .paint(get_missing_object_message(raw_doc, *object_id))
+                            .to_string(),
+                        true,
+                    )?;
+                    return Ok(());
+                }
+            }
+        }
+        direct_obj => direct_obj,
+    };
+    // Dictionaries and streams print no value, so add their type to make it a useful summary.
+    let type_names = get_type_label(ref_obj)
+        .map(|type_names| format!(" {}", EXTRA_INFO_STYLE.paint(format!("<{}>", type_names))))
+        .unwrap_or_default();
+    cursor.print_subitem(
+        format!(
+            "{}{}",
+            get_pdf_object_info(display_settings, None, ref_obj)?,
+            type_names
+        ),
+        true,
+    )?;
+    Ok(())
+}
+
+/// The sub dictionary of `Resources` in which the name operands of an operator are defined.
+fn resource_category(operator: &str) -> Option<&'static [u8]> {
+    match operator {
+        "Do" => Some(b"XObject"),
+        "Tf" => Some(b"Font"),
+        "gs" => Some(b"ExtGState"),
+        "sh" => Some(b"Shading"),
+        "cs" | "CS" => Some(b"ColorSpace"),
+        "scn" | "SCN" => Some(b"Pattern"),
+        "BDC" | "DP" => Some(b"Properties"),
+        _ => None,
+    }
+}
+
+/// Describe the image XObject invoked by a `Do` operation, like `100x50, 8bpc, DeviceRGB`.
+///
+/// Returns `None` when the XObject can not be resolved or is not an image.
+fn get_image_xobject_info(
+    operation: &Operation,
+    resources: Option<&Dictionary>,
+    raw_doc: &Document,
+) -> Option<String> {
+    let name = operation.operands.first()?.as_name().ok()?;
+    let xobjects = resources?
+        .get_deref(b"XObject", raw_doc)
+        .and_then(Object::as_dict)
+        .ok()?;
+    let image = xobjects
+        .get_deref(name, raw_doc)
+        .and_then(Object::as_stream)
+        .ok()?;
+    if image.dict.get(b"Subtype").and_then(Object::as_name).ok()? != b"Image" {
+        return None;
+    }
+
+    let get_integer = |key: &[u8]| {
+        image
+            .dict
+            .get_deref(key, raw_doc)
+            .and_then(Object::as_i64)
+            .map(|value| value.to_string())
+            .unwrap_or_else(|_| "?".to_owned())
+    };
+    let is_mask = image
+        .dict
+        .get_deref(b"ImageMask", raw_doc)
+        .and_then(Object::as_bool)
+        .unwrap_or(false);
+    let color_space = if is_mask {
+        "ImageMask".to_owned()
+    } else {
+        match image.dict.get_deref(b"ColorSpace", raw_doc) {
+            Ok(Object::Name(name)) => String::from_utf8_lossy(name).to_string(),
+            // For example: `[/ICCBased 5 0 R]` or `[/Indexed /DeviceRGB 255 <...>]`
+            Ok(Object::Array(list)) => list
+                .first()
+                .and_then(|family| family.as_name().ok())
+                .map(|family| String::from_utf8_lossy(family).to_string())
+                .unwrap_or_else(|| "?".to_owned()),
+            _ => "no ColorSpace".to_owned(),
+        }
+    };
+    let bits_per_component = if is_mask {
+        "1".to_owned()
+    } else {
+        get_integer(b"BitsPerComponent")
+    };
+
+    Some(format!(
+        "{}x{}, {}bpc, {}",
+        get_integer(b"Width"),
+        get_integer(b"Height"),
+        bits_per_component,
+        color_space
+    ))
+}
@@ -1,6 +1,6 @@
 use super::{TreeDisplaySettings, SKIPPED_STYLE};
 use crate::StreamDisplay;
-use lopdf::{Object, StringFormat};
+use lopdf::{Dictionary, Object, Stream, StringFormat};
 use yansi::{Color, Style};
 
 #[derive(Debug, Default, Clone)]
@@ -12,9 +12,148 @@ pub struct ObjectPrintInfo {
     pub extra_info: Option<String>,
 }
 
+/// Function that renders the value of an object, replacing the default value.
+///
+/// Returns `None` to keep the default value (for example when the object has an
+/// unexpected type).
+pub type ValueFormatter = fn(&Object) -> Option<String>;
+
+/// Type names whose value can be rendered with a `ValueFormatter`.
+pub static FORMATTABLE_TYPE_NAMES: [&str; 5] = [
+    "Integer_Number",
+    "Real_Number",
+    "Name",
+    "Literal_String",
+    "Hexadecimal_String",
+];
+
+/// Get one of the included formatters by name.
+pub fn get_value_formatter(name: &str) -> Option<ValueFormatter> {
+    match name {
+        "hex" => Some(format_value_hex),
+        "text" => Some(format_value_text),
+        "debug" => Some(format_value_debug),
+        _ => None,
+    }
+}
+
+/// Print the bytes of names and strings, and integers in hexadecimal.
+fn format_value_hex(obj: &Object) -> Option<String> {
+    match obj {
+        Object::Name(bytes) | Object::String(bytes, _) => Some(
+            bytes
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Object::Integer(value) => Some(format!("{:#x}", value)),
+        _ => None,
+    }
+}
+
+/// Decode names and strings as text strings (UTF-16BE with byte order mark or UTF-8).
+fn format_value_text(obj: &Object) -> Option<String> {
+    match obj {
+        Object::Name(bytes) | Object::String(bytes, _) => Some(decode_text_string(bytes)),
+        _ => None,
+    }
+}
+
+/// Print the object as lopdf stores it.
+fn format_value_debug(obj: &Object) -> Option<String> {
+    Some(format!("{:?}", obj))
+}
+
+/// Decode a PDF text string, these are UTF-16BE when they start with a byte order mark.
+pub fn decode_text_string(value: &[u8]) -> String {
+    match value.strip_prefix(&[0xFE, 0xFF]) {
+        Some(utf16_bytes) => {
+            let code_units: Vec<u16> = utf16_bytes
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&code_units)
+        }
+        None => String::from_utf8_lossy(value).to_string(),
+    }
+}
+
 pub fn get_object_print_info(
     obj: &Object,
     display_settings: &TreeDisplaySettings,
+) -> ObjectPrintInfo {
+    let mut print_info = get_default_object_print_info(obj, display_settings);
+    // Redacted strings should stay redacted.
+    let is_redacted = display_settings.redact_strings && matches!(obj, Object::String(..));
+    if !is_redacted {
+        let formatter = display_settings
+            .value_formatters
+            .iter()
+            .find(|(type_name, _)| type_name == print_info.type_name);
+        if let Some(value) = formatter.and_then(|(_, formatter)| formatter(obj)) {
+            print_info.value = value;
+        }
+    }
+    // Hexadecimal strings have their own limit.
+    let is_text_value = matches!(
+        obj,
+        Object::Name(_) | Object::String(_, StringFormat::Literal)
+    );
+    if let Some(max_value_len) = display_settings.max_value_len.filter(|_| is_text_value) {
+        if !is_redacted {
+            print_info.value = truncate_value(&print_info.value, max_value_len);
+        }
+    }
+    print_info
+}
+
+/// Get the `Type` and `Subtype` of a dictionary or stream, like `XObject/Image`.
+pub fn get_type_label(obj: &Object) -> Option<String> {
+    let dict: &Dictionary = match obj {
+        Object::Dictionary(dict) => dict,
+        Object::Stream(stream) => &stream.dict,
+        _ => return None,
+    };
+    let type_names: Vec<_> = [&b"Type"[..], b"Subtype"]
+        .iter()
+        .filter_map(|key| dict.get(key).and_then(Object::as_name).ok())
+        .map(|name| String::from_utf8_lossy(name).to_string())
+        .collect();
+    if type_names.is_empty() {
+        None
+    } else {
+        Some(type_names.join("/"))
+    }
+}
+
+/// Cut off a value after `max_len` characters, like `'Long tit…(12 more)'`.
+///
+/// The quotes around a value are kept and not counted.
+fn truncate_value(value: &str, max_len: usize) -> String {
+    let (text, quote) = match value
+        .strip_prefix('\'')
+        .and_then(|text| text.strip_suffix('\''))
+    {
+        Some(text) => (text, "'"),
+        None => (value, ""),
+    };
+    let char_count = text.chars().count();
+    if char_count <= max_len {
+        return value.to_owned();
+    }
+    format!(
+        "{}{}…({} more){}",
+        quote,
+        text.chars().take(max_len).collect::<String>(),
+        char_count - max_len,
+        quote
+    )
+}
+
+fn get_default_object_print_info(
+    obj: &Object,
+    display_settings: &TreeDisplaySettings,
 ) -> ObjectPrintInfo {
     match obj {
         Object::Null => ObjectPrintInfo {
@@ -60,11 +199,23 @@ pub fn get_object_print_info(
                 symbol_style: Style::new(Color::Yellow).bold(),
                 symbol: "az",
                 type_name: "Literal_String",
-                value: format!("'{}'", String::from_utf8_lossy(string_value)),
-                ..Default::default()
+                value: if display_settings.redact_strings {
+                    get_redacted_string(string_value)
+                } else if display_settings.raw_string_bytes {
+                    format!("'{}'", get_escaped_bytes(string_value))
+                } else {
+                    format!("'{}'", decode_text_string(string_value))
+                },
+                extra_info: get_utf16_info(
+                    display_settings,
+                    string_value,
+                    !display_settings.raw_string_bytes,
+                ),
             },
             StringFormat::Hexadecimal => {
-                let short_data = if let Some(display_limit) = display_settings.hex_display_limit {
+                let short_data = if display_settings.redact_strings {
+                    get_redacted_string(string_value)
+                } else if let Some(display_limit) = display_settings.hex_display_limit {
                     if string_value.len() < display_limit {
                         // Shorter, so print all
                         format!("{:02x?}", string_value)
@@ -84,8 +235,11 @@ pub fn get_object_print_info(
                                 let skipped_items = list_count - display_limit.max(2);
                                 temp_string.push_str(&format!(
                                     "{}, ",
-                                    SKIPPED_STYLE
-                                        .paint(format!("...skipped {} bytes...", skipped_items)),
+                                    SKIPPED_STYLE.paint(get_skip_marker(
+                                        display_settings,
+                                        skipped_items,
+                                        "bytes"
+                                    )),
                                 ));
                                 continue;
                             } else {
@@ -104,10 +258,23 @@ pub fn get_object_print_info(
                     symbol: "0x",
                     type_name: "Hexadecimal_String",
                     value: short_data,
-                    ..Default::default()
+                    extra_info: get_utf16_info(display_settings, string_value, false),
                 }
             }
         },
+        Object::Array(array_value) if is_inline_number_array(display_settings, array_value) => {
+            let numbers: Vec<String> = array_value
+                .iter()
+                .map(|item| get_object_print_info(item, display_settings).value)
+                .collect();
+            ObjectPrintInfo {
+                symbol_style: Style::new(Color::Blue).bold(),
+                symbol: "[]",
+                type_name: "Array",
+                value: format!("[{}]", numbers.join(" ")),
+                ..Default::default()
+            }
+        }
         Object::Array(array_value) => ObjectPrintInfo {
             symbol_style: Style::new(Color::Blue).bold(),
             symbol: "[]",
@@ -134,7 +301,7 @@ pub fn get_object_print_info(
                     "".to_owned()
                 }
             },
-            extra_info: Some(format!("(length: {} bytes)", stream_value.content.len())),
+            extra_info: Some(get_stream_extra_info(stream_value)),
         },
         Object::Reference(object_id) => ObjectPrintInfo {
             symbol_style: Style::new(Color::White).dimmed().bold(),
@@ -145,3 +312,241 @@ pub fn get_object_print_info(
         },
     }
 }
+
+/// The length of a stream, object streams and cross-reference streams are marked.
+///
+/// Objects stored in object streams are not top-level objects in the file,
+/// see 7.5.7 (Object Streams) and 7.5.8 (Cross-Reference Streams) in PDF v1.7 Spec.
+fn get_stream_extra_info(stream: &Stream) -> String {
+    let length = stream.content.len();
+    let get_number = |key: &[u8]| match stream.dict.get(key).and_then(Object::as_i64) {
+        Ok(number) => number.to_string(),
+        Err(_) => "?".to_owned(),
+    };
+    if stream.dict.type_is(b"ObjStm") {
+        format!(
+            "(length: {} bytes, object stream with {} objects, first object at offset {})",
+            length,
+            get_number(b"N"),
+            get_number(b"First")
+        )
+    } else if stream.dict.type_is(b"XRef") {
+        format!(
+            "(length: {} bytes, cross-reference stream for {} objects)",
+            length,
+            get_number(b"Size")
+        )
+    } else {
+        format!("(length: {} bytes)", length)
+    }
+}
+
+/// Check if an array only contains numbers and is short enough to be printed on one line,
+/// like bounding boxes and matrices.
+pub fn is_inline_number_array(display_settings: &TreeDisplaySettings, list: &[Object]) -> bool {
+    display_settings
+        .inline_number_array_limit
+        .is_some_and(|limit| {
+            !list.is_empty()
+                && list.len() <= limit
+                && list
+                    .iter()
+                    .all(|item| matches!(item, Object::Integer(_) | Object::Real(_)))
+        })
+}
+
+/// Keys of rectangles that describe the page boundaries.
+///
+/// See p85 (Section 14.11.2) in PDF v1.7 Spec.
+pub static PAGE_BOX_KEYS: [&str; 5] = ["MediaBox", "CropBox", "BleedBox", "TrimBox", "ArtBox"];
+
+/// Common paper sizes in points (width, height), in portrait orientation.
+static PAPER_SIZES: [(&str, f64, f64); 9] = [
+    ("A3", 842.0, 1191.0),
+    ("A4", 595.0, 842.0),
+    ("A5", 420.0, 595.0),
+    ("A6", 298.0, 420.0),
+    ("B5", 499.0, 709.0),
+    ("Letter", 612.0, 792.0),
+    ("Legal", 612.0, 1008.0),
+    ("Tabloid", 792.0, 1224.0),
+    ("Executive", 522.0, 756.0),
+];
+
+/// Describe the size of a page box rectangle, like `595x842 pt, 210x297 mm = A4`.
+///
+/// Returns `None` if the object is not an array of 4 numbers.
+pub fn get_box_size_info(obj: &Object) -> Option<String> {
+    let rect = match obj {
+        Object::Array(list) if list.len() == 4 => list
+            .iter()
+            .map(|item| item.as_float().ok())
+            .collect::<Option<Vec<_>>>()?,
+        _ => return None,
+    };
+    let width = (rect[2] - rect[0]).abs();
+    let height = (rect[3] - rect[1]).abs();
+    let points_to_mm = 25.4 / 72.0;
+    let paper_size = PAPER_SIZES
+        .iter()
+        .find_map(|(name, paper_width, paper_height)| {
+            let matches = |w: f64, h: f64| (width - w).abs() <= 1.0 && (height - h).abs() <= 1.0;
+            if matches(*paper_width, *paper_height) {
+                Some(format!(" = {}", name))
+            } else if matches(*paper_height, *paper_width) {
+                Some(format!(" = {} landscape", name))
+            } else {
+                None
+            }
+        })
+        .unwrap_or_default();
+    Some(format!(
+        "{}x{} pt, {:.0}x{:.0} mm{}",
+        width,
+        height,
+        width * points_to_mm,
+        height * points_to_mm,
+        paper_size
+    ))
+}
+
+/// Placeholder for a string value when `redact_strings` is set.
+/// Show the bytes of a string as stored, bytes that are not printable ASCII are escaped.
+fn get_escaped_bytes(string_value: &[u8]) -> String {
+    let mut escaped = String::new();
+    for byte in string_value {
+        match byte {
+            b'\\' => escaped.push_str("\\\\"),
+            b' '..=b'~' => escaped.push(*byte as char),
+            _ => escaped.push_str(&format!("\\x{:02X}", byte)),
+        }
+    }
+    escaped
+}
+
+/// Mark strings that start with the UTF-16BE byte order mark, with the decoded text
+/// when `value_is_decoded` is `false`.
+fn get_utf16_info(
+    display_settings: &TreeDisplaySettings,
+    string_value: &[u8],
+    value_is_decoded: bool,
+) -> Option<String> {
+    if display_settings.redact_strings || !string_value.starts_with(&[0xFE, 0xFF]) {
+        None
+    } else if value_is_decoded {
+        Some("(UTF-16BE)".to_owned())
+    } else {
+        Some(format!(
+            "(UTF-16BE: '{}')",
+            decode_text_string(string_value)
+        ))
+    }
+}
+
+pub fn get_redacted_string(string_value: &[u8]) -> String {
+    format!("<redacted, {} bytes>", string_value.len())
+}
+
+/// Text for the marker that replaces items that are not printed, like `...skipped 3 items...`.
+///
+/// With `short_skip_marker` this is shortened to `…+3`.
+pub fn get_skip_marker(display_settings: &TreeDisplaySettings, count: usize, unit: &str) -> String {
+    if display_settings.short_skip_marker {
+        format!("…+{}", count)
+    } else {
+        format!("...skipped {} {}...", count, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_values(objects: &[Object], display_settings: &TreeDisplaySettings) -> Vec<String> {
+        objects
+            .iter()
+            .map(|obj| get_object_print_info(obj, display_settings).value)
+            .collect()
+    }
+
+    fn with_formatter(type_name: &str, formatter_name: &str) -> TreeDisplaySettings {
+        TreeDisplaySettings {
+            value_formatters: vec![(
+                type_name.to_owned(),
+                get_value_formatter(formatter_name).unwrap(),
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_values_without_formatters() {
+        let objects = [
+            Object::Integer(255),
+            Object::Real(1.5),
+            Object::Name(b"Helvetica".to_vec()),
+            Object::string_literal("Title"),
+        ];
+        assert_eq!(
+            get_values(&objects, &TreeDisplaySettings::default()),
+            vec!["255", "1.5", "'Helvetica'", "'Title'"]
+        );
+    }
+
+    #[test]
+    fn formatter_only_replaces_its_type() {
+        let objects = [
+            Object::Integer(255),
+            Object::Name(b"F1".to_vec()),
+            Object::string_literal("F1"),
+        ];
+        assert_eq!(
+            get_values(&objects, &with_formatter("Name", "hex")),
+            vec!["255", "46 31", "'F1'"]
+        );
+        assert_eq!(
+            get_values(&objects, &with_formatter("Integer_Number", "hex")),
+            vec!["0xff", "'F1'", "'F1'"]
+        );
+    }
+
+    #[test]
+    fn text_formatter_decodes_utf16() {
+        let utf16 = Object::String(
+            vec![0xFE, 0xFF, 0x00, 0x48, 0x00, 0x69],
+            StringFormat::Literal,
+        );
+        assert_eq!(
+            get_object_print_info(&utf16, &with_formatter("Literal_String", "text")).value,
+            "Hi"
+        );
+    }
+
+    #[test]
+    fn formatter_keeps_default_for_other_objects() {
+        // The hex formatter has no value for real numbers.
+        let real = Object::Real(2.0);
+        assert_eq!(
+            get_object_print_info(&real, &with_formatter("Real_Number", "hex")).value,
+            "2"
+        );
+    }
+
+    #[test]
+    fn redacted_strings_are_not_formatted() {
+        let display_settings = TreeDisplaySettings {
+            redact_strings: true,
+            ..with_formatter("Literal_String", "debug")
+        };
+        assert_eq!(
+            get_object_print_info(&Object::string_literal("secret"), &display_settings).value,
+            "<redacted, 6 bytes>"
+        );
+    }
+
+    #[test]
+    fn unknown_formatter_name() {
+        assert!(get_value_formatter("hex").is_some());
+        assert!(get_value_formatter("base64").is_none());
+    }
+}
@@ -0,0 +1,379 @@
+use super::cursor_info::TreeCursorInfo;
+use super::{
+    decode_text_string, TreeCursorSettings, EXPAND_INFO_STYLE, EXTRA_INFO_STYLE, VALUE_STYLE,
+};
+use lopdf::{Document, Error, Object, ObjectId};
+use regex::{Regex, RegexBuilder};
+use std::collections::{BTreeSet, VecDeque};
+use std::io::Write;
+use yansi::Paint;
+
+/// What to search for and where.
+#[derive(Debug, Clone, Default)]
+pub struct SearchSettings {
+    pub query: String,
+    /// Use `query` as a regular expression instead of plain text.
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Match dictionary keys.
+    pub by_key: bool,
+    /// Match name and string values.
+    pub by_value: bool,
+    /// Also match the decoded content of streams.
+    pub search_content: bool,
+    /// Only print the number of matches.
+    pub count_only: bool,
+}
+
+enum Matcher {
+    Text { query: String, case_sensitive: bool },
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(settings: &SearchSettings) -> Result<Self, regex::Error> {
+        if settings.regex {
+            RegexBuilder::new(&settings.query)
+                .case_insensitive(!settings.case_sensitive)
+                .build()
+                .map(Matcher::Regex)
+        } else if settings.case_sensitive {
+            Ok(Matcher::Text {
+                query: settings.query.clone(),
+                case_sensitive: true,
+            })
+        } else {
+            Ok(Matcher::Text {
+                query: settings.query.to_lowercase(),
+                case_sensitive: false,
+            })
+        }
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Matcher::Text {
+                query,
+                case_sensitive: true,
+            } => text.contains(query.as_str()),
+            Matcher::Text { query, .. } => text.to_lowercase().contains(query.as_str()),
+            Matcher::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// A dictionary key, value or stream content that matches the query.
+#[derive(Debug, Clone, PartialEq)]
+struct SearchMatch {
+    /// Dot separated path like `expand` uses, array items use their index.
+    path: String,
+    /// The object the match is in, `None` for the trailer.
+    object_id: Option<ObjectId>,
+    /// What matched, like ``key `Type` ``.
+    description: String,
+    /// The object can be reached from the trailer, otherwise `path` is relative to the object.
+    reachable: bool,
+}
+
+/// Walks over the objects, starting at the trailer, and collects every match.
+struct Search<'a> {
+    settings: &'a SearchSettings,
+    matcher: Matcher,
+    visited: BTreeSet<ObjectId>,
+    /// Objects to search next, with the path to reach them.
+    queue: VecDeque<(ObjectId, String)>,
+    /// The objects being searched can be reached from the trailer.
+    reachable: bool,
+    match_count: usize,
+    /// Not collected with `count_only`.
+    matches: Vec<SearchMatch>,
+}
+
+/// Print the path and object id of every dictionary key or value that matches the query.
+///
+/// Objects are searched breadth first from the trailer, so each object is reported with the
+/// shortest path to it. The path can be used with `expand` in the `tree` command.
+/// `Parent` references are not followed, like in the tree.
+pub fn print_search_results(
+    out: &mut dyn Write,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+    file_name: String,
+    settings: &SearchSettings,
+) -> Result<(), Error> {
+    let search = match search_document(raw_doc, settings) {
+        Ok(search) => search,
+        Err(err) => {
+            log::error!("Invalid regular expression `{}`: {}", settings.query, err);
+            return Err(Error::IO(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                err,
+            )));
+        }
+    };
+    if !settings.count_only {
+        writeln!(
+            out,
+            "{} {}",
+            Paint::default(file_name).bold(),
+            EXTRA_INFO_STYLE.paint(format!("(search for `{}`)", settings.query))
+        )?;
+    }
+
+    let match_count = search.match_count;
+    if settings.count_only {
+        writeln!(
+            out,
+            "{} {}",
+            VALUE_STYLE.paint(match_count),
+            if match_count == 1 {
+                "matching node"
+            } else {
+                "matching nodes"
+            }
+        )?;
+        return Ok(());
+    }
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, out);
+    if search.matches.is_empty() {
+        cursor.print_subitem(
+            EXPAND_INFO_STYLE
+                .paint("... (no matches found)")
+                .to_string(),
+            true,
+        )?;
+    }
+    for (index, search_match) in search.matches.iter().enumerate() {
+        cursor.print_subitem(format_match(search_match), index + 1 == match_count)?;
+    }
+    Ok(())
+}
+
+/// Find every match, objects are searched breadth first from the trailer.
+fn search_document<'a>(
+    raw_doc: &Document,
+    settings: &'a SearchSettings,
+) -> Result<Search<'a>, regex::Error> {
+    let mut search = Search {
+        settings,
+        matcher: Matcher::new(settings)?,
+        visited: BTreeSet::new(),
+        queue: VecDeque::new(),
+        reachable: true,
+        match_count: 0,
+        matches: Vec::new(),
+    };
+
+    search.search_object(&Object::Dictionary(raw_doc.trailer.clone()), "", None);
+    while let Some((object_id, path)) = search.queue.pop_front() {
+        if let Some(obj) = raw_doc.objects.get(&object_id) {
+            search.search_object(obj, &path, Some(object_id));
+        }
+    }
+    // Objects that are not used by the document can still contain what is searched for.
+    search.reachable = false;
+    for (object_id, obj) in &raw_doc.objects {
+        if !search.visited.contains(object_id) {
+            search.visited.insert(*object_id);
+            search.search_object(obj, "", Some(*object_id));
+        }
+    }
+    Ok(search)
+}
+
+fn format_match(search_match: &SearchMatch) -> String {
+    let location = match search_match.object_id {
+        Some(object_id) => format!("({},{})", object_id.0, object_id.1),
+        None => "trailer".to_owned(),
+    };
+    let mut text = format!(
+        "{} {} {}",
+        if search_match.path.is_empty() {
+            "."
+        } else {
+            &search_match.path
+        },
+        VALUE_STYLE.paint(location),
+        EXTRA_INFO_STYLE.paint(&search_match.description)
+    );
+    if !search_match.reachable {
+        // The path is relative to the object, it can not be used with `expand`.
+        text.push_str(
+            &EXPAND_INFO_STYLE
+                .paint(" (object is not used by the document)")
+                .to_string(),
+        );
+    }
+    text
+}
+
+impl<'a> Search<'a> {
+    fn search_object(&mut self, obj: &Object, path: &str, object_id: Option<ObjectId>) {
+        match obj {
+            // Unused objects are searched one by one, without following their references.
+            Object::Reference(reference_id)
+                if self.reachable && self.visited.insert(*reference_id) =>
+            {
+                self.queue.push_back((*reference_id, path.to_owned()));
+            }
+            Object::Name(name) => {
+                self.check_value(&String::from_utf8_lossy(name), path, object_id, "/")
+            }
+            Object::String(value, _) => {
+                self.check_value(&decode_text_string(value), path, object_id, "")
+            }
+            Object::Array(list) => {
+                for (index, item) in list.iter().enumerate() {
+                    self.search_object(item, &join_path(path, &index.to_string()), object_id);
+                }
+            }
+            Object::Dictionary(dict) => self.search_dictionary(dict, path, object_id),
+            Object::Stream(stream) => {
+                self.search_dictionary(&stream.dict, path, object_id);
+                if self.settings.search_content {
+                    self.search_stream_content(stream, path, object_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn search_dictionary(
+        &mut self,
+        dict: &lopdf::Dictionary,
+        path: &str,
+        object_id: Option<ObjectId>,
+    ) {
+        for (key, value) in dict.iter() {
+            let key = String::from_utf8_lossy(key);
+            let key_path = join_path(path, &key);
+            if self.settings.by_key && self.matcher.is_match(&key) {
+                self.add_match(&key_path, object_id, || format!("key `{}`", key));
+            }
+            // `Parent` points back up the tree.
+            if key == "Parent" && matches!(value, Object::Reference(_)) {
+                continue;
+            }
+            self.search_object(value, &key_path, object_id);
+        }
+    }
+
+    fn search_stream_content(
+        &mut self,
+        stream: &lopdf::Stream,
+        path: &str,
+        object_id: Option<ObjectId>,
+    ) {
+        // lopdf returns an error for streams without filters.
+        let content = if stream.dict.has(b"Filter") {
+            match stream.decompressed_content() {
+                Ok(content) => content,
+                Err(_) => return,
+            }
+        } else {
+            stream.content.clone()
+        };
+        let content = String::from_utf8_lossy(&content);
+        if self.matcher.is_match(&content) {
+            self.add_match(path, object_id, || "stream content".to_owned());
+        }
+    }
+
+    fn check_value(&mut self, value: &str, path: &str, object_id: Option<ObjectId>, prefix: &str) {
+        if self.settings.by_value && self.matcher.is_match(value) {
+            self.add_match(path, object_id, || format!("value `{}{}`", prefix, value));
+        }
+    }
+
+    /// Count the match, the description is only created when the matches are printed.
+    fn add_match(
+        &mut self,
+        path: &str,
+        object_id: Option<ObjectId>,
+        get_description: impl FnOnce() -> String,
+    ) {
+        self.match_count += 1;
+        if self.settings.count_only {
+            return;
+        }
+        self.matches.push(SearchMatch {
+            path: path.to_owned(),
+            object_id,
+            description: get_description(),
+            reachable: self.reachable,
+        });
+    }
+}
+
+fn join_path(path: &str, item: &str) -> String {
+    if path.is_empty() {
+        item.to_owned()
+    } else {
+        format!("{}.{}", path, item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::print_tree::{print_pdf_tree, TreeDisplaySettings};
+    use crate::sample_doc::create_sample_document;
+
+    #[test]
+    fn count_only_counts_without_collecting() {
+        let raw_doc = create_sample_document().unwrap();
+        let settings = SearchSettings {
+            query: "Type".to_owned(),
+            by_key: true,
+            ..Default::default()
+        };
+        let listed_count = search_document(&raw_doc, &settings).unwrap().matches.len();
+        let count_settings = SearchSettings {
+            count_only: true,
+            ..settings
+        };
+        let search = search_document(&raw_doc, &count_settings).unwrap();
+        assert!(listed_count > 0);
+        assert_eq!(search.match_count, listed_count);
+        assert!(search.matches.is_empty());
+    }
+
+    #[test]
+    fn search_path_can_be_used_with_expand() {
+        Paint::disable();
+        let raw_doc = create_sample_document().unwrap();
+        let settings = SearchSettings {
+            query: "^Page$".to_owned(),
+            regex: true,
+            case_sensitive: true,
+            by_value: true,
+            ..Default::default()
+        };
+        let matches = search_document(&raw_doc, &settings).unwrap().matches;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].path, "Root.Pages.Kids.0.Type");
+
+        let display_settings = TreeDisplaySettings {
+            expand: Some(matches[0].path.split('.').map(str::to_owned).collect()),
+            display_legend: false,
+            ..Default::default()
+        };
+        let cursor_settings = TreeCursorSettings {
+            print_line_numbers: false,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        print_pdf_tree(
+            &mut output,
+            &display_settings,
+            &cursor_settings,
+            &raw_doc,
+            "sample.pdf".to_owned(),
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Type = 'Page'"), "{}", output);
+        // Only the expanded path is printed.
+        assert!(!output.contains("Contents"), "{}", output);
+    }
+}
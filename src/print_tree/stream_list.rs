@@ -0,0 +1,226 @@
+use super::cursor_info::{DepthInfo, TreeCursorInfo};
+use super::legend::print_legend;
+use super::pdf_content_stream::print_content_stream;
+use super::{
+    get_pdf_object_info, TreeCursorSettings, TreeDisplaySettings, EXPAND_INFO_STYLE,
+    EXTRA_INFO_STYLE,
+};
+use lopdf::{Document, Error, Object, ObjectId, Stream};
+use std::collections::{BTreeSet, VecDeque};
+use std::io::Write;
+use yansi::Paint;
+
+/// Print every stream in the document with the path where it was found.
+///
+/// Streams that can not be reached from the trailer are listed at the end.
+/// Content streams are only decoded when `force_stream_decoding` is set.
+pub fn print_stream_list(
+    out: &mut dyn Write,
+    display_settings: &TreeDisplaySettings,
+    tree_cursor_settings: &TreeCursorSettings,
+    raw_doc: &Document,
+    file_name: String,
+) -> Result<(), Error> {
+    if display_settings.display_legend {
+        print_legend(out, display_settings, &tree_cursor_settings.tree_chars)?;
+    }
+
+    let mut visited = BTreeSet::new();
+    let mut streams = collect_streams(raw_doc, &mut visited);
+    for (object_id, obj) in &raw_doc.objects {
+        if !visited.contains(object_id) && matches!(obj, Object::Stream(_)) {
+            streams.push((
+                vec![format!("({},{}) unreachable", object_id.0, object_id.1)],
+                *object_id,
+            ));
+        }
+    }
+
+    writeln!(out, "{}", Paint::default(file_name).bold())?;
+    let cursor = TreeCursorInfo::new(tree_cursor_settings, out);
+    if streams.is_empty() {
+        cursor.print_subitem(
+            EXPAND_INFO_STYLE
+                .paint("... (no streams found)")
+                .to_string(),
+            true,
+        )?;
+    }
+    let stream_count = streams.len();
+    for (index, (path, object_id)) in streams.iter().enumerate() {
+        let obj = match raw_doc.objects.get(object_id) {
+            Some(obj) => obj,
+            None => continue,
+        };
+        let stream = match obj {
+            Object::Stream(stream) => stream,
+            _ => continue,
+        };
+        let is_last = index + 1 == stream_count;
+        let stream_kind = get_stream_kind(get_last_key(path), stream);
+        cursor.print_subitem(
+            format!(
+                "{} {}",
+                get_pdf_object_info(display_settings, Some(path.join(".")), obj)?,
+                EXTRA_INFO_STYLE.paint(format!(
+                    "({},{}) {}",
+                    object_id.0,
+                    object_id.1,
+                    stream_kind.description()
+                ))
+            ),
+            is_last,
+        )?;
+        if display_settings.force_stream_decoding && stream_kind.has_operations() {
+            let new_cursor = cursor.add_depth(DepthInfo {
+                name: get_last_key(path).map(|key| key.to_owned()),
+                indent_line: !is_last,
+            });
+            print_content_stream(display_settings, stream, raw_doc, &new_cursor)?;
+        }
+    }
+    Ok(())
+}
+
+/// Walk the document breadth first and return all streams found with their path.
+///
+/// Each indirect object is only visited once, so every stream is listed once
+/// with the shortest path to it.
+fn collect_streams<'a>(
+    raw_doc: &'a Document,
+    visited: &mut BTreeSet<ObjectId>,
+) -> Vec<(Vec<String>, ObjectId)> {
+    let mut streams = Vec::new();
+    let mut queue: VecDeque<(Vec<String>, &'a Object)> = raw_doc
+        .trailer
+        .iter()
+        .map(|(key, value)| (vec![String::from_utf8_lossy(key).to_string()], value))
+        .collect();
+    while let Some((path, obj)) = queue.pop_front() {
+        match obj {
+            Object::Reference(object_id) => {
+                if !visited.insert(*object_id) {
+                    continue;
+                }
+                match raw_doc.objects.get(object_id) {
+                    Some(Object::Stream(stream)) => {
+                        for (key, value) in stream.dict.iter() {
+                            let key = String::from_utf8_lossy(key).to_string();
+                            queue.push_back((add_to_path(&path, key), value));
+                        }
+                        streams.push((path, *object_id));
+                    }
+                    Some(ref_obj) => queue.push_back((path, ref_obj)),
+                    None => {}
+                }
+            }
+            Object::Array(list) => {
+                for (index, item) in list.iter().enumerate() {
+                    queue.push_back((add_to_path(&path, index.to_string()), item));
+                }
+            }
+            Object::Dictionary(dict) => {
+                for (key, value) in dict.iter() {
+                    let key = String::from_utf8_lossy(key).to_string();
+                    queue.push_back((add_to_path(&path, key), value));
+                }
+            }
+            _ => {}
+        }
+    }
+    streams
+}
+
+fn add_to_path(path: &[String], item: String) -> Vec<String> {
+    let mut new_path = path.to_vec();
+    new_path.push(item);
+    new_path
+}
+
+/// Get the last dictionary key in the path, skipping array indexes.
+fn get_last_key(path: &[String]) -> Option<&str> {
+    path.iter()
+        .rev()
+        .find(|item| item.parse::<usize>().is_err())
+        .map(|item| item.as_str())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamKind {
+    PageContent,
+    FormXObject,
+    AppearanceStream,
+    Pattern,
+    Image,
+    XmlMetadata,
+    ObjectStream,
+    CrossReferenceStream,
+    EmbeddedFile,
+    FontProgram,
+    ToUnicode,
+    JavaScript,
+    Function,
+    Shading,
+    Unknown,
+}
+
+impl StreamKind {
+    /// Streams that contain content stream operations.
+    fn has_operations(&self) -> bool {
+        matches!(
+            self,
+            Self::PageContent | Self::FormXObject | Self::AppearanceStream | Self::Pattern
+        )
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Self::PageContent => "[page content]",
+            Self::FormXObject => "[form XObject]",
+            Self::AppearanceStream => "[appearance stream]",
+            Self::Pattern => "[pattern]",
+            Self::Image => "[image]",
+            Self::XmlMetadata => "[XML metadata]",
+            Self::ObjectStream => "[object stream]",
+            Self::CrossReferenceStream => "[cross-reference stream]",
+            Self::EmbeddedFile => "[embedded file]",
+            Self::FontProgram => "[font program]",
+            Self::ToUnicode => "[ToUnicode CMap]",
+            Self::JavaScript => "[JavaScript]",
+            Self::Function => "[function]",
+            Self::Shading => "[shading]",
+            Self::Unknown => "[unknown]",
+        }
+    }
+}
+
+/// Guess what the stream is used for, based on its dictionary and the key it was found under.
+fn get_stream_kind(key: Option<&str>, stream: &Stream) -> StreamKind {
+    let get_name = |name_key: &[u8]| -> Option<&[u8]> {
+        match stream.dict.get(name_key) {
+            Ok(Object::Name(name)) => Some(name.as_slice()),
+            _ => None,
+        }
+    };
+    match (get_name(b"Type"), get_name(b"Subtype")) {
+        (_, Some(b"Image")) => return StreamKind::Image,
+        (_, Some(b"Form")) => return StreamKind::FormXObject,
+        (_, Some(b"XML")) => return StreamKind::XmlMetadata,
+        (Some(b"ObjStm"), _) => return StreamKind::ObjectStream,
+        (Some(b"XRef"), _) => return StreamKind::CrossReferenceStream,
+        (Some(b"EmbeddedFile"), _) => return StreamKind::EmbeddedFile,
+        (Some(b"Pattern"), _) => return StreamKind::Pattern,
+        _ => {}
+    }
+    match key {
+        Some("Contents") => StreamKind::PageContent,
+        Some("FontFile") | Some("FontFile2") | Some("FontFile3") => StreamKind::FontProgram,
+        Some("ToUnicode") => StreamKind::ToUnicode,
+        Some("N") | Some("R") | Some("D") => StreamKind::AppearanceStream,
+        Some("Metadata") => StreamKind::XmlMetadata,
+        Some("JS") => StreamKind::JavaScript,
+        _ if stream.dict.has(b"FunctionType") => StreamKind::Function,
+        _ if stream.dict.has(b"ShadingType") => StreamKind::Shading,
+        _ => StreamKind::Unknown,
+    }
+}
@@ -1,19 +1,65 @@
-use super::{get_object_print_info, TreeDisplaySettings, SKIPPED_STYLE, VALUE_STYLE};
+use super::inline_images::get_inline_image_arguments;
+use super::{
+    get_object_print_info, get_redacted_string, TreeDisplaySettings, SKIPPED_STYLE, VALUE_STYLE,
+};
 use indexmap::{indexmap, IndexMap};
 use lopdf::content::Operation;
 use lopdf::{Error, Object, StringFormat};
+use yansi::{Color, Style};
 
 pub struct OperationInfo {
     pub operator: &'static str,
+    pub category: OperatorCategory,
+    /// Operands after the ones the operator supports, these are not part of `values`.
+    pub extra_operands: Vec<Object>,
     pub description: &'static str,
     pub values: OperationInfoValue,
 }
 
+/// Categories of operators, see p111 (Table 51, Operator Categories) in PDF v1.7 Spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatorCategory {
+    GraphicsState,
+    PathConstruction,
+    PathPainting,
+    Clipping,
+    Text,
+    Type3Font,
+    Color,
+    Shading,
+    XObject,
+    InlineImage,
+    MarkedContent,
+    Compatibility,
+}
+
+impl OperatorCategory {
+    /// Style of the operator in the tree, only the most common categories have a color.
+    pub fn style(&self) -> Style {
+        match self {
+            OperatorCategory::PathConstruction => Style::new(Color::Yellow),
+            OperatorCategory::PathPainting => Style::new(Color::Green),
+            OperatorCategory::Text => Style::new(Color::Magenta),
+            OperatorCategory::GraphicsState => Style::new(Color::Blue),
+            _ => Style::default(),
+        }
+    }
+}
+
 pub enum OperationInfoValue {
     Arguments(IndexMap<String, Object>),
     FormattedString(String),
 }
 
+/// All operators `operation_info` knows, in the same order.
+pub static KNOWN_OPERATORS: [&str; 73] = [
+    "b", "B", "b*", "B*", "BDC", "BI", "BMC", "BT", "BX", "c", "cm", "CS", "cs", "d", "d0", "d1",
+    "Do", "DP", "EI", "EMC", "ET", "EX", "f", "F", "f*", "G", "g", "gs", "h", "i", "ID", "j", "J",
+    "K", "k", "l", "m", "M", "MP", "n", "q", "Q", "re", "RG", "rg", "ri", "s", "S", "SC", "sc",
+    "SCN", "scn", "sh", "T*", "Tc", "Td", "TD", "Tf", "Tj", "TJ", "TL", "Tm", "Tr", "Ts", "Tw",
+    "Tz", "v", "w", "W", "W*", "y", "'", "\"",
+];
+
 pub fn operation_info(
     operation: &Operation,
     display_settings: &TreeDisplaySettings,
@@ -24,41 +70,51 @@ pub fn operation_info(
     // For a list of all operations: see p643 (Table A.1) in PDF v1.7 Spec
     let operation_info = match operator.as_str() {
         "b" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "b",
+                category: OperatorCategory::PathPainting,
+                extra_operands,
                 description: "Close, fill, and stroke path using nonzero winding number rule.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "B" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "B",
+                category: OperatorCategory::PathPainting,
+                extra_operands,
                 description: "Fill and stroke path using nonzero winding number rule.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "b*" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "b*",
+                category: OperatorCategory::PathPainting,
+                extra_operands,
                 description: "Close, fill, and stroke path using even-odd rule.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "B*" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "B*",
+                category: OperatorCategory::PathPainting,
+                extra_operands,
                 description: "Fill and stroke path using even-odd rule.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "BDC" => {
-            check_max_operands(operation, 2);
+            let extra_operands = get_extra_operands(operation, 2);
             OperationInfo {
                 operator: "BDC",
+                category: OperatorCategory::MarkedContent,
+                extra_operands,
                 description: "(PDF 1.2) Begin marked-content sequence with property list.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "tag".to_owned() => get_operands_value(operation, 0)?,
@@ -67,17 +123,21 @@ pub fn operation_info(
             }
         }
         "BI" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "BI",
+                category: OperatorCategory::InlineImage,
+                extra_operands,
                 description: "Begin inline image object.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "BMC" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "BMC",
+                category: OperatorCategory::MarkedContent,
+                extra_operands,
                 description: "(PDF 1.2) Begin marked-content sequence.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "tag".to_owned() => get_operands_value(operation, 0)?,
@@ -85,25 +145,31 @@ pub fn operation_info(
             }
         }
         "BT" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "BT",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Begin text object.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "BX" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "BX",
+                category: OperatorCategory::Compatibility,
+                extra_operands,
                 description: "(PDF 1.1) Begin compatibility section.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "c" => {
-            check_max_operands(operation, 6);
+            let extra_operands = get_extra_operands(operation, 6);
             OperationInfo {
                 operator: "c",
+                category: OperatorCategory::PathConstruction,
+                extra_operands,
                 description: "Append curved segment to path (three control points).",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "x1".to_owned() => get_operands_value(operation, 0)?,
@@ -116,9 +182,11 @@ pub fn operation_info(
             }
         }
         "cm" => {
-            check_max_operands(operation, 6);
+            let extra_operands = get_extra_operands(operation, 6);
             OperationInfo {
                 operator: "cm",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description:
                     "Concatenate matrix to current transformation matrix. `[a b 0; c d 0; e f 1]`",
                 values: OperationInfoValue::Arguments(indexmap! {
@@ -132,9 +200,11 @@ pub fn operation_info(
             }
         }
         "CS" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "CS",
+                category: OperatorCategory::Color,
+                extra_operands,
                 description: "(PDF 1.1) Set color space for stroking operations.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "name".to_owned() => get_operands_value(operation, 0)?,
@@ -142,9 +212,11 @@ pub fn operation_info(
             }
         }
         "cs" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "cs",
+                category: OperatorCategory::Color,
+                extra_operands,
                 description: "(PDF 1.1) Set color space for nonstroking operations.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "name".to_owned() => get_operands_value(operation, 0)?,
@@ -152,9 +224,11 @@ pub fn operation_info(
             }
         }
         "d" => {
-            check_max_operands(operation, 2);
+            let extra_operands = get_extra_operands(operation, 2);
             OperationInfo {
                 operator: "d",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description: "Set line dash pattern.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "dashArray".to_owned() => get_operands_value(operation, 0)?,
@@ -163,9 +237,11 @@ pub fn operation_info(
             }
         }
         "d0" => {
-            check_max_operands(operation, 2);
+            let extra_operands = get_extra_operands(operation, 2);
             OperationInfo {
                 operator: "d0",
+                category: OperatorCategory::Type3Font,
+                extra_operands,
                 description: "Set glyph width in Type 3 font.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "wx".to_owned() => get_operands_value(operation, 0)?,
@@ -174,9 +250,11 @@ pub fn operation_info(
             }
         }
         "d1" => {
-            check_max_operands(operation, 6);
+            let extra_operands = get_extra_operands(operation, 6);
             OperationInfo {
                 operator: "d1",
+                category: OperatorCategory::Type3Font,
+                extra_operands,
                 description: "Set glyph width and bounding box in Type 3 font.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "w_x".to_owned() => get_operands_value(operation, 0)?,
@@ -189,9 +267,11 @@ pub fn operation_info(
             }
         }
         "Do" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "Do",
+                category: OperatorCategory::XObject,
+                extra_operands,
                 description: "Invoke named XObject.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "name".to_owned() => get_operands_value(operation, 0)?,
@@ -199,9 +279,11 @@ pub fn operation_info(
             }
         }
         "DP" => {
-            check_max_operands(operation, 2);
+            let extra_operands = get_extra_operands(operation, 2);
             OperationInfo {
                 operator: "DP",
+                category: OperatorCategory::MarkedContent,
+                extra_operands,
                 description: "(PDF 1.2) Define marked-content point with property list.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "tag".to_owned() => get_operands_value(operation, 0)?,
@@ -210,65 +292,81 @@ pub fn operation_info(
             }
         }
         "EI" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "EI",
+                category: OperatorCategory::InlineImage,
+                extra_operands,
                 description: "End inline image object.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "EMC" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "EMC",
+                category: OperatorCategory::MarkedContent,
+                extra_operands,
                 description: "(PDF 1.2) End marked-content sequence.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "ET" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "ET",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "End text object.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "EX" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "EX",
+                category: OperatorCategory::Compatibility,
+                extra_operands,
                 description: "(PDF 1.1) End compatibility section.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "f" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "f",
+                category: OperatorCategory::PathPainting,
+                extra_operands,
                 description: "Fill path using nonzero winding number rule.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "F" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "F",
+                category: OperatorCategory::PathPainting,
+                extra_operands,
                 description: "Fill path using nonzero winding number rule (obsolete).",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "f*" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "f*",
+                category: OperatorCategory::PathPainting,
+                extra_operands,
                 description: "Fill path using even-odd rule.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "G" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "G",
+                category: OperatorCategory::Color,
+                extra_operands,
                 description: "Set gray level for stroking operations. (0=black, 1=while)",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "gray".to_owned() => get_operands_value(operation, 0)?,
@@ -276,9 +374,11 @@ pub fn operation_info(
             }
         }
         "g" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "g",
+                category: OperatorCategory::Color,
+                extra_operands,
                 description: "Set gray level for nonstroking operations. (0=black, 1=while)",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "gray".to_owned() => get_operands_value(operation, 0)?,
@@ -286,9 +386,11 @@ pub fn operation_info(
             }
         }
         "gs" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "gs",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description: "(PDF 1.2) Set parameters from graphics state parameter dictionary.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "dictName".to_owned() => get_operands_value(operation, 0)?,
@@ -296,35 +398,43 @@ pub fn operation_info(
             }
         }
         "h" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "h",
+                category: OperatorCategory::PathConstruction,
+                extra_operands,
                 description: "Close subpath.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "i" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "i",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description: "Set flatness tolerance.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "flatness".to_owned() => get_operands_value(operation, 0)?,
                 }),
             }
         }
-        "ID" => {
-            check_max_operands(operation, 0);
-            OperationInfo {
-                operator: "ID",
-                description: "Begin inline image data.",
-                values: unknown_values(operands),
-            }
-        }
+        "ID" => OperationInfo {
+            operator: "ID",
+            category: OperatorCategory::InlineImage,
+            extra_operands: Vec::new(),
+            description: "Begin inline image data.",
+            // The operands are the image dictionary, followed by the image data.
+            values: OperationInfoValue::Arguments(
+                get_inline_image_arguments(operands).into_iter().collect(),
+            ),
+        },
         "j" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "j",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description: "Set line join style.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "lineJoin".to_owned() => get_operands_value(operation, 0)?,
@@ -332,9 +442,11 @@ pub fn operation_info(
             }
         }
         "J" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "J",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description: "Set line cap style.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "lineCap".to_owned() => get_operands_value(operation, 0)?,
@@ -342,9 +454,11 @@ pub fn operation_info(
             }
         }
         "K" => {
-            check_max_operands(operation, 4);
+            let extra_operands = get_extra_operands(operation, 4);
             OperationInfo {
                 operator: "K",
+                category: OperatorCategory::Color,
+                extra_operands,
                 description: "Set CMYK color for stroking operations.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "cyan".to_owned() => get_operands_value(operation, 0)?,
@@ -355,9 +469,11 @@ pub fn operation_info(
             }
         }
         "k" => {
-            check_max_operands(operation, 4);
+            let extra_operands = get_extra_operands(operation, 4);
             OperationInfo {
                 operator: "k",
+                category: OperatorCategory::Color,
+                extra_operands,
                 description: "Set CMYK color for nonstroking operations.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "cyan".to_owned() => get_operands_value(operation, 0)?,
@@ -368,9 +484,11 @@ pub fn operation_info(
             }
         }
         "l" => {
-            check_max_operands(operation, 2);
+            let extra_operands = get_extra_operands(operation, 2);
             OperationInfo {
                 operator: "l",
+                category: OperatorCategory::PathConstruction,
+                extra_operands,
                 description: "Append straight line segment to path.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "x".to_owned() => get_operands_value(operation, 0)?,
@@ -379,9 +497,11 @@ pub fn operation_info(
             }
         }
         "m" => {
-            check_max_operands(operation, 2);
+            let extra_operands = get_extra_operands(operation, 2);
             OperationInfo {
                 operator: "m",
+                category: OperatorCategory::PathConstruction,
+                extra_operands,
                 description: "Begin new subpath.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "x".to_owned() => get_operands_value(operation, 0)?,
@@ -390,9 +510,11 @@ pub fn operation_info(
             }
         }
         "M" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "M",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description: "Set miter limit.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "miterLimit".to_owned() => get_operands_value(operation, 0)?,
@@ -400,9 +522,11 @@ pub fn operation_info(
             }
         }
         "MP" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "MP",
+                category: OperatorCategory::MarkedContent,
+                extra_operands,
                 description: "(PDF 1.2) Define marked-content point.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "tag".to_owned() => get_operands_value(operation, 0)?,
@@ -410,33 +534,41 @@ pub fn operation_info(
             }
         }
         "n" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "n",
+                category: OperatorCategory::PathPainting,
+                extra_operands,
                 description: "End path without filling or stroking.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "q" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "q",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description: "Save graphics state.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "Q" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "Q",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description: "Restore graphics state.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "re" => {
-            check_max_operands(operation, 4);
+            let extra_operands = get_extra_operands(operation, 4);
             OperationInfo {
                 operator: "re",
+                category: OperatorCategory::PathConstruction,
+                extra_operands,
                 description: "Append rectangle to path.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "x".to_owned() => get_operands_value(operation, 0)?,
@@ -447,9 +579,11 @@ pub fn operation_info(
             }
         }
         "RG" => {
-            check_max_operands(operation, 3);
+            let extra_operands = get_extra_operands(operation, 3);
             OperationInfo {
                 operator: "RG",
+                category: OperatorCategory::Color,
+                extra_operands,
                 description: "Set RGB color for stroking operations.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "red".to_owned() => get_operands_value(operation, 0)?,
@@ -459,9 +593,11 @@ pub fn operation_info(
             }
         }
         "rg" => {
-            check_max_operands(operation, 3);
+            let extra_operands = get_extra_operands(operation, 3);
             OperationInfo {
                 operator: "rg",
+                category: OperatorCategory::Color,
+                extra_operands,
                 description: "Set RGB color for nonstroking operations.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "red".to_owned() => get_operands_value(operation, 0)?,
@@ -471,9 +607,11 @@ pub fn operation_info(
             }
         }
         "ri" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "ri",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description: "Set color rendering intent.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "intent".to_owned() => get_operands_value(operation, 0)?,
@@ -481,25 +619,31 @@ pub fn operation_info(
             }
         }
         "s" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "s",
+                category: OperatorCategory::PathPainting,
+                extra_operands,
                 description: "Close and stroke path.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "S" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "S",
+                category: OperatorCategory::PathPainting,
+                extra_operands,
                 description: "Stroke path.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "SC" => {
             // No Limit
             OperationInfo {
                 operator: "SC",
+                category: OperatorCategory::Color,
+                extra_operands: Vec::new(),
                 description: "(PDF 1.1) Set color for stroking operations.",
                 values: infinite_values(operands, "c"),
             }
@@ -508,6 +652,8 @@ pub fn operation_info(
             // No Limit
             OperationInfo {
                 operator: "sc",
+                category: OperatorCategory::Color,
+                extra_operands: Vec::new(),
                 description: "(PDF 1.1) Set color for nonstroking operations.",
                 values: infinite_values(operands, "c"),
             }
@@ -516,6 +662,8 @@ pub fn operation_info(
             // No Limit
             OperationInfo {
                 operator: "SCN",
+                category: OperatorCategory::Color,
+                extra_operands: Vec::new(),
                 description: "(PDF 1.2) Set color for stroking operations (ICCBased and special colour spaces).",
                 values: infinite_values(operands, "c"),
             }
@@ -524,14 +672,18 @@ pub fn operation_info(
             // No Limit
             OperationInfo {
                 operator: "scn",
+                category: OperatorCategory::Color,
+                extra_operands: Vec::new(),
                 description: "(PDF 1.2) Set color for nonstroking operations (ICCBased and special colour spaces).",
                 values: infinite_values(operands, "c"),
             }
         }
         "sh" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "sh",
+                category: OperatorCategory::Shading,
+                extra_operands,
                 description: "(PDF 1.3) Paint area defined by shading pattern.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "name".to_owned() => get_operands_value(operation, 0)?,
@@ -539,17 +691,21 @@ pub fn operation_info(
             }
         }
         "T*" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "T*",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Move to start of next text line.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "Tc" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "Tc",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Set character spacing.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "charSpace".to_owned() => get_operands_value(operation, 0)?,
@@ -557,9 +713,11 @@ pub fn operation_info(
             }
         }
         "Td" => {
-            check_max_operands(operation, 2);
+            let extra_operands = get_extra_operands(operation, 2);
             OperationInfo {
                 operator: "Td",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Move text position.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "Tx".to_owned() => get_operands_value(operation, 0)?,
@@ -568,9 +726,11 @@ pub fn operation_info(
             }
         }
         "TD" => {
-            check_max_operands(operation, 2);
+            let extra_operands = get_extra_operands(operation, 2);
             OperationInfo {
                 operator: "TD",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Move text position and set leading.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "Tx".to_owned() => get_operands_value(operation, 0)?,
@@ -579,9 +739,11 @@ pub fn operation_info(
             }
         }
         "Tf" => {
-            check_max_operands(operation, 2);
+            let extra_operands = get_extra_operands(operation, 2);
             OperationInfo {
                 operator: "Tf",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Set text font and size.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "font".to_owned() => get_operands_value(operation, 0)?,
@@ -590,9 +752,11 @@ pub fn operation_info(
             }
         }
         "Tj" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "Tj",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Show text.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "string".to_owned() => get_operands_value(operation, 0)?,
@@ -601,12 +765,16 @@ pub fn operation_info(
         }
         "TJ" => {
             let mut formatted_string = String::new();
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             for item in get_operands_value(operation, 0)?.as_array()? {
                 match item {
                     Object::String(string_value, string_format) => match string_format {
-                        StringFormat::Literal => formatted_string
-                            .push_str(&String::from_utf8_lossy(string_value).to_string()),
+                        StringFormat::Literal if display_settings.redact_strings => {
+                            formatted_string.push_str(&get_redacted_string(string_value))
+                        }
+                        StringFormat::Literal => {
+                            formatted_string.push_str(&String::from_utf8_lossy(string_value))
+                        }
                         StringFormat::Hexadecimal => {
                             let obj_print_info = get_object_print_info(item, display_settings);
                             formatted_string.push_str(&format!(
@@ -625,6 +793,8 @@ pub fn operation_info(
             }
             OperationInfo {
                 operator: "TJ",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Show text, allowing individual glyph positioning",
                 values: OperationInfoValue::FormattedString(format!(
                     "'{}' {}",
@@ -634,9 +804,11 @@ pub fn operation_info(
             }
         }
         "TL" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "TL",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Set text leading.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "leading".to_owned() => get_operands_value(operation, 0)?,
@@ -644,9 +816,11 @@ pub fn operation_info(
             }
         }
         "Tm" => {
-            check_max_operands(operation, 6);
+            let extra_operands = get_extra_operands(operation, 6);
             OperationInfo {
                 operator: "Tm",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Set text matrix and text line matrix. `[a b 0; c d 0; e f 1]`",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "a".to_owned() => get_operands_value(operation, 0)?,
@@ -659,9 +833,11 @@ pub fn operation_info(
             }
         }
         "Tr" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "Tr",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Set text rendering mode.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "render".to_owned() => get_operands_value(operation, 0)?,
@@ -669,9 +845,11 @@ pub fn operation_info(
             }
         }
         "Ts" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "Ts",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Set text rise.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "rise".to_owned() => get_operands_value(operation, 0)?,
@@ -679,9 +857,11 @@ pub fn operation_info(
             }
         }
         "Tw" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "Tw",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Set word spacing.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "wordSpace".to_owned() => get_operands_value(operation, 0)?,
@@ -689,9 +869,11 @@ pub fn operation_info(
             }
         }
         "Tz" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "Tz",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Set horizontal text scaling.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "scale".to_owned() => get_operands_value(operation, 0)?,
@@ -699,9 +881,11 @@ pub fn operation_info(
             }
         }
         "v" => {
-            check_max_operands(operation, 4);
+            let extra_operands = get_extra_operands(operation, 4);
             OperationInfo {
                 operator: "v",
+                category: OperatorCategory::PathConstruction,
+                extra_operands,
                 description: "Append curved segment to path (initial point replicated).",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "x2".to_owned() => get_operands_value(operation, 0)?,
@@ -712,9 +896,11 @@ pub fn operation_info(
             }
         }
         "w" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "w",
+                category: OperatorCategory::GraphicsState,
+                extra_operands,
                 description: "Set line width.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "lineWidth".to_owned() => get_operands_value(operation, 0)?,
@@ -722,25 +908,31 @@ pub fn operation_info(
             }
         }
         "W" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "W",
+                category: OperatorCategory::Clipping,
+                extra_operands,
                 description: "Set clipping path using nonzero winding number rule.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "W*" => {
-            check_max_operands(operation, 0);
+            let extra_operands = get_extra_operands(operation, 0);
             OperationInfo {
                 operator: "W*",
+                category: OperatorCategory::Clipping,
+                extra_operands,
                 description: "Set clipping path using even-odd rule.",
-                values: unknown_values(operands),
+                values: no_values(),
             }
         }
         "y" => {
-            check_max_operands(operation, 4);
+            let extra_operands = get_extra_operands(operation, 4);
             OperationInfo {
                 operator: "y",
+                category: OperatorCategory::PathConstruction,
+                extra_operands,
                 description: "Append curved segment to path (final point replicated).",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "x1".to_owned() => get_operands_value(operation, 0)?,
@@ -751,9 +943,11 @@ pub fn operation_info(
             }
         }
         "'" => {
-            check_max_operands(operation, 1);
+            let extra_operands = get_extra_operands(operation, 1);
             OperationInfo {
                 operator: "'",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Move to next line and show text.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "string".to_owned() => get_operands_value(operation, 0)?,
@@ -761,9 +955,11 @@ pub fn operation_info(
             }
         }
         "\"" => {
-            check_max_operands(operation, 3);
+            let extra_operands = get_extra_operands(operation, 3);
             OperationInfo {
                 operator: "\"",
+                category: OperatorCategory::Text,
+                extra_operands,
                 description: "Set word and character spacing, move to next line, and show text.",
                 values: OperationInfoValue::Arguments(indexmap! {
                     "a_word".to_owned() => get_operands_value(operation, 0)?,
@@ -778,6 +974,39 @@ pub fn operation_info(
     Ok(operation_info)
 }
 
+/// Get the description and argument names of an operator, `None` if the operator is unknown.
+///
+/// The names are found by calling `operation_info` with more and more (empty) operands
+/// until all required operands are present.
+pub fn get_operator_help(operator: &str) -> Option<(&'static str, Vec<String>)> {
+    let display_settings = TreeDisplaySettings::default();
+    for operand_count in 0..=6 {
+        let operation = Operation::new(operator, vec![Object::Array(vec![]); operand_count]);
+        match operation_info(&operation, &display_settings) {
+            Ok(OperationInfo {
+                description,
+                values: OperationInfoValue::Arguments(arguments),
+                ..
+            }) => {
+                if arguments.is_empty() && VARIABLE_OPERAND_OPERATORS.contains(&operator) {
+                    return Some((description, vec!["c1 ... cn".to_owned()]));
+                }
+                return Some((description, arguments.keys().cloned().collect()));
+            }
+            Ok(OperationInfo {
+                description,
+                values: OperationInfoValue::FormattedString(_),
+                ..
+            }) => return Some((description, vec!["array".to_owned()])),
+            Err(_) => continue,
+        }
+    }
+    None
+}
+
+/// Operators that take any amount of operands (color components).
+static VARIABLE_OPERAND_OPERATORS: [&str; 4] = ["SC", "sc", "SCN", "scn"];
+
 fn get_operands_value(operation: &Operation, index: usize) -> Result<Object, Error> {
     operation.operands.get(index).cloned().ok_or_else(|| {
         Error::Syntax(format!(
@@ -787,7 +1016,8 @@ fn get_operands_value(operation: &Operation, index: usize) -> Result<Object, Err
     })
 }
 
-fn check_max_operands(operation: &Operation, max_len: usize) {
+/// Get the operands after the first `max_len`, so they can be shown as extra operands.
+fn get_extra_operands(operation: &Operation, max_len: usize) -> Vec<Object> {
     if operation.operands.len() > max_len {
         log::warn!(
             "`{}` operation does not support more then {} values.",
@@ -795,10 +1025,11 @@ fn check_max_operands(operation: &Operation, max_len: usize) {
             max_len
         );
     }
+    operation.operands.iter().skip(max_len).cloned().collect()
 }
 
-fn unknown_values(values: &[Object]) -> OperationInfoValue {
-    infinite_values(values, "Unknown_")
+fn no_values() -> OperationInfoValue {
+    OperationInfoValue::Arguments(IndexMap::new())
 }
 
 fn infinite_values(values: &[Object], prefix: &str) -> OperationInfoValue {
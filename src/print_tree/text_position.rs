@@ -0,0 +1,144 @@
+use lopdf::content::Operation;
+
+/// A transformation matrix `[a b c d e f]`, see p118 (8.3.4 Transformation Matrices) in PDF v1.7 Spec.
+type Matrix = [f64; 6];
+
+static IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// The part of the graphics state that is saved and restored with `q` and `Q`.
+#[derive(Debug, Clone, PartialEq)]
+struct GraphicsState {
+    ctm: Matrix,
+    leading: f64,
+    rise: f64,
+}
+
+impl Default for GraphicsState {
+    fn default() -> Self {
+        GraphicsState {
+            ctm: IDENTITY,
+            leading: 0.0,
+            rise: 0.0,
+        }
+    }
+}
+
+/// Keeps track of the text position while walking over a content stream.
+///
+/// Only `q`/`Q`, `cm` and the text positioning operators are interpreted.
+/// Glyph widths are not known, so the position does not move while text is shown.
+/// See p248 (9.4.2 Text-Positioning Operators) in PDF v1.7 Spec.
+#[derive(Debug, Clone)]
+pub struct TextPositionTracker {
+    current: GraphicsState,
+    saved: Vec<GraphicsState>,
+    text_matrix: Matrix,
+    text_line_matrix: Matrix,
+    /// Text was shown since the last positioning operator.
+    has_shown_text: bool,
+}
+
+impl TextPositionTracker {
+    pub fn new() -> Self {
+        TextPositionTracker {
+            current: GraphicsState::default(),
+            saved: Vec::new(),
+            text_matrix: IDENTITY,
+            text_line_matrix: IDENTITY,
+            has_shown_text: false,
+        }
+    }
+
+    /// Update the state with this operation.
+    pub fn apply(&mut self, operation: &Operation) {
+        let operands: Vec<f64> = operation
+            .operands
+            .iter()
+            .filter_map(|operand| operand.as_float().ok())
+            .collect();
+        match (operation.operator.as_ref(), operands.as_slice()) {
+            ("q", _) => self.saved.push(self.current.clone()),
+            ("Q", _) => match self.saved.pop() {
+                Some(saved) => self.current = saved,
+                None => {
+                    log::warn!("Found `Q` without matching `q`, text position is not restored.")
+                }
+            },
+            ("cm", [a, b, c, d, e, f]) => {
+                self.current.ctm = multiply(&[*a, *b, *c, *d, *e, *f], &self.current.ctm);
+            }
+            ("BT", _) => self.set_text_matrix(IDENTITY),
+            ("Tm", [a, b, c, d, e, f]) => self.set_text_matrix([*a, *b, *c, *d, *e, *f]),
+            ("Td", [tx, ty]) => self.move_text_position(*tx, *ty),
+            ("TD", [tx, ty]) => {
+                self.current.leading = -ty;
+                self.move_text_position(*tx, *ty);
+            }
+            ("TL", [leading]) => self.current.leading = *leading,
+            ("Ts", [rise]) => self.current.rise = *rise,
+            ("T*", _) | ("'", _) | ("\"", _) => self.move_text_position(0.0, -self.current.leading),
+            _ => {}
+        }
+    }
+
+    /// Describe where a text showing operation starts on the page, `None` for other operations.
+    ///
+    /// Call this after `apply`, the position is marked as used afterwards.
+    pub fn get_text_position_info(&mut self, operator: &str) -> Option<String> {
+        if !matches!(operator, "Tj" | "TJ" | "'" | "\"") {
+            return None;
+        }
+        let matrix = multiply(&self.text_matrix, &self.current.ctm);
+        // The text origin is moved up by the text rise.
+        let x = self.current.rise * matrix[2] + matrix[4];
+        let y = self.current.rise * matrix[3] + matrix[5];
+        let info = if self.has_shown_text {
+            format!(
+                "after text started at x: {}, y: {}",
+                format_number(x),
+                format_number(y)
+            )
+        } else {
+            format!("at x: {}, y: {}", format_number(x), format_number(y))
+        };
+        self.has_shown_text = true;
+        Some(info)
+    }
+
+    fn set_text_matrix(&mut self, matrix: Matrix) {
+        self.text_matrix = matrix;
+        self.text_line_matrix = matrix;
+        self.has_shown_text = false;
+    }
+
+    fn move_text_position(&mut self, tx: f64, ty: f64) {
+        self.set_text_matrix(multiply(
+            &[1.0, 0.0, 0.0, 1.0, tx, ty],
+            &self.text_line_matrix,
+        ));
+    }
+}
+
+/// Multiply two matrices, `first` is applied first.
+fn multiply(first: &Matrix, second: &Matrix) -> Matrix {
+    let [a1, b1, c1, d1, e1, f1] = *first;
+    let [a2, b2, c2, d2, e2, f2] = *second;
+    [
+        a1 * a2 + b1 * c2,
+        a1 * b2 + b1 * d2,
+        c1 * a2 + d1 * c2,
+        c1 * b2 + d1 * d2,
+        e1 * a2 + f1 * c2 + e2,
+        e1 * b2 + f1 * d2 + f2,
+    ]
+}
+
+/// Round to 2 decimals and remove trailing zeros.
+pub(super) fn format_number(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+    match formatted {
+        "-0" => "0".to_owned(),
+        formatted => formatted.to_owned(),
+    }
+}
@@ -1,36 +1,115 @@
-use crate::StreamDisplay;
+use super::pdf_objects::ValueFormatter;
+use crate::{StreamDisplay, TreeStart};
 
 #[derive(Debug, Clone)]
 pub struct TreeDisplaySettings {
     pub max_depth: usize,
+    /// The maximum amount of references followed after the `expand` path.
+    pub follow_refs_depth: Option<usize>,
     pub expand: Option<Vec<String>>,
+    pub relative_depth: bool,
+    /// Only print the items on the `expand` path, not the children of the last one.
+    pub only_path: bool,
     pub display_type_names: bool,
     pub array_display_limit: Option<usize>,
     pub hex_display_limit: Option<usize>,
+    /// Literal strings and names are cut off after this many characters.
+    pub max_value_len: Option<usize>,
+    /// Arrays of up to this many numbers are printed on one line, like `[0 0 595 842]`.
+    pub inline_number_array_limit: Option<usize>,
+    /// Nesting depth of arrays and dictionaries printed in content stream operands.
+    pub max_operand_array_depth: usize,
     pub display_stream: StreamDisplay,
     pub display_legend: bool,
+    /// Print the legend on one line.
+    pub compact_legend: bool,
     pub display_font: bool,
     pub display_parent: bool,
+    pub no_cycle_guard: bool,
     pub stream_enhanced_operations: bool,
     pub stream_enhanced_operator_info: bool,
+    /// Print the operations between `q` and `Q`, `BT` and `ET` and marked content one level deeper.
+    pub stream_nesting: bool,
+    pub show_raw_operands: bool,
     pub force_stream_decoding: bool,
+    /// Extra keys whose streams are decoded as content streams.
+    pub decode_stream_keys: Vec<String>,
+    /// Streams are not decompressed, so content streams can not be decoded.
+    pub no_decompress: bool,
+    pub inline_stream_refs: bool,
+    pub display_box_sizes: bool,
+    /// Start the tree here instead of at the trailer.
+    pub tree_start: Option<TreeStart>,
+    /// Start the tree at this page, the first page is 1.
+    pub page: Option<usize>,
+    pub collapse_repeats: bool,
+    pub operation_indices: bool,
+    pub operator_filter: Option<Vec<String>>,
+    /// Dictionary keys that are printed with `HIGHLIGHT_STYLE`.
+    pub highlight_keys: Vec<String>,
+    pub resolve_scalars: bool,
+    pub track_colors: bool,
+    pub text_positions: bool,
+    pub redact_strings: bool,
+    /// Print literal strings as escaped bytes instead of decoding them.
+    pub raw_string_bytes: bool,
+    pub short_skip_marker: bool,
+    pub mark_indirect: bool,
+    pub overview_tree: bool,
+    /// Print dictionary entries sorted by key instead of in file order.
+    pub sort_keys: bool,
+    /// Print a chain of dictionaries with a single entry on one line, like `Root > Pages`.
+    pub collapse_single_child: bool,
+    /// Formatters that replace the printed value of a type, by type name (like `Name`).
+    pub value_formatters: Vec<(String, ValueFormatter)>,
 }
 
 impl Default for TreeDisplaySettings {
     fn default() -> Self {
         TreeDisplaySettings {
             max_depth: 20,
+            follow_refs_depth: None,
             expand: None,
+            relative_depth: false,
+            only_path: false,
             display_type_names: false,
             array_display_limit: Some(5),
             hex_display_limit: Some(16),
+            max_value_len: None,
+            inline_number_array_limit: None,
+            max_operand_array_depth: 8,
             display_stream: StreamDisplay::NoDisplay,
             display_font: false,
             display_parent: false,
+            no_cycle_guard: false,
             display_legend: true,
+            compact_legend: false,
             stream_enhanced_operations: true,
             stream_enhanced_operator_info: false,
+            stream_nesting: true,
+            show_raw_operands: false,
             force_stream_decoding: false,
+            decode_stream_keys: Vec::new(),
+            no_decompress: false,
+            inline_stream_refs: false,
+            display_box_sizes: false,
+            tree_start: None,
+            page: None,
+            collapse_repeats: false,
+            operation_indices: false,
+            operator_filter: None,
+            highlight_keys: Vec::new(),
+            resolve_scalars: false,
+            track_colors: false,
+            text_positions: false,
+            redact_strings: false,
+            raw_string_bytes: false,
+            short_skip_marker: false,
+            mark_indirect: false,
+            overview_tree: false,
+            sort_keys: false,
+            collapse_single_child: false,
+            value_formatters: Vec::new(),
         }
     }
 }
@@ -0,0 +1,79 @@
+use lopdf::{Object, ObjectId};
+
+/// A reference found in an object, with the key path to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub object_id: ObjectId,
+    /// Dot separated keys and array indices, empty if the object itself is a reference.
+    pub key_path: String,
+}
+
+/// Collect all references in `obj` (without following them), also in the dictionary of a stream.
+pub fn get_references(obj: &Object) -> Vec<Reference> {
+    let mut references = Vec::new();
+    collect_references(obj, "", &mut references);
+    references
+}
+
+fn collect_references(obj: &Object, key_path: &str, references: &mut Vec<Reference>) {
+    let join = |item: &str| {
+        if key_path.is_empty() {
+            item.to_owned()
+        } else {
+            format!("{}.{}", key_path, item)
+        }
+    };
+    match obj {
+        Object::Reference(object_id) => references.push(Reference {
+            object_id: *object_id,
+            key_path: key_path.to_owned(),
+        }),
+        Object::Array(list) => {
+            for (index, item) in list.iter().enumerate() {
+                collect_references(item, &join(&index.to_string()), references);
+            }
+        }
+        Object::Dictionary(dict) => {
+            for (key, value) in dict.iter() {
+                collect_references(value, &join(&String::from_utf8_lossy(key)), references);
+            }
+        }
+        Object::Stream(stream) => {
+            for (key, value) in stream.dict.iter() {
+                collect_references(value, &join(&String::from_utf8_lossy(key)), references);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, Stream};
+
+    #[test]
+    fn collects_references_with_key_paths() {
+        let stream = Stream::new(
+            dictionary! { "Metadata" => Object::Reference((3, 0)) },
+            vec![],
+        );
+        let obj = Object::Dictionary(dictionary! {
+            "Kids" => vec![Object::Integer(1), Object::Reference((1, 0))],
+            "Resources" => dictionary! { "Font" => Object::Reference((2, 0)) },
+            "Contents" => Object::Stream(stream),
+        });
+        let key_paths: Vec<(ObjectId, String)> = get_references(&obj)
+            .into_iter()
+            .map(|reference| (reference.object_id, reference.key_path))
+            .collect();
+        assert_eq!(
+            key_paths,
+            vec![
+                ((1, 0), "Kids.1".to_owned()),
+                ((2, 0), "Resources.Font".to_owned()),
+                ((3, 0), "Contents.Metadata".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use crate::object_offsets::get_revision_ends;
+use crate::print_tree::get_type_label;
+use lopdf::{Document, Error, Object, ObjectId};
+use std::collections::BTreeSet;
+use std::io::Write;
+use yansi::{Paint, Style};
+
+/// Print which objects were added, changed or removed between two revisions of the file.
+///
+/// Each `%%EOF` marker ends a revision (starting at 0), incremental updates add new revisions.
+/// A revision is loaded from the bytes up to its `%%EOF` marker, so its objects are
+/// resolved with the cross-reference sections of that revision and the ones before it.
+pub fn print_revision_diff(
+    out: &mut dyn Write,
+    file_bytes: &[u8],
+    from: usize,
+    to: usize,
+) -> Result<(), Error> {
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+    let extra_info_style = Style::default().italic();
+
+    let revision_ends = get_revision_ends(file_bytes);
+    for revision in [from, to] {
+        if revision >= revision_ends.len() {
+            log::error!(
+                "Revision {} does not exist, the file has {} revision(s).",
+                revision,
+                revision_ends.len()
+            );
+            return Err(Error::IO(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Revision {} does not exist.", revision),
+            )));
+        }
+    }
+    let from_doc = load_revision(file_bytes, &revision_ends, from)?;
+    let to_doc = load_revision(file_bytes, &revision_ends, to)?;
+
+    writeln!(out, "--- {} ---", Paint::cyan("Revision diff").bold())?;
+    writeln!(
+        out,
+        "{} {} {}",
+        label_style.paint(format!("From revision {}:", from)),
+        value_style.paint(from_doc.objects.len()),
+        extra_info_style.paint(format!("objects (ends at offset {})", revision_ends[from])),
+    )?;
+    writeln!(
+        out,
+        "{} {} {}",
+        label_style.paint(format!("To revision {}:", to)),
+        value_style.paint(to_doc.objects.len()),
+        extra_info_style.paint(format!("objects (ends at offset {})", revision_ends[to])),
+    )?;
+
+    let object_ids: BTreeSet<ObjectId> = from_doc
+        .objects
+        .keys()
+        .chain(to_doc.objects.keys())
+        .copied()
+        .collect();
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    let mut removed = Vec::new();
+    for object_id in object_ids {
+        match (
+            from_doc.objects.get(&object_id),
+            to_doc.objects.get(&object_id),
+        ) {
+            (None, Some(object)) => added.push((object_id, object)),
+            (Some(from_object), Some(object)) if !objects_equal(from_object, object) => {
+                changed.push((object_id, object))
+            }
+            (Some(object), None) => removed.push((object_id, object)),
+            _ => {}
+        }
+    }
+
+    for (title, objects) in [
+        ("Added", &added),
+        ("Changed", &changed),
+        ("Removed", &removed),
+    ] {
+        writeln!(
+            out,
+            "{} {}",
+            label_style.paint(format!("{}:", title)),
+            value_style.paint(objects.len())
+        )?;
+        for (object_id, object) in objects {
+            let type_label = get_type_label(object)
+                .map(|type_label| format!(" <{}>", type_label))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "  {}{}",
+                value_style.paint(format!("({},{})", object_id.0, object_id.1)),
+                extra_info_style.paint(type_label)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn load_revision(
+    file_bytes: &[u8],
+    revision_ends: &[usize],
+    revision: usize,
+) -> Result<Document, Error> {
+    Document::load_mem(&file_bytes[..revision_ends[revision]]).inspect_err(|err| {
+        log::error!("Could not load revision {}: {}", revision, err);
+    })
+}
+
+/// Compare the objects as they are stored, references are only equal with the same id.
+fn objects_equal(first: &Object, second: &Object) -> bool {
+    match (first, second) {
+        (Object::Null, Object::Null) => true,
+        (Object::Boolean(first), Object::Boolean(second)) => first == second,
+        (Object::Integer(first), Object::Integer(second)) => first == second,
+        (Object::Real(first), Object::Real(second)) => first == second,
+        (Object::Name(first), Object::Name(second)) => first == second,
+        (Object::String(first, _), Object::String(second, _)) => first == second,
+        (Object::Reference(first), Object::Reference(second)) => first == second,
+        (Object::Array(first), Object::Array(second)) => {
+            first.len() == second.len()
+                && first
+                    .iter()
+                    .zip(second)
+                    .all(|(first, second)| objects_equal(first, second))
+        }
+        (Object::Dictionary(first), Object::Dictionary(second)) => {
+            dictionaries_equal(first, second)
+        }
+        (Object::Stream(first), Object::Stream(second)) => {
+            first.content == second.content && dictionaries_equal(&first.dict, &second.dict)
+        }
+        _ => false,
+    }
+}
+
+fn dictionaries_equal(first: &lopdf::Dictionary, second: &lopdf::Dictionary) -> bool {
+    first.len() == second.len()
+        && first.iter().all(|(key, value)| match second.get(key) {
+            Ok(other_value) => objects_equal(value, other_value),
+            Err(_) => false,
+        })
+}
@@ -0,0 +1,160 @@
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Error, Object, Stream, StringFormat};
+use std::path::Path;
+
+/// Create a small PDF that uses the most common structures.
+///
+/// It has one page with a content stream that draws text with a font and shows an image XObject.
+/// The output is always the same, so it can be used as a known file to try things on.
+pub fn create_sample_document() -> Result<Document, Error> {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    // 2x2 pixels: red, green, blue and white.
+    let image_data = vec![255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255];
+    let image_id = doc.add_object(Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => 2,
+            "Height" => 2,
+            "ColorSpace" => "DeviceRGB",
+            "BitsPerComponent" => 8,
+        },
+        image_data,
+    ));
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! {
+            "F1" => font_id,
+        },
+        "XObject" => dictionary! {
+            "Im1" => image_id,
+        },
+    });
+
+    let content = Content {
+        operations: vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["F1".into(), 24.into()]),
+            Operation::new("Td", vec![72.into(), 720.into()]),
+            Operation::new(
+                "Tj",
+                vec![Object::String(
+                    b"PDF Structure Viewer".to_vec(),
+                    StringFormat::Literal,
+                )],
+            ),
+            Operation::new("ET", vec![]),
+            Operation::new("q", vec![]),
+            Operation::new(
+                "cm",
+                vec![
+                    100.into(),
+                    0.into(),
+                    0.into(),
+                    100.into(),
+                    72.into(),
+                    560.into(),
+                ],
+            ),
+            Operation::new("Do", vec!["Im1".into()]),
+            Operation::new("Q", vec![]),
+        ],
+    };
+    let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode()?));
+    let page_id = doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "Contents" => content_id,
+    });
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![page_id.into()],
+            "Count" => 1,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        }),
+    );
+    let info_id = doc.add_object(dictionary! {
+        "Title" => Object::string_literal("Sample document"),
+        "Producer" => Object::string_literal("pdf_structure_viewer"),
+    });
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc.trailer.set("Info", info_id);
+    Ok(doc)
+}
+
+/// Create the sample document and write it to `output`.
+pub fn save_sample_document(output: &Path) -> Result<(), Error> {
+    let mut doc = create_sample_document()?;
+    doc.save(output)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save_to_bytes(doc: &mut Document) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn output_is_always_the_same() {
+        let first = save_to_bytes(&mut create_sample_document().unwrap());
+        let second = save_to_bytes(&mut create_sample_document().unwrap());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn saved_document_can_be_loaded() {
+        let bytes = save_to_bytes(&mut create_sample_document().unwrap());
+        let doc = Document::load_mem(&bytes).unwrap();
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 1);
+        let page_id = pages[&1];
+        assert_eq!(
+            doc.extract_text(&[1]).unwrap().trim(),
+            "PDF Structure Viewer"
+        );
+
+        let fonts = doc.get_page_fonts(page_id);
+        let font = fonts.get(b"F1".as_slice()).unwrap();
+        assert_eq!(
+            font.get(b"BaseFont").and_then(Object::as_name).unwrap(),
+            b"Helvetica"
+        );
+
+        let (_, resource_ids) = doc.get_page_resources(page_id);
+        let image = resource_ids
+            .iter()
+            .filter_map(|resources_id| doc.get_dictionary(*resources_id).ok())
+            .filter_map(|resources| resources.get(b"XObject").and_then(Object::as_dict).ok())
+            .filter_map(|xobjects| xobjects.get(b"Im1").and_then(Object::as_reference).ok())
+            .find_map(|image_id| doc.get_object(image_id).and_then(Object::as_stream).ok())
+            .unwrap();
+        assert_eq!(
+            image
+                .dict
+                .get(b"Subtype")
+                .and_then(Object::as_name)
+                .unwrap(),
+            b"Image"
+        );
+        assert_eq!(image.content.len(), 2 * 2 * 3);
+    }
+}
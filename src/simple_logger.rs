@@ -1,8 +1,18 @@
+use indexmap::IndexMap;
 use log::{Level, Metadata, Record};
+use std::sync::Mutex;
 pub use yansi::Paint;
 
 /// An instance of the `Logger`.
 pub static LOGGER: Logger = Logger;
+
+lazy_static::lazy_static! {
+    /// All warnings emitted during this run, with the amount of times they occurred.
+    static ref WARNING_COUNTS: Mutex<IndexMap<String, usize>> = Mutex::new(IndexMap::new());
+    /// Messages that are held back while a pager shows the output, see `hold_messages`.
+    static ref HELD_MESSAGES: Mutex<Option<Vec<String>>> = Mutex::new(None);
+}
+
 /// The log collector and handler for most printed messages in terminal.
 pub struct Logger;
 
@@ -26,9 +36,14 @@ impl log::Log for Logger {
     }
 
     fn log(&self, record: &Record) {
+        if record.level() == Level::Warn {
+            if let Ok(mut warning_counts) = WARNING_COUNTS.lock() {
+                *warning_counts.entry(record.args().to_string()).or_insert(0) += 1;
+            }
+        }
         if self.enabled(record.metadata()) {
             // Print to stderr instead of stdout
-            eprintln!(
+            print_message(format!(
                 "{:<5}:{} - {}",
                 match record.level() {
                     Level::Error => Paint::red("ERROR"),
@@ -39,9 +54,73 @@ impl log::Log for Logger {
                 },
                 Paint::new(record.target()).dimmed(),
                 record.args()
-            );
+            ));
         }
     }
 
     fn flush(&self) {}
 }
+
+/// Print a summary of all warnings that were logged, grouped by message.
+///
+/// Nothing is printed when there were no warnings.
+pub fn print_warning_summary() {
+    let warning_counts = match WARNING_COUNTS.lock() {
+        Ok(warning_counts) => warning_counts,
+        Err(_) => return,
+    };
+    if warning_counts.is_empty() {
+        return;
+    }
+    let total: usize = warning_counts.values().sum();
+    let mut sorted_warnings: Vec<_> = warning_counts.iter().collect();
+    // Most frequent first, keep first occurrence order otherwise.
+    sorted_warnings.sort_by(|a, b| b.1.cmp(a.1));
+
+    print_message(format!(
+        "{} ({} total):",
+        Paint::yellow("Warning summary").bold(),
+        total
+    ));
+    for (message, count) in sorted_warnings {
+        print_message(format!("  {:>5}x {}", count, message));
+    }
+}
+
+/// Forget all logged warnings, used before running again in `watch` mode.
+pub fn reset_warning_summary() {
+    if let Ok(mut warning_counts) = WARNING_COUNTS.lock() {
+        warning_counts.clear();
+    }
+}
+
+/// Print a message on stderr, or hold it back while a pager is shown.
+fn print_message(message: String) {
+    if let Ok(mut held_messages) = HELD_MESSAGES.lock() {
+        if let Some(held_messages) = held_messages.as_mut() {
+            held_messages.push(message);
+            return;
+        }
+    }
+    eprintln!("{}", message);
+}
+
+/// Hold back the messages until `print_held_messages`.
+///
+/// Used while a pager shows the output, messages on stderr would be printed over it.
+pub fn hold_messages() {
+    if let Ok(mut held_messages) = HELD_MESSAGES.lock() {
+        held_messages.get_or_insert_with(Vec::new);
+    }
+}
+
+/// Print the messages that were held back, and print new messages directly again.
+pub fn print_held_messages() {
+    let held_messages = match HELD_MESSAGES.lock() {
+        Ok(mut held_messages) => held_messages.take(),
+        Err(_) => return,
+    };
+    for message in held_messages.into_iter().flatten() {
+        eprintln!("{}", message);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::page_tree::load_page_tree;
+use crate::print_tree::{
+    decode_content_stream, get_object_print_info, get_operator_help, TreeDisplaySettings,
+};
+use lopdf::{Dictionary, Document, Error, Object, ObjectId, Stream};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use yansi::{Paint, Style};
+
+/// Counts of the objects in the document, for a quick impression of a file.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Stats {
+    pub objects_amount: usize,
+    /// The amount of values of each type, also counting values inside other objects.
+    /// Labeled with the type names of the tree, like `Literal_String`.
+    pub type_counts: BTreeMap<String, usize>,
+    /// `None` when the page tree could not be loaded.
+    pub page_count: Option<usize>,
+    /// The bytes of all streams, as stored in the file.
+    pub stream_bytes: usize,
+    /// The bytes of all streams after removing their filters, only when requested.
+    pub decoded_stream_bytes: Option<usize>,
+    /// Streams with filters that could not be decoded, not included in `decoded_stream_bytes`.
+    pub undecodable_streams: usize,
+    /// How often each operator is used in all content streams, only when requested.
+    pub operator_counts: Option<BTreeMap<String, usize>>,
+    /// Content streams that could not be decoded, not included in `operator_counts`.
+    pub undecodable_content_streams: usize,
+}
+
+/// Print how many values of each type the document contains, with the size of its streams.
+///
+/// Decoding all streams is slow for large files, so it is only done with `decode_streams`.
+/// The same goes for counting the operators of all content streams with `count_operators`.
+pub fn print_stats(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    decode_streams: bool,
+    count_operators: bool,
+    json: bool,
+) -> Result<(), Error> {
+    let stats = get_stats(raw_doc, decode_streams, count_operators);
+    if json {
+        let json_string = serde_json::to_string_pretty(&stats)
+            .map_err(|err| Error::Syntax(format!("Could not create JSON: {}", err)))?;
+        writeln!(out, "{}", json_string)?;
+        return Ok(());
+    }
+
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+    let extra_info_style = Style::default().italic();
+
+    writeln!(out, "--- {} ---", Paint::cyan("PDF Stats").bold())?;
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("Objects amount"),
+        value_style.paint(stats.objects_amount)
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("Pages"),
+        match stats.page_count {
+            Some(page_count) => value_style.paint(page_count.to_string()),
+            None => extra_info_style.paint("unknown (page tree could not be loaded)".to_owned()),
+        }
+    )?;
+    writeln!(out, "{}:", label_style.paint("Values by type"))?;
+    let name_width = stats
+        .type_counts
+        .keys()
+        .map(|type_name| type_name.len())
+        .max()
+        .unwrap_or(0);
+    for (type_name, count) in &stats.type_counts {
+        writeln!(
+            out,
+            "  {:<width$} {}",
+            label_style.paint(type_name),
+            value_style.paint(count),
+            width = name_width
+        )?;
+    }
+    writeln!(
+        out,
+        "{}: {}",
+        label_style.paint("Stream bytes"),
+        value_style.paint(stats.stream_bytes)
+    )?;
+    if let Some(decoded_stream_bytes) = stats.decoded_stream_bytes {
+        writeln!(
+            out,
+            "{}: {}",
+            label_style.paint("Decoded stream bytes"),
+            value_style.paint(decoded_stream_bytes)
+        )?;
+        if stats.undecodable_streams > 0 {
+            writeln!(
+                out,
+                "  {}",
+                extra_info_style.paint(format!(
+                    "({} stream(s) could not be decoded and are not included)",
+                    stats.undecodable_streams
+                ))
+            )?;
+        }
+    }
+    if let Some(operator_counts) = &stats.operator_counts {
+        print_operator_counts(out, operator_counts, stats.undecodable_content_streams)?;
+    }
+    Ok(())
+}
+
+/// Print the operators with their count, the most used first.
+fn print_operator_counts(
+    out: &mut dyn Write,
+    operator_counts: &BTreeMap<String, usize>,
+    undecodable_streams: usize,
+) -> std::io::Result<()> {
+    let label_style = Style::default();
+    let value_style = Style::default().bold();
+    let extra_info_style = Style::default().italic();
+
+    writeln!(out, "{}:", label_style.paint("Operators"))?;
+    let mut rows: Vec<(&String, &usize)> = operator_counts.iter().collect();
+    rows.sort_by(|(first, first_count), (second, second_count)| {
+        second_count.cmp(first_count).then(first.cmp(second))
+    });
+    let operator_width = rows
+        .iter()
+        .map(|(operator, _)| operator.len())
+        .max()
+        .unwrap_or(0);
+    let count_width = rows
+        .iter()
+        .map(|(_, count)| count.to_string().len())
+        .max()
+        .unwrap_or(0);
+    for (operator, count) in rows {
+        let description = match get_operator_help(operator) {
+            Some((description, _)) => label_style.paint(description),
+            None => extra_info_style.paint("(unknown operator)"),
+        };
+        writeln!(
+            out,
+            "  {:<operator_width$} {:>count_width$} {}",
+            label_style.paint(operator),
+            value_style.paint(count),
+            description,
+            operator_width = operator_width,
+            count_width = count_width
+        )?;
+    }
+    if operator_counts.is_empty() {
+        writeln!(out, "  {}", extra_info_style.paint("(no operations found)"))?;
+    }
+    if undecodable_streams > 0 {
+        writeln!(
+            out,
+            "  {}",
+            extra_info_style.paint(format!(
+                "({} content stream(s) could not be decoded and are not included)",
+                undecodable_streams
+            ))
+        )?;
+    }
+    Ok(())
+}
+
+fn get_stats(raw_doc: &Document, decode_streams: bool, count_operators: bool) -> Stats {
+    let display_settings = TreeDisplaySettings::default();
+    let mut stats = Stats {
+        objects_amount: raw_doc.objects.len(),
+        page_count: load_page_tree(raw_doc)
+            .ok()
+            .map(|page_tree| page_tree.leaf_pages().len()),
+        decoded_stream_bytes: decode_streams.then_some(0),
+        ..Default::default()
+    };
+    for obj in raw_doc.objects.values() {
+        count_object(&mut stats, &display_settings, obj);
+    }
+    if count_operators {
+        count_operators_in_content_streams(&mut stats, raw_doc);
+    }
+    stats
+}
+
+fn count_operators_in_content_streams(stats: &mut Stats, raw_doc: &Document) {
+    let mut operator_counts = BTreeMap::new();
+    for stream_id in get_content_stream_ids(raw_doc) {
+        let stream = match raw_doc.get_object(stream_id).and_then(Object::as_stream) {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        // lopdf returns an error for streams without filters.
+        let content = if stream.dict.has(b"Filter") {
+            stream.decompressed_content()
+        } else {
+            Ok(stream.content.clone())
+        };
+        let operations = content
+            .and_then(|content| decode_content_stream(&Stream::new(Dictionary::new(), content)));
+        match operations {
+            Ok(operations) => {
+                for operation in operations.operations {
+                    *operator_counts.entry(operation.operator).or_insert(0) += 1;
+                }
+            }
+            Err(_) => stats.undecodable_content_streams += 1,
+        }
+    }
+    stats.operator_counts = Some(operator_counts);
+}
+
+/// Find the streams with content stream operations: the `Contents` of pages,
+/// form XObjects (including appearance streams) and tiling patterns.
+fn get_content_stream_ids(raw_doc: &Document) -> BTreeSet<ObjectId> {
+    let mut stream_ids = BTreeSet::new();
+    for (object_id, obj) in &raw_doc.objects {
+        match obj {
+            Object::Dictionary(dict) if dict.type_is(b"Page") => match dict.get(b"Contents") {
+                Ok(Object::Reference(stream_id)) => {
+                    stream_ids.insert(*stream_id);
+                }
+                Ok(Object::Array(list)) => {
+                    stream_ids.extend(list.iter().filter_map(|item| item.as_reference().ok()));
+                }
+                _ => {}
+            },
+            Object::Stream(stream)
+                if stream
+                    .dict
+                    .get(b"Subtype")
+                    .and_then(Object::as_name)
+                    .is_ok_and(|subtype| subtype == b"Form")
+                    || stream
+                        .dict
+                        .get(b"PatternType")
+                        .and_then(Object::as_i64)
+                        .is_ok_and(|pattern_type| pattern_type == 1) =>
+            {
+                stream_ids.insert(*object_id);
+            }
+            _ => {}
+        }
+    }
+    stream_ids
+}
+
+fn count_object(stats: &mut Stats, display_settings: &TreeDisplaySettings, obj: &Object) {
+    let type_name = get_object_print_info(obj, display_settings).type_name;
+    *stats.type_counts.entry(type_name.to_owned()).or_insert(0) += 1;
+    match obj {
+        Object::Array(list) => {
+            for item in list {
+                count_object(stats, display_settings, item);
+            }
+        }
+        Object::Dictionary(dict) => {
+            for (_, value) in dict.iter() {
+                count_object(stats, display_settings, value);
+            }
+        }
+        Object::Stream(stream) => {
+            for (_, value) in stream.dict.iter() {
+                count_object(stats, display_settings, value);
+            }
+            stats.stream_bytes += stream.content.len();
+            if let Some(decoded_stream_bytes) = &mut stats.decoded_stream_bytes {
+                // lopdf returns an error for streams without filters.
+                if !stream.dict.has(b"Filter") {
+                    *decoded_stream_bytes += stream.content.len();
+                } else if let Ok(content) = stream.decompressed_content() {
+                    *decoded_stream_bytes += content.len();
+                } else {
+                    stats.undecodable_streams += 1;
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,423 @@
+use crate::object_offsets::{
+    find_duplicate_objects, get_object_locations, get_revision_ends, get_revision_index,
+    get_stream_data_length, ObjectLocation,
+};
+use crate::references::get_references;
+use lopdf::{Document, Object, ObjectId};
+use std::io::Write;
+use yansi::{Color, Paint, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The document breaks the spec, readers might not display it correctly.
+    Error,
+    /// Allowed by the spec, but likely a mistake.
+    Warning,
+    /// Not a problem, but good to know, like objects replaced by incremental updates.
+    Note,
+}
+
+/// A problem found in the structure of the document.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Path to the problem, like `Root.Pages.Kids.0`.
+    pub path: String,
+    /// The object the problem is in, `None` for the trailer.
+    pub object_id: Option<ObjectId>,
+    pub message: String,
+}
+
+/// Check the structure of the document and print the problems found.
+///
+/// Checks the `Count` and `Kids` of the page tree, that every page has a `MediaBox`
+/// (directly or inherited), the `Length` of streams, references to missing objects and
+/// object ids that are defined more than once.
+/// Returns `true` when a finding is an error.
+pub fn print_validation(
+    out: &mut dyn Write,
+    raw_doc: &Document,
+    file_bytes: &[u8],
+) -> std::io::Result<bool> {
+    let mut findings = Vec::new();
+    validate_page_tree(raw_doc, &mut findings);
+    validate_stream_lengths(raw_doc, file_bytes, &mut findings);
+    validate_references(raw_doc, &mut findings);
+    validate_duplicate_objects(file_bytes, &mut findings);
+
+    let error_style = Style::new(Color::Red).bold();
+    let warning_style = Style::new(Color::Yellow).bold();
+    let note_style = Style::new(Color::Blue).bold();
+    let extra_info_style = Style::default().italic();
+
+    writeln!(out, "--- {} ---", Paint::cyan("Validation").bold())?;
+    if findings.is_empty() {
+        writeln!(out, "{}", extra_info_style.paint("No problems found."))?;
+        return Ok(false);
+    }
+    for finding in &findings {
+        let severity = match finding.severity {
+            Severity::Error => error_style.paint("ERROR  "),
+            Severity::Warning => warning_style.paint("WARNING"),
+            Severity::Note => note_style.paint("NOTE   "),
+        };
+        let object_id = match finding.object_id {
+            Some(object_id) => format!("({},{})", object_id.0, object_id.1),
+            None => "trailer".to_owned(),
+        };
+        writeln!(
+            out,
+            "{} {} {}: {}",
+            severity,
+            if finding.path.is_empty() {
+                "."
+            } else {
+                &finding.path
+            },
+            extra_info_style.paint(object_id),
+            finding.message
+        )?;
+    }
+    let count_severity = |severity: Severity| {
+        findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    };
+    let error_count = count_severity(Severity::Error);
+    let note_count = count_severity(Severity::Note);
+    let mut summary = format!(
+        "{} error(s), {} warning(s)",
+        error_count,
+        count_severity(Severity::Warning)
+    );
+    if note_count > 0 {
+        summary.push_str(&format!(", {} note(s)", note_count));
+    }
+    writeln!(out, "{}", extra_info_style.paint(summary))?;
+    Ok(error_count > 0)
+}
+
+fn add_finding(
+    findings: &mut Vec<Finding>,
+    severity: Severity,
+    path: &str,
+    object_id: Option<ObjectId>,
+    message: String,
+) {
+    findings.push(Finding {
+        severity,
+        path: path.to_owned(),
+        object_id,
+        message,
+    });
+}
+
+fn validate_page_tree(raw_doc: &Document, findings: &mut Vec<Finding>) {
+    let root_id = raw_doc
+        .trailer
+        .get(b"Root")
+        .and_then(Object::as_reference)
+        .ok();
+    let pages = raw_doc
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Pages"))
+        .and_then(Object::as_reference);
+    match pages {
+        Ok(pages_id) => {
+            let mut visited = Vec::new();
+            validate_page_tree_node(
+                raw_doc,
+                pages_id,
+                "Root.Pages",
+                false,
+                &mut visited,
+                findings,
+            );
+        }
+        Err(_) => add_finding(
+            findings,
+            Severity::Error,
+            "Root",
+            root_id,
+            "catalog has no `Pages` reference".to_owned(),
+        ),
+    }
+}
+
+/// Check a node of the page tree, returns the amount of pages below it.
+fn validate_page_tree_node(
+    raw_doc: &Document,
+    object_id: ObjectId,
+    path: &str,
+    inherits_media_box: bool,
+    visited: &mut Vec<ObjectId>,
+    findings: &mut Vec<Finding>,
+) -> usize {
+    if visited.contains(&object_id) {
+        add_finding(
+            findings,
+            Severity::Error,
+            path,
+            Some(object_id),
+            "page tree contains a loop".to_owned(),
+        );
+        return 0;
+    }
+    visited.push(object_id);
+    let dict = match raw_doc.get_object(object_id) {
+        Ok(Object::Dictionary(dict)) => dict,
+        Ok(_) => {
+            add_finding(
+                findings,
+                Severity::Error,
+                path,
+                Some(object_id),
+                "page tree node is not a dictionary".to_owned(),
+            );
+            return 0;
+        }
+        Err(_) => {
+            add_finding(
+                findings,
+                Severity::Error,
+                path,
+                Some(object_id),
+                "page tree node does not exist".to_owned(),
+            );
+            return 0;
+        }
+    };
+    let has_media_box = inherits_media_box || dict.has(b"MediaBox");
+
+    // Some writers omit `/Type`, so fall back to checking for `/Kids`.
+    let is_pages = dict.type_is(b"Pages") || (!dict.type_is(b"Page") && dict.has(b"Kids"));
+    if !is_pages {
+        if !has_media_box {
+            add_finding(
+                findings,
+                Severity::Error,
+                path,
+                Some(object_id),
+                "page has no `MediaBox` and does not inherit one".to_owned(),
+            );
+        }
+        return 1;
+    }
+
+    let kids = match dict.get_deref(b"Kids", raw_doc) {
+        Ok(Object::Array(kids)) => kids.as_slice(),
+        _ => {
+            add_finding(
+                findings,
+                Severity::Error,
+                path,
+                Some(object_id),
+                "`Pages` node has no `Kids` array".to_owned(),
+            );
+            &[]
+        }
+    };
+    let mut page_count = 0;
+    for (index, kid) in kids.iter().enumerate() {
+        let kid_path = format!("{}.Kids.{}", path, index);
+        match kid {
+            Object::Reference(kid_id) if raw_doc.objects.contains_key(kid_id) => {
+                page_count += validate_page_tree_node(
+                    raw_doc,
+                    *kid_id,
+                    &kid_path,
+                    has_media_box,
+                    visited,
+                    findings,
+                );
+            }
+            Object::Reference(kid_id) => add_finding(
+                findings,
+                Severity::Error,
+                &kid_path,
+                Some(object_id),
+                format!("`Kids` entry ({},{}) does not resolve", kid_id.0, kid_id.1),
+            ),
+            _ => add_finding(
+                findings,
+                Severity::Error,
+                &kid_path,
+                Some(object_id),
+                "`Kids` entry is not an indirect reference".to_owned(),
+            ),
+        }
+    }
+    match dict.get_deref(b"Count", raw_doc).and_then(Object::as_i64) {
+        Ok(count) if count == page_count as i64 => {}
+        Ok(count) => add_finding(
+            findings,
+            Severity::Error,
+            path,
+            Some(object_id),
+            format!("`Count` is {}, but {} pages were found", count, page_count),
+        ),
+        Err(_) => add_finding(
+            findings,
+            Severity::Error,
+            path,
+            Some(object_id),
+            format!("`Count` is missing, {} pages were found", page_count),
+        ),
+    }
+    page_count
+}
+
+/// Compare the `Length` of streams with the data in the file.
+///
+/// lopdf only loads a stream when `endstream` follows after `Length` bytes, otherwise only
+/// the dictionary is kept. So the data of these is measured in the file.
+fn validate_stream_lengths(raw_doc: &Document, file_bytes: &[u8], findings: &mut Vec<Finding>) {
+    for (object_id, location) in get_object_locations(file_bytes, raw_doc) {
+        let offset = match location {
+            ObjectLocation::Offset(offset) => offset,
+            ObjectLocation::InObjectStream { .. } => continue,
+        };
+        let (data_length, data_length_with_end_of_line) =
+            match get_stream_data_length(file_bytes, offset) {
+                Some(data_length) => data_length,
+                None => continue,
+            };
+        let dict = match raw_doc.objects.get(&object_id) {
+            Some(Object::Dictionary(dict)) => dict,
+            _ => continue,
+        };
+        match dict.get_deref(b"Length", raw_doc).and_then(Object::as_i64) {
+            Ok(length)
+                if length == data_length as i64
+                    || length == data_length_with_end_of_line as i64 => {}
+            Ok(length) => add_finding(
+                findings,
+                Severity::Error,
+                "Length",
+                Some(object_id),
+                format!(
+                    "stream `Length` is {}, but the data is {} bytes",
+                    length, data_length
+                ),
+            ),
+            Err(_) => add_finding(
+                findings,
+                Severity::Error,
+                "Length",
+                Some(object_id),
+                format!(
+                    "stream has no valid `Length`, the data is {} bytes",
+                    data_length
+                ),
+            ),
+        }
+    }
+}
+
+/// Report references to objects that do not exist.
+///
+/// These are allowed and read as `null`, but are often caused by a broken writer.
+fn validate_references(raw_doc: &Document, findings: &mut Vec<Finding>) {
+    let trailer = Object::Dictionary(raw_doc.trailer.clone());
+    let objects = std::iter::once((None, &trailer)).chain(
+        raw_doc
+            .objects
+            .iter()
+            .map(|(object_id, obj)| (Some(*object_id), obj)),
+    );
+    for (object_id, obj) in objects {
+        for reference in get_references(obj) {
+            if !raw_doc.objects.contains_key(&reference.object_id) {
+                add_finding(
+                    findings,
+                    Severity::Warning,
+                    &reference.key_path,
+                    object_id,
+                    format!(
+                        "reference to missing object ({},{})",
+                        reference.object_id.0, reference.object_id.1
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Report object ids that are defined more than once in the file.
+///
+/// Within one revision this is an error, as it is unclear which definition is used.
+/// Incremental updates replace objects by defining them again, that is only a note.
+fn validate_duplicate_objects(file_bytes: &[u8], findings: &mut Vec<Finding>) {
+    let revision_ends = get_revision_ends(file_bytes);
+    for (object_id, offsets) in find_duplicate_objects(file_bytes) {
+        let revisions: Vec<usize> = offsets
+            .iter()
+            .map(|offset| get_revision_index(&revision_ends, *offset))
+            .collect();
+        let same_revision = revisions
+            .iter()
+            .enumerate()
+            .any(|(index, revision)| revisions[..index].contains(revision));
+        let definitions = offsets
+            .iter()
+            .zip(&revisions)
+            .map(|(offset, revision)| format!("{} (revision {})", offset, revision + 1))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if same_revision {
+            add_finding(
+                findings,
+                Severity::Error,
+                "",
+                Some(object_id),
+                format!(
+                    "object is defined multiple times in the same revision, at offsets: {}",
+                    definitions
+                ),
+            );
+        } else {
+            add_finding(
+                findings,
+                Severity::Note,
+                "",
+                Some(object_id),
+                format!(
+                    "object is replaced by an incremental update, defined at offsets: {}",
+                    definitions
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_objects_by_revision() {
+        let file_bytes = b"%PDF-1.5\n\
+            1 0 obj\n(first)\nendobj\n\
+            1 0 obj\n(second)\nendobj\n\
+            2 0 obj\n(old)\nendobj\n\
+            %%EOF\n\
+            2 0 obj\n(new)\nendobj\n\
+            %%EOF\n";
+        let mut findings = Vec::new();
+        validate_duplicate_objects(file_bytes, &mut findings);
+        let summary: Vec<(ObjectId, Severity)> = findings
+            .iter()
+            .map(|finding| (finding.object_id.unwrap(), finding.severity))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![((1, 0), Severity::Error), ((2, 0), Severity::Note)]
+        );
+        assert_eq!(
+            findings[0].message,
+            "object is defined multiple times in the same revision, at offsets: \
+            9 (revision 1), 32 (revision 1)"
+        );
+    }
+}